@@ -84,17 +84,9 @@ struct AccessPoint {
 
 impl AccessPoint {
     fn channel(&self) -> u32 {
-        if self.frequency >= 2412 && self.frequency <= 2472 {
-            return (self.frequency - 2407) / 5;
-        }
-        if self.frequency == 2484 {
-            return 14;
-        }
-        if self.frequency >= 5000 && self.frequency < 6000 {
-            return (self.frequency - 5000) / 5;
-        } else {
-            return 0xffffffff;
-        }
+        channel::frequency_to_channel(self.frequency)
+            .map(|(_, channel)| u32::from(channel))
+            .unwrap_or(0xffffffff)
     }
 
     fn num_bars(&self) -> usize {
@@ -260,6 +252,7 @@ fn parse_bss(data: &[u8]) -> Result<AccessPoint, Error> {
                                 _ie.data.len()
                             );
                         }
+                        _ => (),
                     }
                 }
             }
@@ -308,6 +301,7 @@ fn parse_bss(data: &[u8]) -> Result<AccessPoint, Error> {
                             println!("Information Element: {:?}, Len: {}", _ie.ie_id(), _ie.data.len());
                             */
                         }
+                        _ => (),
                     }
                 }
             }
@@ -505,43 +499,11 @@ impl Monitor {
         }
     }
 
-    fn device_id_from_attributes(
-        attributes: &Vec<netlink_rust::Attribute>,
-    ) -> Result<WirelessDeviceId, Error> {
-        let mut interface_index = None;
-        let mut device_identifier = None;
-        for attr in attributes {
-            let id = nl80211::Attribute::from(attr.identifier);
-            match id {
-                nl80211::Attribute::Ifindex => {
-                    interface_index = Some(attr.as_u32()?);
-                }
-                nl80211::Attribute::Wdev => {
-                    device_identifier = Some(attr.as_u64()?);
-                }
-                _ => (),
-            }
-            if interface_index.is_some() && device_identifier.is_some() {
-                break;
-            }
-        }
-        if device_identifier.is_some() {
-            return Ok(WirelessDeviceId::DeviceIdentifier(
-                device_identifier.unwrap(),
-            ));
-        }
-        if interface_index.is_some() {
-            return Ok(WirelessDeviceId::InterfaceIndex(interface_index.unwrap()));
-        }
-        Ok(WirelessDeviceId::None)
-    }
-
     fn handle_event_nl80211_message(&mut self, message: &generic::Message) -> Result<(), Error> {
-        let command = nl80211::Command::from(message.command);
-        let device_id = Self::device_id_from_attributes(&message.attributes)?;
-        match command {
-            nl80211::Command::TriggerScan => (),
-            nl80211::Command::NewScanResults => {
+        let device_id = nl80211::EventOrigin::from_attributes(&message.attributes)?.device_id();
+        match nl80211::Nl80211Event::from_message(message)? {
+            nl80211::Nl80211Event::ScanStarted => (),
+            nl80211::Nl80211Event::ScanDone => {
                 let tx_msg = self
                     .device
                     .prepare_message(nl80211::Command::GetScan, MessageMode::Dump)?;
@@ -552,34 +514,14 @@ impl Monitor {
                     }
                 }
             }
-            nl80211::Command::GetRegulatory => {
+            nl80211::Nl80211Event::Other { command }
+                if command == u8::from(nl80211::Command::GetRegulatory) =>
+            {
                 let info = nl80211::RegulatoryInformation::from_message(&message)?;
                 println!("[{}] Regulatory Domain\n{}", device_id, info);
             }
-            nl80211::Command::RegulatoryChange => {
-                let change = nl80211::RegulatoryChange::from_message(&message)?;
-                println!("[{}] Regulatory Change {}", device_id, change);
-            }
-            nl80211::Command::Connect => {
-                for ref attr in &message.attributes {
-                    let id = nl80211::Attribute::from(attr.identifier);
-                    match id {
-                        nl80211::Attribute::StatusCode => {
-                            let status = attr.as_u16()?;
-                            if status == 0 {
-                                println!("[{}] Connect status: OK", device_id);
-                            } else {
-                                println!("[{}] Connect status: {}", device_id, status);
-                            }
-                        }
-                        _ => (),
-                    }
-                }
-            }
-            nl80211::Command::Disconnect => {
-                println!("[{}] Disconnect", device_id);
-            }
-            _ => {
+            nl80211::Nl80211Event::Other { command } => {
+                let command = nl80211::Command::from(command);
                 println!("[{}] Event Command: {:?}", device_id, command);
                 for ref attr in &message.attributes {
                     let attr_id = nl80211::Attribute::from(attr.identifier);
@@ -655,6 +597,7 @@ impl Monitor {
                     }
                 }
             }
+            event => println!("[{}] {}", device_id, event),
         }
         Ok(())
     }
@@ -799,10 +742,12 @@ fn main() {
                     dev.disconnect(&mut control_socket).unwrap();
                 }
                 UserCommand::GetRegulatory => {
-                    dev.get_regulatory(&mut control_socket).unwrap();
+                    for domain in dev.get_regulatory(&mut control_socket).unwrap() {
+                        println!("{}", domain);
+                    }
                 }
                 UserCommand::SetRegulatory { alpha } => {
-                    dev.set_regulatory(&mut control_socket, &alpha)
+                    dev.set_regulatory(&mut control_socket, &nl80211::RegulatoryHint::User(alpha))
                         .expect("Failed to set regulatory domain");
                 }
                 UserCommand::GetSurvey => {
@@ -812,7 +757,9 @@ fn main() {
                     dev.set_channel(&mut control_socket, channel).unwrap();
                 }
                 UserCommand::GetStation => {
-                    dev.get_station(&mut control_socket).unwrap();
+                    for station in dev.get_station(&mut control_socket).unwrap() {
+                        println!("{}", station);
+                    }
                 }
                 _ => (),
             }
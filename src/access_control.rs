@@ -0,0 +1,167 @@
+//! ## MAC Access Control
+//!
+//! Access control lists (ACL) of station addresses for access point interfaces.
+//!
+
+use std::io;
+
+use crate::attributes::{AclPolicy, Attribute};
+use crate::commands::Command;
+use crate::pack::nested_attribute;
+use crate::wireless_interface::WirelessInterface;
+use crate::wireless_phy::WirelessPhy;
+use netlink_rust as netlink;
+use netlink_rust::{Error, HardwareAddress, MessageMode, Socket};
+
+/// MAC address based access control list
+#[derive(Clone, Debug, PartialEq)]
+pub struct MacAccessControlList {
+    /// Policy for the listed addresses
+    pub policy: AclPolicy,
+    /// Station addresses
+    pub addresses: Vec<HardwareAddress>,
+}
+
+impl MacAccessControlList {
+    /// Accept all stations except the listed ones
+    pub fn deny(addresses: &[HardwareAddress]) -> MacAccessControlList {
+        MacAccessControlList {
+            policy: AclPolicy::AcceptUnlessListed,
+            addresses: addresses.to_vec(),
+        }
+    }
+
+    /// Deny all stations except the listed ones
+    pub fn accept(addresses: &[HardwareAddress]) -> MacAccessControlList {
+        MacAccessControlList {
+            policy: AclPolicy::DenyUnlessListed,
+            addresses: addresses.to_vec(),
+        }
+    }
+
+    /// Add a station address to the list
+    pub fn address(mut self, address: HardwareAddress) -> Self {
+        if !self.addresses.contains(&address) {
+            self.addresses.push(address);
+        }
+        self
+    }
+
+    /// Check that the list fits within the MAC ACL limit of the phy
+    pub fn validate(&self, phy: &WirelessPhy) -> Result<(), Error> {
+        match phy.max_mac_acl_entries() {
+            None | Some(0) => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Phy {} does not support MAC ACL", phy.name()),
+            )
+            .into()),
+            Some(max) if self.addresses.len() > max as usize => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "MAC ACL with {} entries exceeds the limit of {} entries of phy {}",
+                    self.addresses.len(),
+                    max,
+                    phy.name()
+                ),
+            )
+            .into()),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that the phy is the phy of the interface and that the list fits within its MAC
+    /// ACL limit
+    pub(crate) fn validate_for_interface(
+        &self,
+        phy_id: u32,
+        phy: &WirelessPhy,
+    ) -> Result<(), Error> {
+        check_interface_phy(phy_id, phy.identifier())?;
+        self.validate(phy)
+    }
+
+    /// Attributes describing the access control list
+    pub(crate) fn attributes(&self) -> Vec<netlink::Attribute> {
+        let addresses: Vec<netlink::Attribute> = self
+            .addresses
+            .iter()
+            .enumerate()
+            .map(|(i, address)| netlink::Attribute::new(i as u16 + 1, *address))
+            .collect();
+        vec![
+            netlink::Attribute::new(Attribute::AclPolicy, u32::from(self.policy.clone())),
+            nested_attribute(Attribute::MacAddrs, &addresses),
+        ]
+    }
+}
+
+/// Check that the phy the MAC ACL was validated against is the phy of the interface
+pub(crate) fn check_interface_phy(interface_phy_id: u32, phy_id: u32) -> Result<(), Error> {
+    if interface_phy_id != phy_id {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "MAC ACL checked against phy {} but the interface belongs to phy {}",
+                phy_id, interface_phy_id
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+impl WirelessInterface {
+    /// Replace the MAC access control list of a running access point
+    ///
+    /// The list is checked against the MAC ACL limit of the phy, which must be the phy of the
+    /// interface.
+    pub fn set_mac_acl(
+        &self,
+        socket: &mut Socket,
+        phy: &WirelessPhy,
+        acl: &MacAccessControlList,
+    ) -> Result<(), Error> {
+        acl.validate_for_interface(self.phy_id, phy)?;
+        let mut msg =
+            self.prepare_message(Command::SetMacAccessControl, MessageMode::Acknowledge)?;
+        for attribute in acl.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phy(max: Option<u32>) -> WirelessPhy {
+        let mut attributes = vec![netlink::Attribute::new(Attribute::Wiphy, 0u32)];
+        if let Some(max) = max {
+            attributes.push(netlink::Attribute::new(Attribute::MacAclMax, max));
+        }
+        WirelessPhy::from_attributes(&attributes).unwrap()
+    }
+
+    #[test]
+    fn acl_limit() {
+        let first = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        let second = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x02][..]);
+        let acl = MacAccessControlList::accept(&[first])
+            .address(second)
+            .address(first);
+        assert_eq!(acl.addresses.len(), 2);
+        assert!(acl.validate(&phy(Some(2))).is_ok());
+        assert!(acl.validate(&phy(Some(1))).is_err());
+        assert!(acl.validate(&phy(None)).is_err());
+        assert!(acl.validate_for_interface(0, &phy(Some(2))).is_ok());
+        assert!(acl.validate_for_interface(1, &phy(Some(2))).is_err());
+    }
+}
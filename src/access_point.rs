@@ -0,0 +1,809 @@
+//! ## Access Point
+//!
+//! Starting and stopping access point (AP) operation. Beacon and probe response templates can
+//! be assembled from the information element types of this crate.
+//!
+
+use std::io;
+
+use crate::access_control::{check_interface_phy, MacAccessControlList};
+use crate::attributes::{Attribute, AuthenticationType, HiddenSsid};
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::frame::CapabilityInformation;
+use crate::information_element::{
+    AuthenticationKeyManagement, CipherSuite, RobustSecurityNetwork, Ssid, SupportedRates,
+};
+use crate::information_element_ids::InformationElementId;
+use crate::pack::{flag_attribute, hardware_address_bytes, kernel_suite_selector};
+use crate::wireless_interface::WirelessInterface;
+use crate::wireless_phy::WirelessPhy;
+use netlink_rust as netlink;
+use netlink_rust::{Error, HardwareAddress, MessageMode, Socket};
+
+/// Frame control field of a beacon frame
+const FRAME_CONTROL_BEACON: u16 = 0x0080;
+/// Frame control field of a probe response frame
+const FRAME_CONTROL_PROBE_RESPONSE: u16 = 0x0050;
+
+bitflags! {
+    /// WPA versions
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct WpaVersions: u32 {
+        const WPA = 1;
+        const WPA2 = 1 << 1;
+        const WPA3 = 1 << 2;
+    }
+}
+
+/// Pack management frame header and the fixed fields of beacon and probe response frames
+fn pack_beacon_header(
+    frame_control: u16,
+    bssid: HardwareAddress,
+    beacon_interval: u16,
+    capabilities: CapabilityInformation,
+) -> Vec<u8> {
+    let bssid = hardware_address_bytes(bssid);
+    let mut data = frame_control.to_le_bytes().to_vec();
+    // Duration
+    data.extend_from_slice(&[0, 0]);
+    // Destination, the broadcast address
+    data.extend_from_slice(&[0xff; 6]);
+    // Source and BSSID
+    data.extend_from_slice(&bssid);
+    data.extend_from_slice(&bssid);
+    // Sequence control, set by the driver
+    data.extend_from_slice(&[0, 0]);
+    // Timestamp, set by the driver
+    data.extend_from_slice(&[0; 8]);
+    data.extend_from_slice(&beacon_interval.to_le_bytes());
+    data.extend_from_slice(&capabilities.bits().to_le_bytes());
+    data
+}
+
+/// Beacon and probe response template builder
+///
+/// The beacon is split into a head, the part before the traffic indication map (TIM) element,
+/// and a tail. The TIM element is inserted by the driver.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BeaconTemplate {
+    bssid: HardwareAddress,
+    ssid: String,
+    hidden_ssid: HiddenSsid,
+    beacon_interval: u16,
+    capabilities: CapabilityInformation,
+    rates: Option<SupportedRates>,
+    channel: Option<u8>,
+    rsn: Option<Vec<u8>>,
+    crypto: Option<CryptoSettings>,
+    elements: Vec<u8>,
+}
+
+impl BeaconTemplate {
+    /// Create beacon template for an access point with given BSSID and SSID, the SSID can be
+    /// at most 32 octets long
+    pub fn new(bssid: HardwareAddress, ssid: &str) -> Result<BeaconTemplate, Error> {
+        if ssid.is_empty() || ssid.len() > Ssid::MAX_LENGTH {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid SSID length").into());
+        }
+        Ok(BeaconTemplate {
+            bssid,
+            ssid: ssid.to_string(),
+            hidden_ssid: HiddenSsid::NotInUse,
+            beacon_interval: 100,
+            capabilities: CapabilityInformation::ESS,
+            rates: None,
+            channel: None,
+            rsn: None,
+            crypto: None,
+            elements: vec![],
+        })
+    }
+
+    /// Beacon interval in time units (TU), 1024 µs
+    pub fn beacon_interval(mut self, interval: u16) -> Self {
+        self.beacon_interval = interval;
+        self
+    }
+
+    /// Hide the SSID in beacons
+    pub fn hidden_ssid(mut self, hidden_ssid: HiddenSsid) -> Self {
+        self.hidden_ssid = hidden_ssid;
+        self
+    }
+
+    /// Capability information, the privacy flag is set when a RSN element is present
+    pub fn capabilities(mut self, capabilities: CapabilityInformation) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Supported rates, rates beyond the first eight go into an extended supported rates
+    /// element in the tail
+    pub fn supported_rates(mut self, rates: SupportedRates) -> Self {
+        self.rates = Some(rates);
+        self
+    }
+
+    /// Channel number announced in the DS parameter set element
+    pub fn channel(mut self, channel: u8) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Robust security network element, the crypto settings of the access point are derived
+    /// from the element
+    pub fn robust_security_network(mut self, rsn: &RobustSecurityNetwork) -> Self {
+        self.rsn = Some(rsn.pack());
+        self.crypto = Some(CryptoSettings::from_robust_security_network(rsn));
+        self.capabilities |= CapabilityInformation::PRIVACY;
+        self
+    }
+
+    /// Append a packed information element to the tail
+    pub fn element(mut self, element: &[u8]) -> Self {
+        self.elements.extend_from_slice(element);
+        self
+    }
+
+    fn pack_ssid(&self, hidden_ssid: &HiddenSsid) -> Vec<u8> {
+        match *hidden_ssid {
+            HiddenSsid::NotInUse => {
+                let mut data = vec![InformationElementId::Ssid.into(), self.ssid.len() as u8];
+                data.extend_from_slice(self.ssid.as_bytes());
+                data
+            }
+            HiddenSsid::ZeroLength => vec![InformationElementId::Ssid.into(), 0],
+            HiddenSsid::ZeroContents => {
+                let mut data = vec![InformationElementId::Ssid.into(), self.ssid.len() as u8];
+                data.resize(self.ssid.len() + 2, 0);
+                data
+            }
+        }
+    }
+
+    fn pack_parameters(&self) -> Vec<u8> {
+        let mut data = vec![];
+        if let Some(ref rates) = self.rates {
+            data.extend(rates.pack());
+        }
+        if let Some(channel) = self.channel {
+            data.extend_from_slice(&[
+                InformationElementId::DirectSpectrumParameters.into(),
+                1,
+                channel,
+            ]);
+        }
+        data
+    }
+
+    /// Beacon head, the frame up to the TIM element
+    pub fn head(&self) -> Vec<u8> {
+        let mut data = pack_beacon_header(
+            FRAME_CONTROL_BEACON,
+            self.bssid,
+            self.beacon_interval,
+            self.capabilities,
+        );
+        data.extend(self.pack_ssid(&self.hidden_ssid));
+        data.extend(self.pack_parameters());
+        data
+    }
+
+    /// Beacon tail, the information elements after the TIM element
+    pub fn tail(&self) -> Vec<u8> {
+        let mut data = vec![];
+        if let Some(extended) = self.rates.as_ref().and_then(SupportedRates::pack_extended) {
+            data.extend(extended);
+        }
+        if let Some(ref rsn) = self.rsn {
+            data.extend_from_slice(rsn);
+        }
+        data.extend_from_slice(&self.elements);
+        data
+    }
+
+    /// Probe response, always carrying the SSID
+    pub fn probe_response(&self) -> Vec<u8> {
+        let mut data = pack_beacon_header(
+            FRAME_CONTROL_PROBE_RESPONSE,
+            self.bssid,
+            self.beacon_interval,
+            self.capabilities,
+        );
+        data.extend(self.pack_ssid(&HiddenSsid::NotInUse));
+        data.extend(self.pack_parameters());
+        data.extend(self.tail());
+        data
+    }
+
+    /// Beacon data with head, tail and probe response from the template
+    pub fn beacon_data(&self) -> BeaconData {
+        BeaconData::new(&self.head(), &self.tail()).probe_response(&self.probe_response())
+    }
+}
+
+/// Beacon data used when starting an access point or updating the beacon
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BeaconData {
+    head: Vec<u8>,
+    tail: Vec<u8>,
+    probe_response: Option<Vec<u8>>,
+    information_elements: Option<Vec<u8>>,
+    probe_response_information_elements: Option<Vec<u8>>,
+    association_response_information_elements: Option<Vec<u8>>,
+}
+
+impl BeaconData {
+    /// Create beacon data from a packed beacon head and tail
+    pub fn new(head: &[u8], tail: &[u8]) -> BeaconData {
+        BeaconData {
+            head: head.to_vec(),
+            tail: tail.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Probe response template for drivers offloading probe responses
+    pub fn probe_response(mut self, probe_response: &[u8]) -> Self {
+        self.probe_response = Some(probe_response.to_vec());
+        self
+    }
+
+    /// Extra information elements for management frames built by the driver
+    pub fn information_elements(mut self, ies: &[u8]) -> Self {
+        self.information_elements = Some(ies.to_vec());
+        self
+    }
+
+    /// Extra information elements for probe responses built by the driver
+    pub fn probe_response_information_elements(mut self, ies: &[u8]) -> Self {
+        self.probe_response_information_elements = Some(ies.to_vec());
+        self
+    }
+
+    /// Extra information elements for (re)association responses built by the driver
+    pub fn association_response_information_elements(mut self, ies: &[u8]) -> Self {
+        self.association_response_information_elements = Some(ies.to_vec());
+        self
+    }
+
+    /// Beacon head
+    pub fn head(&self) -> &[u8] {
+        &self.head
+    }
+
+    /// Beacon tail
+    pub fn tail(&self) -> &[u8] {
+        &self.tail
+    }
+
+    pub(crate) fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        if !self.head.is_empty() {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::BeaconHead,
+                &self.head,
+            ));
+        }
+        attributes.push(netlink::Attribute::new_bytes(
+            Attribute::BeaconTail,
+            &self.tail,
+        ));
+        if let Some(ref probe_response) = self.probe_response {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::ProbeResp,
+                probe_response,
+            ));
+        }
+        if let Some(ref ies) = self.information_elements {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::InformationElement,
+                ies,
+            ));
+        }
+        if let Some(ref ies) = self.probe_response_information_elements {
+            attributes.push(netlink::Attribute::new_bytes(Attribute::IeProbeResp, ies));
+        }
+        if let Some(ref ies) = self.association_response_information_elements {
+            attributes.push(netlink::Attribute::new_bytes(Attribute::IeAssocResp, ies));
+        }
+        attributes
+    }
+}
+
+/// Cipher and key management settings
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CryptoSettings {
+    wpa_versions: Option<WpaVersions>,
+    pairwise_ciphers: Vec<CipherSuite>,
+    group_cipher: Option<CipherSuite>,
+    akm_suites: Vec<AuthenticationKeyManagement>,
+    control_port: bool,
+    control_port_ethertype: Option<u16>,
+    control_port_no_encrypt: bool,
+}
+
+impl CryptoSettings {
+    pub fn new() -> CryptoSettings {
+        CryptoSettings::default()
+    }
+
+    /// Crypto settings matching a robust security network element
+    ///
+    /// WPA3 is used when SAE is offered, together with WPA2 when other AKMs are offered as
+    /// well, as in WPA3 transition mode.
+    pub fn from_robust_security_network(rsn: &RobustSecurityNetwork) -> CryptoSettings {
+        let is_sae = |akm: &AuthenticationKeyManagement| {
+            *akm == AuthenticationKeyManagement::SimultaneousAuthenticationOfEquals
+                || *akm == AuthenticationKeyManagement::FastTransitionSAE
+        };
+        let mut wpa_versions = WpaVersions::empty();
+        if rsn.akms.iter().any(is_sae) {
+            wpa_versions |= WpaVersions::WPA3;
+        }
+        if rsn.akms.is_empty() || !rsn.akms.iter().all(is_sae) {
+            wpa_versions |= WpaVersions::WPA2;
+        }
+        CryptoSettings::new()
+            .wpa_versions(wpa_versions)
+            .pairwise_ciphers(&rsn.ciphers)
+            .group_cipher(rsn.group_cipher())
+            .akm_suites(&rsn.akms)
+    }
+
+    /// WPA versions to use
+    pub fn wpa_versions(mut self, versions: WpaVersions) -> Self {
+        self.wpa_versions = Some(versions);
+        self
+    }
+
+    /// Pairwise cipher suites
+    pub fn pairwise_ciphers(mut self, ciphers: &[CipherSuite]) -> Self {
+        self.pairwise_ciphers = ciphers.to_vec();
+        self
+    }
+
+    /// Group cipher suite
+    pub fn group_cipher(mut self, cipher: CipherSuite) -> Self {
+        self.group_cipher = Some(cipher);
+        self
+    }
+
+    /// Authentication key management suites
+    pub fn akm_suites(mut self, akms: &[AuthenticationKeyManagement]) -> Self {
+        self.akm_suites = akms.to_vec();
+        self
+    }
+
+    /// Block data traffic on the control port until the port is authorized
+    pub fn control_port(mut self, enabled: bool) -> Self {
+        self.control_port = enabled;
+        self
+    }
+
+    /// Ethertype of the control port frames, EAPOL (0x888e) is used if not set
+    pub fn control_port_ethertype(mut self, ethertype: u16) -> Self {
+        self.control_port_ethertype = Some(ethertype);
+        self
+    }
+
+    /// Do not encrypt the control port frames
+    pub fn control_port_no_encrypt(mut self, no_encrypt: bool) -> Self {
+        self.control_port_no_encrypt = no_encrypt;
+        self
+    }
+
+    pub(crate) fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        if let Some(versions) = self.wpa_versions {
+            attributes.push(netlink::Attribute::new(
+                Attribute::WpaVersions,
+                versions.bits(),
+            ));
+        }
+        if !self.pairwise_ciphers.is_empty() {
+            let ciphers: Vec<u8> = self
+                .pairwise_ciphers
+                .iter()
+                .flat_map(|c| {
+                    kernel_suite_selector(u32::from(c.clone()))
+                        .to_ne_bytes()
+                        .to_vec()
+                })
+                .collect();
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::CipherSuitesPairwise,
+                &ciphers,
+            ));
+        }
+        if let Some(ref cipher) = self.group_cipher {
+            attributes.push(netlink::Attribute::new(
+                Attribute::CipherSuiteGroup,
+                kernel_suite_selector(u32::from(cipher.clone())),
+            ));
+        }
+        if !self.akm_suites.is_empty() {
+            let akms: Vec<u8> = self
+                .akm_suites
+                .iter()
+                .flat_map(|a| {
+                    kernel_suite_selector(u32::from(a.clone()))
+                        .to_ne_bytes()
+                        .to_vec()
+                })
+                .collect();
+            attributes.push(netlink::Attribute::new_bytes(Attribute::AkmSuites, &akms));
+        }
+        if self.control_port {
+            attributes.push(flag_attribute(Attribute::ControlPort));
+        }
+        if let Some(ethertype) = self.control_port_ethertype {
+            attributes.push(netlink::Attribute::new(
+                Attribute::ControlPortEthertype,
+                ethertype,
+            ));
+        }
+        if self.control_port_no_encrypt {
+            attributes.push(flag_attribute(Attribute::ControlPortNoEncrypt));
+        }
+        attributes
+    }
+}
+
+/// Request to start operating as access point
+#[derive(Clone, Debug, PartialEq)]
+pub struct StartApRequest {
+    beacon: BeaconData,
+    beacon_interval: u32,
+    dtim_period: u32,
+    ssid: Vec<u8>,
+    hidden_ssid: HiddenSsid,
+    channel: Option<ChannelDefinition>,
+    authentication_type: Option<AuthenticationType>,
+    privacy: bool,
+    crypto: Option<CryptoSettings>,
+    inactivity_timeout: Option<u16>,
+    access_control_list: Option<MacAccessControlList>,
+    access_control_phy: Option<u32>,
+}
+
+impl StartApRequest {
+    /// Create request with beacon data and SSID, a beacon interval of 100 TU and a DTIM period
+    /// of 2
+    pub fn new(beacon: BeaconData, ssid: &[u8]) -> StartApRequest {
+        StartApRequest {
+            beacon,
+            beacon_interval: 100,
+            dtim_period: 2,
+            ssid: ssid.to_vec(),
+            hidden_ssid: HiddenSsid::NotInUse,
+            channel: None,
+            authentication_type: None,
+            privacy: false,
+            crypto: None,
+            inactivity_timeout: None,
+            access_control_list: None,
+            access_control_phy: None,
+        }
+    }
+
+    /// Create request from a beacon template, taking SSID, beacon interval, hidden SSID mode
+    /// and crypto settings from the template
+    pub fn from_template(template: &BeaconTemplate) -> StartApRequest {
+        let mut request = StartApRequest::new(template.beacon_data(), template.ssid.as_bytes())
+            .beacon_interval(u32::from(template.beacon_interval))
+            .hidden_ssid(template.hidden_ssid.clone());
+        if let Some(ref crypto) = template.crypto {
+            request = request.privacy(true).crypto(crypto.clone());
+        }
+        request
+    }
+
+    /// Beacon interval in time units (TU), 1024 µs
+    pub fn beacon_interval(mut self, interval: u32) -> Self {
+        self.beacon_interval = interval;
+        self
+    }
+
+    /// Delivery traffic indication message (DTIM) period in beacon intervals
+    pub fn dtim_period(mut self, period: u32) -> Self {
+        self.dtim_period = period;
+        self
+    }
+
+    /// Hide the SSID in beacons, the beacon head must match the mode
+    pub fn hidden_ssid(mut self, hidden_ssid: HiddenSsid) -> Self {
+        self.hidden_ssid = hidden_ssid;
+        self
+    }
+
+    /// Operating channel, the current channel of the interface is used if not set
+    pub fn channel(mut self, channel: ChannelDefinition) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Authentication type accepted by the access point
+    pub fn authentication_type(mut self, authentication_type: AuthenticationType) -> Self {
+        self.authentication_type = Some(authentication_type);
+        self
+    }
+
+    /// Require encryption of data frames
+    pub fn privacy(mut self, privacy: bool) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Cipher and key management settings
+    pub fn crypto(mut self, crypto: CryptoSettings) -> Self {
+        self.crypto = Some(crypto);
+        self
+    }
+
+    /// Time in seconds before inactive stations are disconnected
+    pub fn inactivity_timeout(mut self, timeout: u16) -> Self {
+        self.inactivity_timeout = Some(timeout);
+        self
+    }
+
+    /// MAC address access control list, checked against the MAC ACL limit of the phy
+    ///
+    /// The access point must be started on an interface of the phy.
+    pub fn access_control_list(
+        mut self,
+        phy: &WirelessPhy,
+        acl: MacAccessControlList,
+    ) -> Result<Self, Error> {
+        acl.validate(phy)?;
+        self.access_control_list = Some(acl);
+        self.access_control_phy = Some(phy.identifier());
+        Ok(self)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.beacon.head.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Missing beacon head").into());
+        }
+        if self.ssid.is_empty() || self.ssid.len() > 32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid SSID length").into());
+        }
+        if self.beacon_interval == 0 {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidInput, "Invalid beacon interval").into(),
+            );
+        }
+        if self.dtim_period == 0 || self.dtim_period > 255 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid DTIM period").into());
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = self.beacon.attributes();
+        attributes.push(netlink::Attribute::new(
+            Attribute::BeaconInterval,
+            self.beacon_interval,
+        ));
+        attributes.push(netlink::Attribute::new(
+            Attribute::DtimPeriod,
+            self.dtim_period,
+        ));
+        attributes.push(netlink::Attribute::new_bytes(Attribute::Ssid, &self.ssid));
+        attributes.push(netlink::Attribute::new(
+            Attribute::HiddenSsid,
+            u32::from(self.hidden_ssid.clone()),
+        ));
+        if let Some(ref channel) = self.channel {
+            attributes.extend(channel.attributes());
+        }
+        if let Some(ref authentication_type) = self.authentication_type {
+            attributes.push(netlink::Attribute::new(
+                Attribute::AuthType,
+                u32::from(authentication_type.clone()),
+            ));
+        }
+        if self.privacy {
+            attributes.push(flag_attribute(Attribute::Privacy));
+        }
+        if let Some(ref crypto) = self.crypto {
+            attributes.extend(crypto.attributes());
+        }
+        if let Some(timeout) = self.inactivity_timeout {
+            attributes.push(netlink::Attribute::new(
+                Attribute::InactivityTimeout,
+                timeout,
+            ));
+        }
+        if let Some(ref acl) = self.access_control_list {
+            attributes.extend(acl.attributes());
+        }
+        attributes
+    }
+}
+
+impl WirelessInterface {
+    /// Start operating as access point
+    ///
+    /// A MAC access control list of the request must have been checked against the phy of the
+    /// interface.
+    pub fn start_ap(&self, socket: &mut Socket, request: &StartApRequest) -> Result<(), Error> {
+        request.validate()?;
+        if let Some(phy_id) = request.access_control_phy {
+            check_interface_phy(self.phy_id, phy_id)?;
+        }
+        let mut msg = self.prepare_message(Command::StartAp, MessageMode::Acknowledge)?;
+        for attribute in request.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Update the beacon of a running access point
+    pub fn set_beacon(&self, socket: &mut Socket, beacon: &BeaconData) -> Result<(), Error> {
+        let mut msg = self.prepare_message(Command::SetBeacon, MessageMode::Acknowledge)?;
+        for attribute in beacon.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop operating as access point
+    pub fn stop_ap(&self, socket: &mut Socket) -> Result<(), Error> {
+        let msg = self.prepare_message(Command::StopAp, MessageMode::Acknowledge)?;
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::information_element::{InformationElements, RsnCapabilities};
+    use netlink_rust::ConvertFrom;
+
+    fn template() -> BeaconTemplate {
+        let bssid = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        BeaconTemplate::new(bssid, "test")
+            .unwrap()
+            .supported_rates(SupportedRates::new(&[2, 4, 11, 22], &[2, 4]))
+            .channel(6)
+    }
+
+    #[test]
+    fn beacon_head_and_tail() {
+        let template = template().hidden_ssid(HiddenSsid::ZeroContents);
+        let head = template.head();
+        assert_eq!(&head[..2], &[0x80, 0x00]);
+        assert_eq!(&head[4..10], &[0xff; 6]);
+        assert_eq!(&head[32..34], &[100, 0]);
+        assert_eq!(&head[34..36], &[0x01, 0x00]);
+        let ies = InformationElements::parse(&head[36..]);
+        assert_eq!(ies.elements.len(), 3);
+        assert_eq!(ies.elements[0].data, &[0, 0, 0, 0]);
+        assert_eq!(ies.elements[2].data, &[6]);
+        assert!(template.tail().is_empty());
+        let bssid = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        assert!(BeaconTemplate::new(bssid, &"a".repeat(32)).is_ok());
+        assert!(BeaconTemplate::new(bssid, &"a".repeat(33)).is_err());
+        assert!(BeaconTemplate::new(bssid, "").is_err());
+        let ssid = Ssid {
+            ssid: "a".repeat(32),
+        };
+        assert_eq!(ssid.pack().unwrap().len(), 34);
+        let ssid = Ssid {
+            ssid: "a".repeat(300),
+        };
+        assert!(ssid.pack().is_err());
+        let probe_response = template.probe_response();
+        assert_eq!(&probe_response[..2], &[0x50, 0x00]);
+        assert_eq!(&probe_response[36..42], &[0, 4, b't', b'e', b's', b't']);
+    }
+
+    #[test]
+    fn start_ap_from_template() {
+        let rsn = RobustSecurityNetwork::new(
+            CipherSuite::CounterModeCbcMacProtocol,
+            &[CipherSuite::CounterModeCbcMacProtocol],
+            &[AuthenticationKeyManagement::PreSharedKey],
+            RsnCapabilities::empty(),
+        );
+        let template = template().robust_security_network(&rsn);
+        assert_eq!(&template.head()[34..36], &[0x11, 0x00]);
+        assert_eq!(template.tail(), rsn.pack());
+        let request = StartApRequest::from_template(&template).dtim_period(1);
+        assert!(request.validate().is_ok());
+        let attributes = request.attributes();
+        let group = attributes
+            .iter()
+            .find(|a| Attribute::convert_from(a.identifier) == Some(Attribute::CipherSuiteGroup))
+            .unwrap();
+        assert_eq!(group.as_u32().unwrap(), 0x000f_ac04);
+        let akms = attributes
+            .iter()
+            .find(|a| Attribute::convert_from(a.identifier) == Some(Attribute::AkmSuites))
+            .unwrap();
+        assert_eq!(akms.as_bytes(), 0x000f_ac02u32.to_ne_bytes().to_vec());
+        assert!(attributes
+            .iter()
+            .any(|a| Attribute::convert_from(a.identifier) == Some(Attribute::Privacy)));
+        let request = StartApRequest::new(BeaconData::new(&[], &[]), b"test");
+        assert!(request.validate().is_err());
+    }
+
+    #[test]
+    fn crypto_settings_wpa_versions() {
+        let versions = |akms: &[AuthenticationKeyManagement]| {
+            let rsn = RobustSecurityNetwork::new(
+                CipherSuite::CounterModeCbcMacProtocol,
+                &[CipherSuite::CounterModeCbcMacProtocol],
+                akms,
+                RsnCapabilities::empty(),
+            );
+            CryptoSettings::from_robust_security_network(&rsn).wpa_versions
+        };
+        assert_eq!(
+            versions(&[AuthenticationKeyManagement::PreSharedKey]),
+            Some(WpaVersions::WPA2)
+        );
+        assert_eq!(
+            versions(&[AuthenticationKeyManagement::SimultaneousAuthenticationOfEquals]),
+            Some(WpaVersions::WPA3)
+        );
+        assert_eq!(
+            versions(&[
+                AuthenticationKeyManagement::PreSharedKey,
+                AuthenticationKeyManagement::SimultaneousAuthenticationOfEquals
+            ]),
+            Some(WpaVersions::WPA2 | WpaVersions::WPA3)
+        );
+    }
+
+    #[test]
+    fn start_ap_access_control_list() {
+        let phy = |max: u32| {
+            WirelessPhy::from_attributes(&[
+                netlink::Attribute::new(Attribute::Wiphy, 1u32),
+                netlink::Attribute::new(Attribute::MacAclMax, max),
+            ])
+            .unwrap()
+        };
+        let station = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        let acl = MacAccessControlList::deny(&[station])
+            .address(HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x02][..]));
+        let request = StartApRequest::from_template(&template());
+        assert!(request
+            .clone()
+            .access_control_list(&phy(1), acl.clone())
+            .is_err());
+        let request = request.access_control_list(&phy(2), acl).unwrap();
+        assert_eq!(request.access_control_phy, Some(1));
+        assert!(request
+            .attributes()
+            .iter()
+            .any(|a| Attribute::convert_from(a.identifier) == Some(Attribute::MacAddrs)));
+        assert!(check_interface_phy(1, 1).is_ok());
+        assert!(check_interface_phy(0, 1).is_err());
+    }
+}
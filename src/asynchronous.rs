@@ -0,0 +1,320 @@
+//! ## Asynchronous API
+//!
+//! Non-blocking nl80211 socket for the tokio runtime, with futures for request and response
+//! exchanges and a stream of typed events. Available with the `tokio` feature.
+//!
+//! Requests are prepared as for the blocking API, for example with
+//! `WirelessInterface::prepare_message`, and exchanged with `AsyncSocket::request`.
+//!
+
+use std::collections::VecDeque;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_core::Stream;
+use tokio::io::unix::AsyncFd;
+
+use crate::commands::Command;
+use crate::event::{events_from_messages, subscribe, Event, EventFilter, MulticastGroup};
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{Error, MessageMode, Protocol, Socket};
+
+fn set_nonblocking(fd: RawFd) -> Result<(), Error> {
+    let flags = unsafe { libc::fcntl(fd, libc::F_GETFL) };
+    if flags < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    if unsafe { libc::fcntl(fd, libc::F_SETFL, flags | libc::O_NONBLOCK) } < 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+fn would_block(error: &Error) -> bool {
+    match *error {
+        Error::Io(ref e) => e.kind() == io::ErrorKind::WouldBlock,
+        _ => false,
+    }
+}
+
+/// Poll for the next queued item, reading from the socket when the queue is empty
+///
+/// The read is retried until it produces items or fails with an error other than would block.
+/// On would block the readiness is cleared, so the task is woken once the socket becomes
+/// readable again.
+fn poll_queued<T, I, F>(
+    socket: &mut AsyncFd<T>,
+    queue: &mut VecDeque<Result<I, Error>>,
+    cx: &mut Context<'_>,
+    mut read: F,
+) -> Poll<Option<Result<I, Error>>>
+where
+    T: AsRawFd,
+    F: FnMut(&mut T) -> Result<Vec<Result<I, Error>>, Error>,
+{
+    loop {
+        if let Some(item) = queue.pop_front() {
+            return Poll::Ready(Some(item));
+        }
+        let mut guard = match socket.poll_read_ready_mut(cx) {
+            Poll::Ready(Ok(guard)) => guard,
+            Poll::Ready(Err(error)) => return Poll::Ready(Some(Err(error.into()))),
+            Poll::Pending => return Poll::Pending,
+        };
+        match read(guard.get_inner_mut()) {
+            Ok(items) => queue.extend(items),
+            Err(ref error) if would_block(error) => guard.clear_ready(),
+            Err(error) => return Poll::Ready(Some(Err(error))),
+        }
+    }
+}
+
+/// Non-blocking generic netlink socket driven by the tokio runtime
+///
+/// Must be created from within a tokio runtime.
+pub struct AsyncSocket {
+    socket: AsyncFd<Socket>,
+}
+
+impl AsyncSocket {
+    /// Create a non-blocking generic netlink socket
+    pub fn new() -> Result<AsyncSocket, Error> {
+        AsyncSocket::from_socket(Socket::new(Protocol::Generic)?)
+    }
+
+    /// Make the socket non-blocking and register it with the tokio runtime
+    pub fn from_socket(socket: Socket) -> Result<AsyncSocket, Error> {
+        set_nonblocking(socket.as_raw_fd())?;
+        Ok(AsyncSocket {
+            socket: AsyncFd::new(socket)?,
+        })
+    }
+
+    /// Send the message, waits until the socket is writable
+    pub async fn send_message(&mut self, message: &generic::Message) -> Result<(), Error> {
+        loop {
+            let mut guard = self.socket.writable_mut().await?;
+            match guard.get_inner_mut().send_message(message) {
+                Err(ref error) if would_block(error) => guard.clear_ready(),
+                result => return result.map(|_| ()),
+            }
+        }
+    }
+
+    /// Receive messages, waits until messages are available
+    ///
+    /// An empty set of messages is returned when a request has been acknowledged or a dump
+    /// is done.
+    ///
+    /// Each read returns the messages of a single datagram. A multipart dump spans several
+    /// datagrams, when the next datagram has not yet arrived the read would block and the
+    /// future waits for the socket to become readable again. No part of the dump is lost.
+    pub async fn receive_messages(&mut self) -> Result<Vec<netlink::Message>, Error> {
+        loop {
+            let mut guard = self.socket.readable_mut().await?;
+            match guard.get_inner_mut().receive_messages() {
+                Err(ref error) if would_block(error) => guard.clear_ready(),
+                result => return result,
+            }
+        }
+    }
+
+    /// Send the request and collect the replies until the request is acknowledged or the dump
+    /// is done
+    pub async fn request(
+        &mut self,
+        message: &generic::Message,
+    ) -> Result<Vec<generic::Message>, Error> {
+        self.send_message(message).await?;
+        let mut replies = vec![];
+        loop {
+            let messages = self.receive_messages().await?;
+            if messages.is_empty() {
+                break;
+            }
+            for m in messages {
+                if m.header.identifier == message.family {
+                    let (_, msg) = generic::Message::unpack(&m.data)?;
+                    replies.push(msg);
+                }
+            }
+        }
+        Ok(replies)
+    }
+
+    /// Get the wireless interfaces of the nl80211 family
+    pub async fn get_wireless_interfaces(
+        &mut self,
+        family: &generic::Family,
+    ) -> Result<Vec<WirelessInterface>, Error> {
+        let msg = generic::Message::new(family.id, Command::GetInterface, MessageMode::Dump);
+        let replies = self.request(&msg).await?;
+        Ok(replies
+            .into_iter()
+            .filter_map(|m| WirelessInterface::from_message(m, family.clone()).ok())
+            .collect())
+    }
+}
+
+impl AsRawFd for AsyncSocket {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+/// Stream of nl80211 events of the subscribed multicast groups
+///
+/// Must be created from within a tokio runtime.
+pub struct EventStream {
+    socket: AsyncFd<Socket>,
+    family_id: u16,
+    filter: EventFilter,
+    events: VecDeque<Result<Event, Error>>,
+}
+
+impl EventStream {
+    /// Create stream subscribed to the multicast groups of the nl80211 family
+    pub fn new(family: &generic::Family, groups: &[MulticastGroup]) -> Result<EventStream, Error> {
+        let mut socket = Socket::new(Protocol::Generic)?;
+        subscribe(&mut socket, family, groups)?;
+        set_nonblocking(socket.as_raw_fd())?;
+        Ok(EventStream {
+            socket: AsyncFd::new(socket)?,
+            family_id: family.id,
+            filter: EventFilter::new(),
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Only deliver events with an origin matching the filter
+    pub fn filter(mut self, filter: EventFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl Stream for EventStream {
+    type Item = Result<Event, Error>;
+
+    /// Poll for the next event
+    ///
+    /// The non-blocking socket is read with the blocking `Socket::receive_messages`, which
+    /// returns the messages of one datagram per read. All events decoded from a datagram are
+    /// queued and delivered before the socket is read again. Events are single part messages.
+    /// If a read would block partway through a multipart message, the remaining parts stay in
+    /// the socket and are read as separate datagrams once it becomes readable again.
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let family_id = this.family_id;
+        let filter = &this.filter;
+        poll_queued(&mut this.socket, &mut this.events, cx, |socket| {
+            let messages = socket.receive_messages()?;
+            Ok(events_from_messages(family_id, filter, messages))
+        })
+    }
+}
+
+impl AsRawFd for EventStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::poll_fn;
+    use std::io::{Read, Write};
+    use std::os::unix::net::UnixStream;
+
+    type Queue = VecDeque<Result<u8, Error>>;
+
+    fn read_octets(stream: &mut UnixStream) -> Result<Vec<Result<u8, Error>>, Error> {
+        let mut buffer = [0u8; 2];
+        let length = stream.read(&mut buffer)?;
+        Ok(buffer[..length].iter().map(|b| Ok(*b)).collect())
+    }
+
+    async fn poll_once(
+        socket: &mut AsyncFd<UnixStream>,
+        queue: &mut Queue,
+        reads: &mut usize,
+    ) -> Poll<Option<Result<u8, Error>>> {
+        poll_fn(|cx| {
+            Poll::Ready(poll_queued(socket, queue, cx, |stream| {
+                *reads += 1;
+                read_octets(stream)
+            }))
+        })
+        .await
+    }
+
+    async fn next(
+        socket: &mut AsyncFd<UnixStream>,
+        queue: &mut Queue,
+        reads: &mut usize,
+    ) -> Option<Result<u8, Error>> {
+        poll_fn(|cx| {
+            poll_queued(socket, queue, cx, |stream| {
+                *reads += 1;
+                read_octets(stream)
+            })
+        })
+        .await
+    }
+
+    #[test]
+    fn poll_queued_would_block() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let (reader, mut writer) = UnixStream::pair().unwrap();
+            reader.set_nonblocking(true).unwrap();
+            let mut socket = AsyncFd::new(reader).unwrap();
+            let mut queue = Queue::new();
+            let mut reads = 0;
+
+            writer.write_all(&[1, 2, 3]).unwrap();
+            let item = next(&mut socket, &mut queue, &mut reads).await;
+            assert_eq!(item.unwrap().unwrap(), 1);
+            assert_eq!(reads, 1);
+            // Queued items are delivered before the socket is read again
+            let item = next(&mut socket, &mut queue, &mut reads).await;
+            assert_eq!(item.unwrap().unwrap(), 2);
+            assert_eq!(reads, 1);
+            let item = next(&mut socket, &mut queue, &mut reads).await;
+            assert_eq!(item.unwrap().unwrap(), 3);
+            assert_eq!(reads, 2);
+
+            // Would block clears the readiness, the next poll does not read
+            let item = poll_once(&mut socket, &mut queue, &mut reads).await;
+            assert!(item.is_pending());
+            assert_eq!(reads, 3);
+            let item = poll_once(&mut socket, &mut queue, &mut reads).await;
+            assert!(item.is_pending());
+            assert_eq!(reads, 3);
+
+            // Readable again once more data arrives
+            writer.write_all(&[4]).unwrap();
+            let item = next(&mut socket, &mut queue, &mut reads).await;
+            assert_eq!(item.unwrap().unwrap(), 4);
+            assert_eq!(reads, 4);
+            assert!(queue.is_empty());
+
+            writer.write_all(&[5]).unwrap();
+            let item = poll_fn(|cx| {
+                poll_queued(&mut socket, &mut queue, cx, |_| {
+                    Err::<Vec<Result<u8, Error>>, Error>(Error::Kernel(-22))
+                })
+            })
+            .await;
+            assert!(item.unwrap().is_err());
+        });
+    }
+}
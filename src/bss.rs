@@ -0,0 +1,352 @@
+//! ## BSS Parameters
+//!
+//! Changing the BSS parameters of a running access point, and BSS color changes announced with
+//! BSS color change announcement elements in beacons and probe responses.
+//!
+
+use std::io;
+
+use crate::access_point::{BeaconData, BeaconTemplate};
+use crate::attributes::Attribute;
+use crate::commands::Command;
+use crate::pack::nested_attribute;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::{Error, MessageMode, Socket};
+
+/// BSS parameters of a running access point, only the given parameters are changed
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BssParameters {
+    cts_protection: Option<bool>,
+    short_preamble: Option<bool>,
+    short_slot_time: Option<bool>,
+    basic_rates: Option<Vec<u8>>,
+    ap_isolate: Option<bool>,
+    ht_opmode: Option<u16>,
+    p2p_ctwindow: Option<u8>,
+    p2p_opportunistic_power_save: Option<bool>,
+    link_id: Option<u8>,
+}
+
+impl BssParameters {
+    pub fn new() -> BssParameters {
+        BssParameters::default()
+    }
+
+    /// Use CTS protection
+    pub fn cts_protection(mut self, enabled: bool) -> Self {
+        self.cts_protection = Some(enabled);
+        self
+    }
+
+    /// Allow short preamble
+    pub fn short_preamble(mut self, enabled: bool) -> Self {
+        self.short_preamble = Some(enabled);
+        self
+    }
+
+    /// Use short slot time
+    pub fn short_slot_time(mut self, enabled: bool) -> Self {
+        self.short_slot_time = Some(enabled);
+        self
+    }
+
+    /// Basic rates in units of 500 kbit/s
+    pub fn basic_rates(mut self, rates: &[u8]) -> Self {
+        self.basic_rates = Some(rates.to_vec());
+        self
+    }
+
+    /// Do not forward frames between associated stations
+    pub fn ap_isolate(mut self, enabled: bool) -> Self {
+        self.ap_isolate = Some(enabled);
+        self
+    }
+
+    /// High throughput (HT) operation mode field of the HT operation element
+    pub fn ht_opmode(mut self, opmode: u16) -> Self {
+        self.ht_opmode = Some(opmode);
+        self
+    }
+
+    /// P2P group owner client traffic window in time units
+    pub fn p2p_ctwindow(mut self, ctwindow: u8) -> Self {
+        self.p2p_ctwindow = Some(ctwindow);
+        self
+    }
+
+    /// P2P group owner opportunistic power save
+    pub fn p2p_opportunistic_power_save(mut self, enabled: bool) -> Self {
+        self.p2p_opportunistic_power_save = Some(enabled);
+        self
+    }
+
+    /// Link of a multi-link access point to change
+    pub fn link_id(mut self, link_id: u8) -> Self {
+        self.link_id = Some(link_id);
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(ref rates) = self.basic_rates {
+            if rates.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "No basic rates").into());
+            }
+        }
+        if let Some(ctwindow) = self.p2p_ctwindow {
+            if ctwindow > 127 {
+                return Err(
+                    io::Error::new(io::ErrorKind::InvalidInput, "Invalid P2P CT window").into(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        let flags = [
+            (Attribute::BssCtsProt, self.cts_protection),
+            (Attribute::BssShortPreamble, self.short_preamble),
+            (Attribute::BssShortSlotTime, self.short_slot_time),
+            (Attribute::ApIsolate, self.ap_isolate),
+            (Attribute::P2pOppps, self.p2p_opportunistic_power_save),
+        ];
+        for (id, value) in flags.iter() {
+            if let Some(value) = value {
+                attributes.push(netlink::Attribute::new(id.clone(), *value as u8));
+            }
+        }
+        if let Some(ref rates) = self.basic_rates {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::BssBasicRates,
+                rates,
+            ));
+        }
+        if let Some(opmode) = self.ht_opmode {
+            attributes.push(netlink::Attribute::new(Attribute::BssHtOpmode, opmode));
+        }
+        if let Some(ctwindow) = self.p2p_ctwindow {
+            attributes.push(netlink::Attribute::new(Attribute::P2pCtwindow, ctwindow));
+        }
+        if let Some(link_id) = self.link_id {
+            attributes.push(netlink::Attribute::new(Attribute::MloLinkId, link_id));
+        }
+        attributes
+    }
+}
+
+/// Request to change the BSS color of an access point
+#[derive(Clone, Debug, PartialEq)]
+pub struct BssColorChangeRequest {
+    color: u8,
+    count: u8,
+    beacon_after: BeaconData,
+    beacon_change: BeaconData,
+    counter_offsets_beacon: Vec<u16>,
+    counter_offsets_probe_response: Vec<u16>,
+    link_id: Option<u8>,
+}
+
+impl BssColorChangeRequest {
+    /// Offset of the color switch countdown in the BSS color change announcement element
+    const COUNTDOWN_OFFSET: usize = 3;
+
+    /// Create BSS color change request
+    ///
+    /// The beacon used during the change carries the color change announcement, its countdown
+    /// is updated by the driver at the counter offsets. The beacon after the change is used once
+    /// the change is completed.
+    pub fn new(
+        color: u8,
+        count: u8,
+        beacon_change: BeaconData,
+        beacon_after: BeaconData,
+    ) -> BssColorChangeRequest {
+        BssColorChangeRequest {
+            color,
+            count,
+            beacon_after,
+            beacon_change,
+            counter_offsets_beacon: vec![],
+            counter_offsets_probe_response: vec![],
+            link_id: None,
+        }
+    }
+
+    /// Create BSS color change request from beacon templates
+    ///
+    /// A BSS color change announcement element is appended to the current beacon template and
+    /// the counter offsets are computed.
+    pub fn from_templates(
+        color: u8,
+        count: u8,
+        current: &BeaconTemplate,
+        after: &BeaconTemplate,
+    ) -> BssColorChangeRequest {
+        // Element extension with the color switch countdown and the new BSS color information
+        let element = [255, 3, 42, count, color & 0x3f];
+        let offset = BssColorChangeRequest::COUNTDOWN_OFFSET;
+        BssColorChangeRequest {
+            color,
+            count,
+            beacon_after: after.beacon_data(),
+            beacon_change: current.clone().element(&element).beacon_data(),
+            counter_offsets_beacon: vec![(current.tail().len() + offset) as u16],
+            counter_offsets_probe_response: vec![(current.probe_response().len() + offset) as u16],
+            link_id: None,
+        }
+    }
+
+    /// Offsets of the countdowns in the tail of the beacon used during the change
+    pub fn counter_offsets_beacon(mut self, offsets: &[u16]) -> Self {
+        self.counter_offsets_beacon = offsets.to_vec();
+        self
+    }
+
+    /// Offsets of the countdowns in the probe response used during the change
+    pub fn counter_offsets_probe_response(mut self, offsets: &[u16]) -> Self {
+        self.counter_offsets_probe_response = offsets.to_vec();
+        self
+    }
+
+    /// Link of a multi-link access point to change
+    pub fn link_id(mut self, link_id: u8) -> Self {
+        self.link_id = Some(link_id);
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.color == 0 || self.color > 63 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid BSS color").into());
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        fn pack_offsets(offsets: &[u16]) -> Vec<u8> {
+            offsets
+                .iter()
+                .flat_map(|o| o.to_ne_bytes().to_vec())
+                .collect()
+        }
+        let mut attributes = vec![
+            netlink::Attribute::new(Attribute::ColorChangeCount, self.count),
+            netlink::Attribute::new(Attribute::ColorChangeColor, self.color),
+        ];
+        attributes.extend(self.beacon_after.attributes());
+        // The counter offsets are part of the beacon used during the change
+        let mut change_attributes = self.beacon_change.attributes();
+        if !self.counter_offsets_beacon.is_empty() {
+            change_attributes.push(netlink::Attribute::new_bytes(
+                Attribute::CsaCOffBeacon,
+                &pack_offsets(&self.counter_offsets_beacon),
+            ));
+        }
+        if !self.counter_offsets_probe_response.is_empty() {
+            change_attributes.push(netlink::Attribute::new_bytes(
+                Attribute::CsaCOffPresp,
+                &pack_offsets(&self.counter_offsets_probe_response),
+            ));
+        }
+        attributes.push(nested_attribute(
+            Attribute::ColorChangeElems,
+            &change_attributes,
+        ));
+        if let Some(link_id) = self.link_id {
+            attributes.push(netlink::Attribute::new(Attribute::MloLinkId, link_id));
+        }
+        attributes
+    }
+}
+
+impl WirelessInterface {
+    fn bss_request(
+        &self,
+        socket: &mut Socket,
+        command: Command,
+        attributes: Vec<netlink::Attribute>,
+    ) -> Result<(), Error> {
+        let mut msg = self.prepare_message(command, MessageMode::Acknowledge)?;
+        for attribute in attributes {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Change the BSS parameters of the access point
+    pub fn set_bss(&self, socket: &mut Socket, parameters: &BssParameters) -> Result<(), Error> {
+        parameters.validate()?;
+        self.bss_request(socket, Command::SetBss, parameters.attributes())
+    }
+
+    /// Change the BSS color of the access point
+    pub fn color_change(
+        &self,
+        socket: &mut Socket,
+        request: &BssColorChangeRequest,
+    ) -> Result<(), Error> {
+        request.validate()?;
+        self.bss_request(socket, Command::ColorChangeRequest, request.attributes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use netlink_rust::{ConvertFrom, HardwareAddress};
+
+    #[test]
+    fn bss_parameters() {
+        let parameters = BssParameters::new()
+            .short_slot_time(true)
+            .ht_opmode(0x0004)
+            .link_id(1);
+        assert!(parameters.validate().is_ok());
+        let attributes = parameters.attributes();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(
+            Attribute::convert_from(attributes[0].identifier),
+            Some(Attribute::BssShortSlotTime)
+        );
+        assert_eq!(attributes[0].as_u8().unwrap(), 1);
+        assert!(BssParameters::new().basic_rates(&[]).validate().is_err());
+        assert!(BssParameters::new().p2p_ctwindow(128).validate().is_err());
+    }
+
+    #[test]
+    fn color_change() {
+        let bssid = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        let current = BeaconTemplate::new(bssid, "test").unwrap().channel(36);
+        let request = BssColorChangeRequest::from_templates(12, 10, &current, &current);
+        assert!(request.validate().is_ok());
+        let tail = request.beacon_change.tail();
+        assert_eq!(tail[request.counter_offsets_beacon[0] as usize], 10);
+        let attributes = request.attributes();
+        assert!(!attributes
+            .iter()
+            .any(|a| { Attribute::convert_from(a.identifier) == Some(Attribute::CsaCOffBeacon) }));
+        let elements = attributes
+            .iter()
+            .find(|a| Attribute::convert_from(a.identifier) == Some(Attribute::ColorChangeElems))
+            .unwrap();
+        let (_, nested) = netlink::Attribute::unpack_all(&elements.as_bytes());
+        let offset = nested
+            .iter()
+            .find(|a| Attribute::convert_from(a.identifier) == Some(Attribute::CsaCOffBeacon))
+            .unwrap();
+        assert_eq!(offset.as_u16().unwrap(), request.counter_offsets_beacon[0]);
+        assert!(
+            BssColorChangeRequest::from_templates(64, 10, &current, &current)
+                .validate()
+                .is_err()
+        );
+    }
+}
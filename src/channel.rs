@@ -0,0 +1,389 @@
+//! ## Channels
+//!
+//! Conversion between frequencies, channel numbers and bands for the 900 MHz sub-1 GHz (S1G),
+//! 2.4 GHz, 4.9 GHz and 5 GHz, 6 GHz and 60 GHz directional multi-gigabit (DMG) bands.
+//!
+//! Operating classes are the global operating classes, IEEE 802.11 Annex E table E-4, as used
+//! in the extended channel switch announcement and supported operating classes elements.
+//!
+
+use crate::attributes::ChannelWidth;
+use crate::channel_definition::ChannelDefinition;
+
+/// Frequency band
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Band {
+    /// 900 MHz sub-1 GHz band
+    BandS1GHz,
+    /// 2.4 GHz band
+    Band2GHz,
+    /// 5 GHz band, including the 4.9 GHz channels
+    Band5GHz,
+    /// 6 GHz band
+    Band6GHz,
+    /// 60 GHz band
+    Band60GHz,
+}
+
+impl Band {
+    /// Band of the frequency in kHz
+    pub fn from_frequency_khz(frequency: u32) -> Option<Band> {
+        match frequency {
+            902_000..=928_000 => Some(Band::BandS1GHz),
+            2_400_000..=2_500_000 => Some(Band::Band2GHz),
+            4_900_000..=5_924_999 => Some(Band::Band5GHz),
+            5_925_000..=7_125_000 => Some(Band::Band6GHz),
+            57_240_000..=71_280_000 => Some(Band::Band60GHz),
+            _ => None,
+        }
+    }
+
+    /// Band of the frequency in MHz
+    pub fn from_frequency(frequency: u32) -> Option<Band> {
+        Band::from_frequency_khz(frequency.checked_mul(1000)?)
+    }
+}
+
+/// Frequency in kHz of the channel in the band
+pub fn channel_to_frequency_khz(band: Band, channel: u8) -> Option<u32> {
+    let channel = u32::from(channel);
+    let frequency = match band {
+        Band::BandS1GHz if (1..=51).contains(&channel) => 902_000 + channel * 500,
+        Band::Band2GHz if channel == 14 => 2_484_000,
+        Band::Band2GHz if (1..=13).contains(&channel) => (2407 + channel * 5) * 1000,
+        // 4.9 GHz channels, channels 178 to 181 are 5.9 GHz channels
+        Band::Band5GHz if (182..=196).contains(&channel) => (4000 + channel * 5) * 1000,
+        Band::Band5GHz if (1..=181).contains(&channel) => (5000 + channel * 5) * 1000,
+        Band::Band6GHz if channel == 2 => 5_935_000,
+        Band::Band6GHz if (1..=233).contains(&channel) && channel % 4 == 1 => {
+            (5950 + channel * 5) * 1000
+        }
+        Band::Band60GHz if (1..=6).contains(&channel) => (56160 + channel * 2160) * 1000,
+        _ => return None,
+    };
+    Some(frequency)
+}
+
+/// Frequency in MHz of the channel in the band, none for channels not on a whole MHz
+pub fn channel_to_frequency(band: Band, channel: u8) -> Option<u32> {
+    let frequency = channel_to_frequency_khz(band, channel)?;
+    if frequency % 1000 == 0 {
+        Some(frequency / 1000)
+    } else {
+        None
+    }
+}
+
+/// Band and channel of the frequency in kHz
+pub fn frequency_khz_to_channel(frequency: u32) -> Option<(Band, u8)> {
+    let band = Band::from_frequency_khz(frequency)?;
+    let channel = match band {
+        Band::BandS1GHz => (frequency - 902_000) / 500,
+        Band::Band2GHz if frequency == 2_484_000 => 14,
+        Band::Band2GHz => (frequency / 1000).checked_sub(2407)? / 5,
+        Band::Band5GHz if frequency < 5_000_000 => (frequency / 1000 - 4000) / 5,
+        Band::Band5GHz => (frequency / 1000 - 5000) / 5,
+        Band::Band6GHz if frequency == 5_935_000 => 2,
+        Band::Band6GHz => (frequency / 1000).checked_sub(5950)? / 5,
+        Band::Band60GHz => (frequency / 1000 - 56160) / 2160,
+    };
+    if channel > u32::from(u8::MAX) {
+        return None;
+    }
+    let channel = channel as u8;
+    // Only frequencies at the center of a channel are accepted. 5 GHz frequencies are checked
+    // directly, as channels 182 to 196 do not convert back to the 5.9 GHz frequencies.
+    let center = match band {
+        Band::Band5GHz if frequency >= 5_000_000 => Some((5000 + u32::from(channel) * 5) * 1000),
+        _ => channel_to_frequency_khz(band, channel),
+    };
+    if center == Some(frequency) {
+        Some((band, channel))
+    } else {
+        None
+    }
+}
+
+/// Band and channel of the frequency in MHz
+pub fn frequency_to_channel(frequency: u32) -> Option<(Band, u8)> {
+    frequency_khz_to_channel(frequency.checked_mul(1000)?)
+}
+
+/// Position of the secondary 20 MHz channel of 40 MHz operating classes
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SecondaryChannel {
+    /// The secondary channel is above the primary channel
+    Above,
+    /// The secondary channel is below the primary channel
+    Below,
+}
+
+/// Global operating class
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OperatingClass {
+    /// Operating class
+    pub class: u8,
+    /// Band of the operating class
+    pub band: Band,
+    /// Channel width in MHz
+    pub bandwidth: u32,
+    /// Position of the secondary channel of 40 MHz classes listing primary channels
+    pub secondary: Option<SecondaryChannel>,
+    /// The channel set lists channel center frequency indexes instead of primary channels
+    pub center_frequency_indexes: bool,
+    /// Channel set
+    pub channels: &'static [u8],
+}
+
+macro_rules! operating_class {
+    ($class:expr, $band:ident, $bandwidth:expr, $secondary:expr, $center:expr, $channels:expr) => {
+        OperatingClass {
+            class: $class,
+            band: Band::$band,
+            bandwidth: $bandwidth,
+            secondary: $secondary,
+            center_frequency_indexes: $center,
+            channels: $channels,
+        }
+    };
+}
+
+const ABOVE: Option<SecondaryChannel> = Some(SecondaryChannel::Above);
+const BELOW: Option<SecondaryChannel> = Some(SecondaryChannel::Below);
+
+const CHANNELS_6GHZ_20MHZ: &[u8] = &[
+    1, 5, 9, 13, 17, 21, 25, 29, 33, 37, 41, 45, 49, 53, 57, 61, 65, 69, 73, 77, 81, 85, 89, 93,
+    97, 101, 105, 109, 113, 117, 121, 125, 129, 133, 137, 141, 145, 149, 153, 157, 161, 165, 169,
+    173, 177, 181, 185, 189, 193, 197, 201, 205, 209, 213, 217, 221, 225, 229, 233,
+];
+const CHANNELS_6GHZ_40MHZ: &[u8] = &[
+    3, 11, 19, 27, 35, 43, 51, 59, 67, 75, 83, 91, 99, 107, 115, 123, 131, 139, 147, 155, 163, 171,
+    179, 187, 195, 203, 211, 219, 227,
+];
+const CHANNELS_6GHZ_80MHZ: &[u8] = &[
+    7, 23, 39, 55, 71, 87, 103, 119, 135, 151, 167, 183, 199, 215,
+];
+const CHANNELS_5GHZ_80MHZ: &[u8] = &[42, 58, 106, 122, 138, 155, 171];
+
+/// Global operating classes, IEEE 802.11 Annex E table E-4
+pub const OPERATING_CLASSES: &[OperatingClass] = &[
+    operating_class!(
+        81,
+        Band2GHz,
+        20,
+        None,
+        false,
+        &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13]
+    ),
+    operating_class!(82, Band2GHz, 20, None, false, &[14]),
+    operating_class!(83, Band2GHz, 40, ABOVE, false, &[1, 2, 3, 4, 5, 6, 7, 8, 9]),
+    operating_class!(
+        84,
+        Band2GHz,
+        40,
+        BELOW,
+        false,
+        &[5, 6, 7, 8, 9, 10, 11, 12, 13]
+    ),
+    operating_class!(115, Band5GHz, 20, None, false, &[36, 40, 44, 48]),
+    operating_class!(116, Band5GHz, 40, ABOVE, false, &[36, 44]),
+    operating_class!(117, Band5GHz, 40, BELOW, false, &[40, 48]),
+    operating_class!(118, Band5GHz, 20, None, false, &[52, 56, 60, 64]),
+    operating_class!(119, Band5GHz, 40, ABOVE, false, &[52, 60]),
+    operating_class!(120, Band5GHz, 40, BELOW, false, &[56, 64]),
+    operating_class!(
+        121,
+        Band5GHz,
+        20,
+        None,
+        false,
+        &[100, 104, 108, 112, 116, 120, 124, 128, 132, 136, 140, 144]
+    ),
+    operating_class!(
+        122,
+        Band5GHz,
+        40,
+        ABOVE,
+        false,
+        &[100, 108, 116, 124, 132, 140]
+    ),
+    operating_class!(
+        123,
+        Band5GHz,
+        40,
+        BELOW,
+        false,
+        &[104, 112, 120, 128, 136, 144]
+    ),
+    operating_class!(124, Band5GHz, 20, None, false, &[149, 153, 157, 161]),
+    operating_class!(
+        125,
+        Band5GHz,
+        20,
+        None,
+        false,
+        &[149, 153, 157, 161, 165, 169, 173, 177]
+    ),
+    operating_class!(126, Band5GHz, 40, ABOVE, false, &[149, 157, 165, 173]),
+    operating_class!(127, Band5GHz, 40, BELOW, false, &[153, 161, 169, 177]),
+    operating_class!(128, Band5GHz, 80, None, true, CHANNELS_5GHZ_80MHZ),
+    operating_class!(129, Band5GHz, 160, None, true, &[50, 114, 163]),
+    operating_class!(130, Band5GHz, 80, None, true, CHANNELS_5GHZ_80MHZ),
+    operating_class!(131, Band6GHz, 20, None, false, CHANNELS_6GHZ_20MHZ),
+    operating_class!(132, Band6GHz, 40, None, true, CHANNELS_6GHZ_40MHZ),
+    operating_class!(133, Band6GHz, 80, None, true, CHANNELS_6GHZ_80MHZ),
+    operating_class!(
+        134,
+        Band6GHz,
+        160,
+        None,
+        true,
+        &[15, 47, 79, 111, 143, 175, 207]
+    ),
+    operating_class!(135, Band6GHz, 80, None, true, CHANNELS_6GHZ_80MHZ),
+    operating_class!(136, Band6GHz, 20, None, false, &[2]),
+    operating_class!(137, Band6GHz, 320, None, true, &[31, 63, 95, 127, 159, 191]),
+    operating_class!(180, Band60GHz, 2160, None, false, &[1, 2, 3, 4, 5, 6]),
+];
+
+/// Global operating class
+pub fn operating_class(class: u8) -> Option<&'static OperatingClass> {
+    OPERATING_CLASSES.iter().find(|c| c.class == class)
+}
+
+impl OperatingClass {
+    /// The operating class uses two frequency segments, 80+80 MHz
+    pub fn is_80p80(&self) -> bool {
+        self.class == 130 || self.class == 135
+    }
+
+    // Center frequency index of the channel block containing the primary channel
+    fn center_index(&self, channel: u8) -> Option<u8> {
+        let offset = (self.bandwidth / 10) as u8;
+        self.channels.iter().cloned().find(|&center| {
+            let first = center.wrapping_sub(offset - 2);
+            (first..center + offset).step_by(4).any(|c| c == channel)
+        })
+    }
+
+    /// The primary channel belongs to the operating class
+    pub fn contains(&self, channel: u8) -> bool {
+        if self.center_frequency_indexes {
+            self.center_index(channel).is_some()
+                && channel_to_frequency(self.band, channel).is_some()
+        } else {
+            self.channels.contains(&channel)
+        }
+    }
+
+    /// Frequency in MHz of the primary channel
+    pub fn frequency(&self, channel: u8) -> Option<u32> {
+        if !self.contains(channel) {
+            return None;
+        }
+        channel_to_frequency(self.band, channel)
+    }
+
+    /// Channel definition of the primary channel
+    ///
+    /// None for 80+80 MHz classes, since the second segment is not given by the operating
+    /// class, and for classes without a matching channel width.
+    pub fn channel_definition(&self, channel: u8) -> Option<ChannelDefinition> {
+        let frequency = self.frequency(channel)?;
+        if self.is_80p80() {
+            return None;
+        }
+        let definition = match (self.bandwidth, self.secondary) {
+            (20, _) => ChannelDefinition::with_width(frequency, ChannelWidth::Width20, frequency),
+            (40, Some(SecondaryChannel::Above)) => {
+                ChannelDefinition::with_width(frequency, ChannelWidth::Width40, frequency + 10)
+            }
+            (40, Some(SecondaryChannel::Below)) => {
+                ChannelDefinition::with_width(frequency, ChannelWidth::Width40, frequency - 10)
+            }
+            (width, None) => {
+                let width = match width {
+                    40 => ChannelWidth::Width40,
+                    80 => ChannelWidth::Width80,
+                    160 => ChannelWidth::Width160,
+                    320 => ChannelWidth::Width320,
+                    _ => return None,
+                };
+                // Center frequency indexes are not necessarily 20 MHz channels
+                let index = u32::from(self.center_index(channel)?);
+                let center = match self.band {
+                    Band::Band5GHz => 5000 + index * 5,
+                    Band::Band6GHz => 5950 + index * 5,
+                    _ => return None,
+                };
+                ChannelDefinition::with_width(frequency, width, center)
+            }
+            _ => return None,
+        };
+        Some(definition)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_frequency() {
+        assert_eq!(channel_to_frequency(Band::Band2GHz, 1), Some(2412));
+        assert_eq!(channel_to_frequency(Band::Band2GHz, 14), Some(2484));
+        assert_eq!(channel_to_frequency(Band::Band5GHz, 36), Some(5180));
+        assert_eq!(channel_to_frequency(Band::Band5GHz, 184), Some(4920));
+        assert_eq!(channel_to_frequency(Band::Band6GHz, 1), Some(5955));
+        assert_eq!(channel_to_frequency(Band::Band6GHz, 2), Some(5935));
+        assert_eq!(channel_to_frequency(Band::Band6GHz, 3), None);
+        assert_eq!(channel_to_frequency(Band::Band60GHz, 2), Some(60480));
+        assert_eq!(channel_to_frequency(Band::BandS1GHz, 1), None);
+        assert_eq!(channel_to_frequency_khz(Band::BandS1GHz, 1), Some(902_500));
+        assert_eq!(frequency_to_channel(2437), Some((Band::Band2GHz, 6)));
+        assert_eq!(frequency_to_channel(2484), Some((Band::Band2GHz, 14)));
+        assert_eq!(frequency_to_channel(5745), Some((Band::Band5GHz, 149)));
+        assert_eq!(frequency_to_channel(4940), Some((Band::Band5GHz, 188)));
+        assert_eq!(frequency_to_channel(5900), Some((Band::Band5GHz, 180)));
+        assert_eq!(frequency_to_channel(5920), Some((Band::Band5GHz, 184)));
+        assert_eq!(channel_to_frequency(Band::Band5GHz, 180), Some(5900));
+        for channel in 178..=181 {
+            let frequency = channel_to_frequency(Band::Band5GHz, channel).unwrap();
+            assert_eq!(
+                frequency_to_channel(frequency),
+                Some((Band::Band5GHz, channel))
+            );
+        }
+        assert_eq!(frequency_to_channel(6115), Some((Band::Band6GHz, 33)));
+        assert_eq!(frequency_to_channel(58320), Some((Band::Band60GHz, 1)));
+        assert_eq!(
+            frequency_khz_to_channel(904_000),
+            Some((Band::BandS1GHz, 4))
+        );
+        assert_eq!(frequency_to_channel(2413), None);
+        assert_eq!(frequency_to_channel(3000), None);
+    }
+
+    #[test]
+    fn operating_classes() {
+        let class = operating_class(116).unwrap();
+        assert_eq!(class.frequency(36), Some(5180));
+        assert_eq!(class.frequency(40), None);
+        let channel = class.channel_definition(36).unwrap();
+        assert_eq!(channel.width, ChannelWidth::Width40);
+        assert_eq!(channel.center_frequency1, Some(5190));
+        let class = operating_class(128).unwrap();
+        let channel = class.channel_definition(44).unwrap();
+        assert_eq!(channel.width, ChannelWidth::Width80);
+        assert_eq!(channel.center_frequency1, Some(5210));
+        assert!(operating_class(130)
+            .unwrap()
+            .channel_definition(44)
+            .is_none());
+        let class = operating_class(134).unwrap();
+        let channel = class.channel_definition(37).unwrap();
+        assert_eq!(channel.frequency, 6135);
+        assert_eq!(channel.center_frequency1, Some(6185));
+        assert!(operating_class(1).is_none());
+    }
+}
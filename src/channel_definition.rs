@@ -0,0 +1,548 @@
+//! ## Channel Definition
+//!
+//! Operating channel of an interface, the control frequency together with the channel width
+//! and center frequencies.
+//!
+//! A channel definition can be checked for consistency with `ChannelDefinition::validate`,
+//! against the channels of a wiphy with `WirelessPhy::check_channel` and against the rules of
+//! a regulatory domain with `RegulatoryInformation::check_channel`.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::{Attribute, ChannelWidth, FrequencyAttribute};
+use crate::regulatory::WmmRule;
+use netlink_rust as netlink;
+use netlink_rust::{ConvertFrom, Error};
+
+fn invalid_channel(message: &str) -> Error {
+    io::Error::new(io::ErrorKind::InvalidInput, message).into()
+}
+
+/// Channel definition
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelDefinition {
+    /// Control channel frequency in MHz
+    pub frequency: u32,
+    /// Channel width
+    pub width: ChannelWidth,
+    /// Center frequency of the first segment in MHz
+    pub center_frequency1: Option<u32>,
+    /// Center frequency of the second segment in MHz, only used for 80+80 MHz channels
+    pub center_frequency2: Option<u32>,
+    /// Bitmap of punctured 20 MHz subchannels, bit 0 being the lowest subchannel
+    pub punctured: u32,
+    /// Offset in kHz of the control frequency, used by sub-1 GHz channels
+    pub frequency_offset: u32,
+}
+
+impl ChannelDefinition {
+    /// Create 20 MHz channel definition without high throughput (HT)
+    pub fn new(frequency: u32) -> ChannelDefinition {
+        ChannelDefinition {
+            frequency,
+            width: ChannelWidth::Width20NoHt,
+            center_frequency1: None,
+            center_frequency2: None,
+            punctured: 0,
+            frequency_offset: 0,
+        }
+    }
+
+    /// Create channel definition with the given width and center frequency
+    pub fn with_width(
+        frequency: u32,
+        width: ChannelWidth,
+        center_frequency1: u32,
+    ) -> ChannelDefinition {
+        ChannelDefinition {
+            frequency,
+            width,
+            center_frequency1: Some(center_frequency1),
+            center_frequency2: None,
+            punctured: 0,
+            frequency_offset: 0,
+        }
+    }
+
+    /// Set the center frequency of the second segment for 80+80 MHz channels
+    pub fn center_frequency2(mut self, frequency: u32) -> Self {
+        self.center_frequency2 = Some(frequency);
+        self
+    }
+
+    /// Set the bitmap of punctured 20 MHz subchannels
+    pub fn punctured(mut self, bitmap: u32) -> Self {
+        self.punctured = bitmap;
+        self
+    }
+
+    /// Set the offset in kHz of the control frequency
+    pub fn frequency_offset(mut self, offset: u32) -> Self {
+        self.frequency_offset = offset;
+        self
+    }
+
+    /// Decode channel definition from the attributes of a message
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<ChannelDefinition, Error> {
+        let mut frequency = None;
+        let mut width = ChannelWidth::Width20NoHt;
+        let mut center_frequency1 = None;
+        let mut center_frequency2 = None;
+        let mut punctured = 0;
+        let mut frequency_offset = 0;
+        for attr in attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::WiphyFreq) => {
+                    frequency = Some(attr.as_u32()?);
+                }
+                Some(Attribute::ChannelWidth) => {
+                    width = ChannelWidth::convert_from(attr.as_u32()?).ok_or_else(|| {
+                        io::Error::new(io::ErrorKind::InvalidData, "Unknown channel width")
+                    })?;
+                }
+                Some(Attribute::CenterFreq1) => {
+                    center_frequency1 = Some(attr.as_u32()?);
+                }
+                Some(Attribute::CenterFreq2) => {
+                    center_frequency2 = Some(attr.as_u32()?);
+                }
+                Some(Attribute::PunctBitmap) => {
+                    punctured = attr.as_u32()?;
+                }
+                Some(Attribute::WiphyFreqOffset) => {
+                    frequency_offset = attr.as_u32()?;
+                }
+                _ => (),
+            }
+        }
+        let frequency = frequency
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing frequency"))?;
+        Ok(ChannelDefinition {
+            frequency,
+            width,
+            center_frequency1,
+            center_frequency2,
+            punctured,
+            frequency_offset,
+        })
+    }
+
+    /// Channel width in MHz
+    pub fn bandwidth(&self) -> u32 {
+        match self.width {
+            ChannelWidth::Width20NoHt | ChannelWidth::Width20 => 20,
+            ChannelWidth::Width40 => 40,
+            ChannelWidth::Width80 | ChannelWidth::Width80P80 => 80,
+            ChannelWidth::Width160 => 160,
+            ChannelWidth::Width320 => 320,
+            ChannelWidth::Width5 => 5,
+            ChannelWidth::Width10 => 10,
+            ChannelWidth::Width1 => 1,
+            ChannelWidth::Width2 => 2,
+            ChannelWidth::Width4 => 4,
+            ChannelWidth::Width8 => 8,
+            ChannelWidth::Width16 => 16,
+        }
+    }
+
+    // The channel is made of 20 MHz subchannels
+    fn has_subchannels(&self) -> bool {
+        self.bandwidth() >= 20
+    }
+
+    /// Frequency ranges in MHz covered by the channel, two ranges for 80+80 MHz channels
+    ///
+    /// Empty if a center frequency is too low for the channel width.
+    pub fn segments(&self) -> Vec<(u32, u32)> {
+        let width = self.bandwidth();
+        let segment = |center: u32| {
+            center
+                .checked_sub(width / 2)
+                .map(|start| (start, center + width / 2))
+        };
+        let mut centers = vec![self.center_frequency1.unwrap_or(self.frequency)];
+        if self.width == ChannelWidth::Width80P80 {
+            centers.extend(self.center_frequency2);
+        }
+        centers
+            .into_iter()
+            .map(segment)
+            .collect::<Option<Vec<_>>>()
+            .unwrap_or_default()
+    }
+
+    /// Attributes describing the channel definition
+    pub fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![
+            netlink::Attribute::new(Attribute::WiphyFreq, self.frequency),
+            netlink::Attribute::new(Attribute::ChannelWidth, u32::from(self.width.clone())),
+        ];
+        if let Some(frequency) = self.center_frequency1 {
+            attributes.push(netlink::Attribute::new(Attribute::CenterFreq1, frequency));
+        }
+        if let Some(frequency) = self.center_frequency2 {
+            attributes.push(netlink::Attribute::new(Attribute::CenterFreq2, frequency));
+        }
+        if self.frequency_offset != 0 {
+            attributes.push(netlink::Attribute::new(
+                Attribute::WiphyFreqOffset,
+                self.frequency_offset,
+            ));
+        }
+        if self.punctured != 0 {
+            attributes.push(netlink::Attribute::new(
+                Attribute::PunctBitmap,
+                self.punctured,
+            ));
+        }
+        attributes
+    }
+
+    /// Center frequencies in MHz of the 20 MHz subchannels in use, lowest first
+    ///
+    /// Punctured subchannels are left out. Channels narrower than 20 MHz have the control
+    /// channel as only subchannel.
+    pub fn subchannels(&self) -> Vec<u32> {
+        if !self.has_subchannels() {
+            return vec![self.frequency];
+        }
+        self.segments()
+            .iter()
+            .flat_map(|&(start, end)| (start + 10..end).step_by(20))
+            .enumerate()
+            .filter(|&(n, _)| n >= 32 || self.punctured & (1 << n) == 0)
+            .map(|(_, frequency)| frequency)
+            .collect()
+    }
+
+    /// Check the consistency of the control frequency, channel width, center frequencies and
+    /// punctured subchannels
+    pub fn validate(&self) -> Result<(), Error> {
+        let width = self.bandwidth();
+        if self.frequency_offset >= 1000 {
+            return Err(invalid_channel("Frequency offset out of range"));
+        }
+        match self.width {
+            ChannelWidth::Width20NoHt
+            | ChannelWidth::Width20
+            | ChannelWidth::Width5
+            | ChannelWidth::Width10 => {
+                if self.center_frequency1.unwrap_or(self.frequency) != self.frequency {
+                    return Err(invalid_channel("Center frequency differs from frequency"));
+                }
+            }
+            ChannelWidth::Width1
+            | ChannelWidth::Width2
+            | ChannelWidth::Width4
+            | ChannelWidth::Width8
+            | ChannelWidth::Width16 => (),
+            _ => {
+                let center = self
+                    .center_frequency1
+                    .ok_or_else(|| invalid_channel("Missing center frequency"))?;
+                let first = center
+                    .checked_sub(width / 2 - 10)
+                    .ok_or_else(|| invalid_channel("Invalid center frequency"))?;
+                if !(first..center + width / 2)
+                    .step_by(20)
+                    .any(|f| f == self.frequency)
+                {
+                    return Err(invalid_channel("Control channel outside of the channel"));
+                }
+            }
+        }
+        match (
+            self.width == ChannelWidth::Width80P80,
+            self.center_frequency2,
+        ) {
+            (true, Some(center)) => {
+                let center1 = self.center_frequency1.unwrap_or(self.frequency);
+                if center.max(center1) - center.min(center1) <= 80 {
+                    return Err(invalid_channel("Overlapping 80+80 MHz segments"));
+                }
+            }
+            (true, None) => return Err(invalid_channel("Missing second center frequency")),
+            (false, Some(_)) => return Err(invalid_channel("Unexpected second center frequency")),
+            (false, None) => (),
+        }
+        if self.segments().is_empty() {
+            return Err(invalid_channel("Channel extends below zero frequency"));
+        }
+        if self.punctured != 0 {
+            if width < 80 || !self.has_subchannels() {
+                return Err(invalid_channel("Puncturing requires 80 MHz or wider"));
+            }
+            let count = width / 20 * self.segments().len() as u32;
+            if count < 32 && self.punctured >> count != 0 {
+                return Err(invalid_channel(
+                    "Punctured subchannel outside of the channel",
+                ));
+            }
+            if !self.subchannels().contains(&self.frequency) {
+                return Err(invalid_channel("Control channel is punctured"));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check the channel against the channels of a wiphy band
+    ///
+    /// The control channel and all subchannels in use shall be enabled and allow the channel
+    /// width.
+    pub(crate) fn check_channels(&self, channels: &[PhyChannel]) -> Result<(), Error> {
+        self.validate()?;
+        let control = channels
+            .iter()
+            .find(|c| c.frequency == self.frequency && c.frequency_offset == self.frequency_offset)
+            .ok_or_else(|| invalid_channel("Control channel not supported"))?;
+        let center = self.center_frequency1.unwrap_or(self.frequency);
+        let prohibited = match self.width {
+            ChannelWidth::Width20NoHt | ChannelWidth::Width20 => ChannelFlags::NO_20MHZ,
+            ChannelWidth::Width10 => ChannelFlags::NO_10MHZ,
+            ChannelWidth::Width40 if center > self.frequency => ChannelFlags::NO_HT40_PLUS,
+            ChannelWidth::Width40 => ChannelFlags::NO_HT40_MINUS,
+            ChannelWidth::Width80 | ChannelWidth::Width80P80 => ChannelFlags::NO_80MHZ,
+            ChannelWidth::Width160 => ChannelFlags::NO_160MHZ,
+            ChannelWidth::Width320 => ChannelFlags::NO_320MHZ,
+            _ => ChannelFlags::empty(),
+        };
+        if control.flags.intersects(prohibited) {
+            return Err(invalid_channel("Channel width not allowed"));
+        }
+        for frequency in self.subchannels() {
+            let channel = channels
+                .iter()
+                .find(|c| c.frequency == frequency)
+                .ok_or_else(|| invalid_channel("Subchannel not supported"))?;
+            if channel.flags.contains(ChannelFlags::DISABLED) {
+                return Err(invalid_channel("Subchannel disabled"));
+            }
+            if self.width != ChannelWidth::Width40 && channel.flags.intersects(prohibited) {
+                return Err(invalid_channel("Channel width not allowed"));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ChannelDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} MHz {:?}", self.frequency, self.width)?;
+        if let Some(frequency) = self.center_frequency1 {
+            write!(f, " Center {} MHz", frequency)?;
+        }
+        if let Some(frequency) = self.center_frequency2 {
+            write!(f, " Center2 {} MHz", frequency)?;
+        }
+        if self.frequency_offset != 0 {
+            write!(f, " Offset {} kHz", self.frequency_offset)?;
+        }
+        if self.punctured != 0 {
+            write!(f, " Punctured {:#06x}", self.punctured)?;
+        }
+        Ok(())
+    }
+}
+
+bitflags! {
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct ChannelFlags: u32 {
+        const DISABLED      = 1;
+        const NO_IR         = 1 << 1;
+        const RADAR         = 1 << 2;
+        const NO_HT40_MINUS = 1 << 3;
+        const NO_HT40_PLUS  = 1 << 4;
+        const NO_80MHZ      = 1 << 5;
+        const NO_160MHZ     = 1 << 6;
+        const INDOOR_ONLY   = 1 << 7;
+        const NO_20MHZ      = 1 << 8;
+        const NO_10MHZ      = 1 << 9;
+        const NO_HE         = 1 << 10;
+        const NO_320MHZ     = 1 << 11;
+        const NO_EHT        = 1 << 12;
+    }
+}
+
+/// Channel of a wiphy band
+#[derive(Clone, Debug, PartialEq)]
+pub struct PhyChannel {
+    /// Frequency in MHz
+    pub frequency: u32,
+    /// Offset in kHz of the frequency
+    pub frequency_offset: u32,
+    /// Channel flags
+    pub flags: ChannelFlags,
+    /// Maximum transmission power in mBm
+    pub max_tx_power: Option<u32>,
+    /// WMM limits of the access categories, in the order voice, video, best effort and
+    /// background
+    pub wmm_rules: Vec<WmmRule>,
+}
+
+impl PhyChannel {
+    /// Decode channel from the frequency attributes of a band
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<PhyChannel, Error> {
+        let mut frequency = None;
+        let mut channel = PhyChannel {
+            frequency: 0,
+            frequency_offset: 0,
+            flags: ChannelFlags::empty(),
+            max_tx_power: None,
+            wmm_rules: vec![],
+        };
+        for attr in attributes {
+            let flag = match FrequencyAttribute::convert_from(attr.identifier) {
+                Some(FrequencyAttribute::Frequency) => {
+                    frequency = Some(attr.as_u32()?);
+                    continue;
+                }
+                Some(FrequencyAttribute::Offset) => {
+                    channel.frequency_offset = attr.as_u32()?;
+                    continue;
+                }
+                Some(FrequencyAttribute::TransmissionPower) => {
+                    channel.max_tx_power = Some(attr.as_u32()?);
+                    continue;
+                }
+                Some(FrequencyAttribute::Wmm) => {
+                    channel.wmm_rules = WmmRule::from_nested_attribute_array(&attr.as_bytes())?;
+                    continue;
+                }
+                Some(FrequencyAttribute::Disabled) => ChannelFlags::DISABLED,
+                Some(FrequencyAttribute::NoIr) => ChannelFlags::NO_IR,
+                Some(FrequencyAttribute::Radar) => ChannelFlags::RADAR,
+                Some(FrequencyAttribute::Ht40Minus) => ChannelFlags::NO_HT40_MINUS,
+                Some(FrequencyAttribute::Ht40Plus) => ChannelFlags::NO_HT40_PLUS,
+                Some(FrequencyAttribute::No80MHz) => ChannelFlags::NO_80MHZ,
+                Some(FrequencyAttribute::No160MHz) => ChannelFlags::NO_160MHZ,
+                Some(FrequencyAttribute::IndoorOnly) => ChannelFlags::INDOOR_ONLY,
+                Some(FrequencyAttribute::No20Mhz) => ChannelFlags::NO_20MHZ,
+                Some(FrequencyAttribute::No10Mhz) => ChannelFlags::NO_10MHZ,
+                Some(FrequencyAttribute::NoHe) => ChannelFlags::NO_HE,
+                Some(FrequencyAttribute::No320Mhz) => ChannelFlags::NO_320MHZ,
+                Some(FrequencyAttribute::NoEht) => ChannelFlags::NO_EHT,
+                _ => continue,
+            };
+            channel.flags |= flag;
+        }
+        channel.frequency = frequency
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing frequency"))?;
+        Ok(channel)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::WmmRuleAttribute;
+    use crate::pack::{flag_attribute, nested_attribute};
+
+    #[test]
+    fn validate_channel_definition() {
+        assert!(ChannelDefinition::new(2412).validate().is_ok());
+        let channel = ChannelDefinition::with_width(5180, ChannelWidth::Width80, 5210);
+        assert!(channel.validate().is_ok());
+        assert_eq!(channel.subchannels(), vec![5180, 5200, 5220, 5240]);
+        assert!(channel.clone().punctured(0b0100).validate().is_ok());
+        assert_eq!(
+            channel.clone().punctured(0b0100).subchannels(),
+            vec![5180, 5200, 5240]
+        );
+        assert!(channel.clone().punctured(0b0001).validate().is_err());
+        assert!(channel.clone().punctured(0b10000).validate().is_err());
+        assert!(
+            ChannelDefinition::with_width(5190, ChannelWidth::Width80, 5210)
+                .validate()
+                .is_err()
+        );
+        assert!(
+            ChannelDefinition::with_width(5180, ChannelWidth::Width40, 5190)
+                .punctured(1)
+                .validate()
+                .is_err()
+        );
+        let channel = ChannelDefinition::with_width(5180, ChannelWidth::Width80P80, 5210);
+        assert!(channel.validate().is_err());
+        assert!(channel.clone().center_frequency2(5290).validate().is_err());
+        assert!(channel.center_frequency2(5530).validate().is_ok());
+        let channel = ChannelDefinition::with_width(10, ChannelWidth::Width80, 10);
+        assert!(channel.segments().is_empty());
+        assert!(channel.subchannels().is_empty());
+        assert!(channel.validate().is_err());
+        let channel = ChannelDefinition::new(5);
+        assert!(channel.segments().is_empty());
+        assert!(channel.validate().is_err());
+    }
+
+    #[test]
+    fn check_phy_channels() {
+        let channel = |frequency: u32, flags: &[FrequencyAttribute]| {
+            let mut attributes = vec![netlink::Attribute::new(
+                FrequencyAttribute::Frequency,
+                frequency,
+            )];
+            attributes.extend(flags.iter().cloned().map(flag_attribute));
+            PhyChannel::from_attributes(&attributes).unwrap()
+        };
+        let channels = [
+            channel(5180, &[FrequencyAttribute::Ht40Minus]),
+            channel(5200, &[FrequencyAttribute::Ht40Plus]),
+            channel(5220, &[FrequencyAttribute::Ht40Minus]),
+            channel(5240, &[FrequencyAttribute::Disabled]),
+        ];
+        assert_eq!(channels[3].flags, ChannelFlags::DISABLED);
+        let ht40 = ChannelDefinition::with_width(5180, ChannelWidth::Width40, 5190);
+        assert!(ht40.check_channels(&channels).is_ok());
+        let ht40 = ChannelDefinition::with_width(5200, ChannelWidth::Width40, 5210);
+        assert!(ht40.check_channels(&channels).is_err());
+        let vht80 = ChannelDefinition::with_width(5180, ChannelWidth::Width80, 5210);
+        assert!(vht80.check_channels(&channels).is_err());
+        assert!(vht80.punctured(0b1000).check_channels(&channels).is_ok());
+        assert!(ChannelDefinition::new(5260)
+            .check_channels(&channels)
+            .is_err());
+    }
+
+    #[test]
+    fn phy_channel_wmm_rules() {
+        let rule = |cw_min: u16, cw_max: u16, aifsn: u8, txop: u16| {
+            vec![
+                netlink::Attribute::new(WmmRuleAttribute::CwMin, cw_min),
+                netlink::Attribute::new(WmmRuleAttribute::CwMax, cw_max),
+                netlink::Attribute::new(WmmRuleAttribute::Aifsn, aifsn),
+                netlink::Attribute::new(WmmRuleAttribute::Txop, txop),
+            ]
+        };
+        let rules = [
+            nested_attribute(1u16, &rule(3, 7, 2, 47)),
+            nested_attribute(2u16, &rule(7, 15, 2, 94)),
+            nested_attribute(3u16, &rule(15, 1023, 3, 0)),
+            nested_attribute(4u16, &rule(15, 1023, 7, 0)),
+        ];
+        let attributes = [
+            netlink::Attribute::new(FrequencyAttribute::Frequency, 5500u32),
+            netlink::Attribute::new(FrequencyAttribute::TransmissionPower, 2000u32),
+            flag_attribute(FrequencyAttribute::Radar),
+            nested_attribute(FrequencyAttribute::Wmm, &rules),
+        ];
+        let channel = PhyChannel::from_attributes(&attributes).unwrap();
+        assert_eq!(channel.frequency, 5500);
+        assert_eq!(channel.max_tx_power, Some(2000));
+        assert_eq!(channel.flags, ChannelFlags::RADAR);
+        assert_eq!(channel.wmm_rules.len(), 4);
+        assert_eq!(
+            channel.wmm_rules[0],
+            WmmRule {
+                cw_min: 3,
+                cw_max: 7,
+                aifsn: 2,
+                txop: 47
+            }
+        );
+        assert_eq!(channel.wmm_rules[3].aifsn, 7);
+        let channel = PhyChannel::from_attributes(&attributes[..3]).unwrap();
+        assert!(channel.wmm_rules.is_empty());
+    }
+}
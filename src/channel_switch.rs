@@ -0,0 +1,311 @@
+//! ## Channel Switch
+//!
+//! Channel switch of a running access point, announced with channel switch announcement (CSA)
+//! elements in beacons and probe responses, and the channel switch notifications.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::access_point::{BeaconData, BeaconTemplate};
+use crate::attributes::Attribute;
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::information_element::{
+    ChannelSwitchAnnouncement, ChannelSwitchMode, ExtendedChannelSwitchAnnouncement,
+};
+use crate::pack::{flag_attribute, nested_attribute};
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, MessageMode, Socket};
+
+/// Request to switch the operating channel of an access point
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelSwitchRequest {
+    channel: ChannelDefinition,
+    count: u32,
+    block_tx: bool,
+    beacon_after: BeaconData,
+    beacon_csa: BeaconData,
+    counter_offsets_beacon: Vec<u16>,
+    counter_offsets_probe_response: Vec<u16>,
+}
+
+impl ChannelSwitchRequest {
+    /// Create channel switch request
+    ///
+    /// The beacon used during the switch carries the switch announcement, its switch count is
+    /// updated by the driver at the counter offsets. The beacon after the switch is used once
+    /// the switch is completed.
+    pub fn new(
+        channel: ChannelDefinition,
+        count: u32,
+        beacon_csa: BeaconData,
+        beacon_after: BeaconData,
+    ) -> ChannelSwitchRequest {
+        ChannelSwitchRequest {
+            channel,
+            count,
+            block_tx: false,
+            beacon_after,
+            beacon_csa,
+            counter_offsets_beacon: vec![],
+            counter_offsets_probe_response: vec![],
+        }
+    }
+
+    /// Create channel switch request from beacon templates
+    ///
+    /// The announcement elements are appended to the current beacon template and the counter
+    /// offsets are computed. Transmission is blocked if the announcement requires it.
+    pub fn from_templates(
+        channel: ChannelDefinition,
+        current: &BeaconTemplate,
+        after: &BeaconTemplate,
+        announcement: &ChannelSwitchAnnouncement,
+        extended: Option<&ExtendedChannelSwitchAnnouncement>,
+    ) -> ChannelSwitchRequest {
+        let mut tail_length = current.tail().len();
+        let mut probe_response_length = current.probe_response().len();
+        let mut counter_offsets_beacon = vec![];
+        let mut counter_offsets_probe_response = vec![];
+        let mut template = current.clone();
+        let mut elements = vec![(
+            announcement.pack(),
+            ChannelSwitchAnnouncement::SWITCH_COUNT_OFFSET,
+        )];
+        if let Some(extended) = extended {
+            elements.push((
+                extended.pack(),
+                ExtendedChannelSwitchAnnouncement::SWITCH_COUNT_OFFSET,
+            ));
+        }
+        for (element, offset) in elements {
+            counter_offsets_beacon.push((tail_length + offset) as u16);
+            counter_offsets_probe_response.push((probe_response_length + offset) as u16);
+            tail_length += element.len();
+            probe_response_length += element.len();
+            template = template.element(&element);
+        }
+        ChannelSwitchRequest {
+            channel,
+            count: u32::from(announcement.switch_count),
+            block_tx: announcement.switch_mode == ChannelSwitchMode::NoTransmission,
+            beacon_after: after.beacon_data(),
+            beacon_csa: template.beacon_data(),
+            counter_offsets_beacon,
+            counter_offsets_probe_response,
+        }
+    }
+
+    /// Block transmission until the switch is completed
+    pub fn block_tx(mut self, block_tx: bool) -> Self {
+        self.block_tx = block_tx;
+        self
+    }
+
+    /// Offsets of the switch counts in the tail of the beacon used during the switch
+    pub fn counter_offsets_beacon(mut self, offsets: &[u16]) -> Self {
+        self.counter_offsets_beacon = offsets.to_vec();
+        self
+    }
+
+    /// Offsets of the switch counts in the probe response used during the switch
+    pub fn counter_offsets_probe_response(mut self, offsets: &[u16]) -> Self {
+        self.counter_offsets_probe_response = offsets.to_vec();
+        self
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        fn pack_offsets(offsets: &[u16]) -> Vec<u8> {
+            offsets
+                .iter()
+                .flat_map(|o| o.to_ne_bytes().to_vec())
+                .collect()
+        }
+        let mut attributes = self.channel.attributes();
+        attributes.push(netlink::Attribute::new(
+            Attribute::ChSwitchCount,
+            self.count,
+        ));
+        if self.block_tx {
+            attributes.push(flag_attribute(Attribute::ChSwitchBlockTx));
+        }
+        attributes.extend(self.beacon_after.attributes());
+        // The counter offsets are part of the beacon used during the switch
+        let mut csa_attributes = self.beacon_csa.attributes();
+        if !self.counter_offsets_beacon.is_empty() {
+            csa_attributes.push(netlink::Attribute::new_bytes(
+                Attribute::CsaCOffBeacon,
+                &pack_offsets(&self.counter_offsets_beacon),
+            ));
+        }
+        if !self.counter_offsets_probe_response.is_empty() {
+            csa_attributes.push(netlink::Attribute::new_bytes(
+                Attribute::CsaCOffPresp,
+                &pack_offsets(&self.counter_offsets_probe_response),
+            ));
+        }
+        attributes.push(nested_attribute(Attribute::CsaIes, &csa_attributes));
+        attributes
+    }
+}
+
+impl WirelessInterface {
+    /// Switch the operating channel of the access point
+    pub fn channel_switch(
+        &self,
+        socket: &mut Socket,
+        request: &ChannelSwitchRequest,
+    ) -> Result<(), Error> {
+        let mut msg = self.prepare_message(Command::ChannelSwitch, MessageMode::Acknowledge)?;
+        for attribute in request.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Channel switch event
+pub struct ChannelSwitchEvent {
+    /// The channel switch has started, otherwise the switch is completed
+    pub started: bool,
+    /// New channel
+    pub channel: ChannelDefinition,
+    /// Number of beacon intervals until the switch, only reported when the switch starts
+    pub count: Option<u32>,
+}
+
+impl ChannelSwitchEvent {
+    /// Decode a ChannelSwitchStartedNotify or ChannelSwitchNotify message
+    pub fn from_message(message: &generic::Message) -> Result<ChannelSwitchEvent, Error> {
+        let started = match Command::convert_from(message.command) {
+            Some(Command::ChannelSwitchStartedNotify) => true,
+            Some(Command::ChannelSwitchNotify) => false,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Not a channel switch notification",
+                )
+                .into());
+            }
+        };
+        let mut count = None;
+        for attr in &message.attributes {
+            if Attribute::convert_from(attr.identifier) == Some(Attribute::ChSwitchCount) {
+                count = Some(attr.as_u32()?);
+            }
+        }
+        let channel = ChannelDefinition::from_attributes(&message.attributes)?;
+        Ok(ChannelSwitchEvent {
+            started,
+            channel,
+            count,
+        })
+    }
+}
+
+impl fmt::Display for ChannelSwitchEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.started {
+            write!(f, "Channel Switch Started {}", self.channel)?;
+        } else {
+            write!(f, "Channel Switch {}", self.channel)?;
+        }
+        if let Some(count) = self.count {
+            write!(f, " Count {}", count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use netlink_rust::HardwareAddress;
+
+    #[test]
+    fn counter_offsets() {
+        let bssid = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        let current = BeaconTemplate::new(bssid, "test")
+            .unwrap()
+            .channel(1)
+            .element(&[221, 3, 0x00, 0x50, 0xf2]);
+        let after = BeaconTemplate::new(bssid, "test").unwrap().channel(6);
+        let csa = ChannelSwitchAnnouncement {
+            switch_mode: ChannelSwitchMode::NoTransmission,
+            new_channel: 6,
+            switch_count: 5,
+        };
+        let ecsa = ExtendedChannelSwitchAnnouncement {
+            switch_mode: ChannelSwitchMode::NoTransmission,
+            new_operating_class: 81,
+            new_channel: 6,
+            switch_count: 5,
+        };
+        let request = ChannelSwitchRequest::from_templates(
+            ChannelDefinition::new(2437),
+            &current,
+            &after,
+            &csa,
+            Some(&ecsa),
+        );
+        assert!(request.block_tx);
+        assert_eq!(request.count, 5);
+        assert_eq!(request.counter_offsets_beacon, vec![9, 15]);
+        let tail = request.beacon_csa.tail();
+        for offset in &request.counter_offsets_beacon {
+            assert_eq!(tail[*offset as usize], 5);
+        }
+        let probe_response = current.clone().element(&csa.pack()).element(&ecsa.pack());
+        let probe_response = probe_response.probe_response();
+        for offset in &request.counter_offsets_probe_response {
+            assert_eq!(probe_response[*offset as usize], 5);
+        }
+    }
+
+    #[test]
+    fn counter_offsets_in_csa_attributes() {
+        let bssid = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x01][..]);
+        let template = BeaconTemplate::new(bssid, "test").unwrap().channel(1);
+        let request = ChannelSwitchRequest::new(
+            ChannelDefinition::new(2437),
+            5,
+            template.beacon_data(),
+            template.beacon_data(),
+        )
+        .counter_offsets_beacon(&[9])
+        .counter_offsets_probe_response(&[11]);
+        let attributes = request.attributes();
+        let id = |attribute: Attribute| u16::from(attribute);
+        assert!(!attributes
+            .iter()
+            .any(|a| a.identifier == id(Attribute::CsaCOffBeacon)));
+        assert!(!attributes
+            .iter()
+            .any(|a| a.identifier == id(Attribute::CsaCOffPresp)));
+        let csa = attributes
+            .iter()
+            .find(|a| a.identifier == id(Attribute::CsaIes))
+            .unwrap();
+        let (_, nested) = netlink::Attribute::unpack_all(&csa.as_bytes());
+        let beacon = nested
+            .iter()
+            .find(|a| a.identifier == id(Attribute::CsaCOffBeacon))
+            .unwrap();
+        assert_eq!(beacon.as_u16().unwrap(), 9);
+        let probe_response = nested
+            .iter()
+            .find(|a| a.identifier == id(Attribute::CsaCOffPresp))
+            .unwrap();
+        assert_eq!(probe_response.as_u16().unwrap(), 11);
+    }
+}
@@ -0,0 +1,389 @@
+//! ## Connection Quality Monitor
+//!
+//! Configuration of the connection quality monitor (CQM) and decoding of the events it emits.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::{Attribute, ConnectionQualityMonitorAttribute};
+use crate::commands::Command;
+use crate::pack::nested_attribute;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, HardwareAddress, MessageMode, Socket};
+
+/// Received signal strength indicator (RSSI) monitoring
+#[derive(Clone, Debug, PartialEq)]
+struct RssiMonitor {
+    /// Thresholds in dBm, a single threshold or a list of increasing thresholds
+    thresholds: Vec<i32>,
+    /// Hysteresis in dB
+    hysteresis: u32,
+}
+
+/// Transmit error (TX-E) monitoring
+#[derive(Clone, Debug, PartialEq)]
+struct TransmitErrorMonitor {
+    /// Number of packets to observe before reporting
+    packets: u32,
+    /// Packet error rate in percent to report at
+    rate: u32,
+    /// Interval in seconds between reports
+    interval: u32,
+}
+
+/// Connection quality monitor configuration
+///
+/// Beacon loss and packet loss events are reported by the driver without further
+/// configuration.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionQualityMonitor {
+    rssi: Option<RssiMonitor>,
+    transmit_error: Option<TransmitErrorMonitor>,
+}
+
+impl ConnectionQualityMonitor {
+    pub fn new() -> ConnectionQualityMonitor {
+        ConnectionQualityMonitor::default()
+    }
+
+    /// Report when the signal crosses the threshold, in dBm, with the given hysteresis in dB
+    pub fn rssi(mut self, threshold: i32, hysteresis: u32) -> Self {
+        self.rssi = Some(RssiMonitor {
+            thresholds: vec![threshold],
+            hysteresis,
+        });
+        self
+    }
+
+    /// Report when the signal moves between the ranges given by the thresholds, in dBm
+    ///
+    /// The thresholds must be in increasing order. Multiple thresholds require a device with
+    /// the CQM_RSSI_LIST extended feature.
+    pub fn rssi_range(mut self, thresholds: &[i32], hysteresis: u32) -> Self {
+        self.rssi = Some(RssiMonitor {
+            thresholds: thresholds.to_vec(),
+            hysteresis,
+        });
+        self
+    }
+
+    /// Disable RSSI monitoring
+    pub fn disable_rssi(self) -> Self {
+        self.rssi(0, 0)
+    }
+
+    /// Report when the transmit packet error rate, in percent, exceeds rate for the given
+    /// number of packets, at most once per interval in seconds
+    pub fn transmit_error(mut self, packets: u32, rate: u32, interval: u32) -> Self {
+        self.transmit_error = Some(TransmitErrorMonitor {
+            packets,
+            rate,
+            interval,
+        });
+        self
+    }
+
+    /// Disable transmit error monitoring
+    pub fn disable_transmit_error(self) -> Self {
+        self.transmit_error(0, 0, 0)
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(ref rssi) = self.rssi {
+            if rssi.thresholds.is_empty() {
+                return Err(
+                    io::Error::new(io::ErrorKind::InvalidInput, "No RSSI thresholds").into(),
+                );
+            }
+            if rssi.thresholds.windows(2).any(|w| w[0] >= w[1]) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "RSSI thresholds must be increasing",
+                )
+                .into());
+            }
+        }
+        if let Some(ref txe) = self.transmit_error {
+            if txe.rate > 100 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Transmit error rate must be at most 100 percent",
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Attributes for each request, the kernel only accepts one kind of monitor per request
+    fn attributes(&self) -> Vec<Vec<netlink::Attribute>> {
+        let mut requests = vec![];
+        if let Some(ref rssi) = self.rssi {
+            let thresholds: Vec<u8> = rssi
+                .thresholds
+                .iter()
+                .flat_map(|t| t.to_ne_bytes().to_vec())
+                .collect();
+            requests.push(vec![
+                netlink::Attribute::new_bytes(
+                    ConnectionQualityMonitorAttribute::RssiThreshold,
+                    &thresholds,
+                ),
+                netlink::Attribute::new(
+                    ConnectionQualityMonitorAttribute::RssiHysteresis,
+                    rssi.hysteresis,
+                ),
+            ]);
+        }
+        if let Some(ref txe) = self.transmit_error {
+            requests.push(vec![
+                netlink::Attribute::new(
+                    ConnectionQualityMonitorAttribute::TransmitErrorRate,
+                    txe.rate,
+                ),
+                netlink::Attribute::new(
+                    ConnectionQualityMonitorAttribute::TransmitErrorPackets,
+                    txe.packets,
+                ),
+                netlink::Attribute::new(
+                    ConnectionQualityMonitorAttribute::TransmitErrorInterval,
+                    txe.interval,
+                ),
+            ]);
+        }
+        requests
+    }
+}
+
+impl WirelessInterface {
+    /// Configure the connection quality monitor for the interface
+    pub fn set_connection_quality_monitor(
+        &self,
+        socket: &mut Socket,
+        monitor: &ConnectionQualityMonitor,
+    ) -> Result<(), Error> {
+        monitor.validate()?;
+        for attributes in monitor.attributes() {
+            let mut msg = self.prepare_message(
+                Command::SetConnectionQualityMonitor,
+                MessageMode::Acknowledge,
+            )?;
+            msg.append_attribute(nested_attribute(Attribute::Cqm, &attributes));
+            socket.send_message(&msg)?;
+            loop {
+                let messages = socket.receive_messages()?;
+                if messages.is_empty() {
+                    break;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Connection quality monitor event
+#[derive(Clone, Debug, PartialEq)]
+pub enum CqmEvent {
+    /// Signal went below the low threshold, level in dBm if reported
+    RssiLow { level: Option<i32> },
+    /// Signal went above the high threshold, level in dBm if reported
+    RssiHigh { level: Option<i32> },
+    /// Packets to the peer were lost
+    PacketLoss {
+        peer: Option<HardwareAddress>,
+        packets: u32,
+    },
+    /// Beacons from the access point were lost
+    BeaconLoss,
+    /// Transmit packet error rate to the peer exceeded the configured rate
+    TransmitError {
+        peer: Option<HardwareAddress>,
+        packets: u32,
+        rate: u32,
+        interval: u32,
+    },
+}
+
+impl CqmEvent {
+    /// Decode a NotifyConnectionQualityMonitor message
+    pub fn from_message(message: &generic::Message) -> Result<CqmEvent, Error> {
+        let mut peer = None;
+        let mut cqm = None;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Mac) => {
+                    peer = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::Cqm) => {
+                    cqm = Some(attr.as_bytes());
+                }
+                _ => (),
+            }
+        }
+        let cqm =
+            cqm.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing CQM attribute"))?;
+        CqmEvent::from_attributes(&cqm, peer)
+    }
+
+    fn from_attributes(data: &[u8], peer: Option<HardwareAddress>) -> Result<CqmEvent, Error> {
+        let mut threshold_event = None;
+        let mut level = None;
+        let mut packet_loss = None;
+        let mut beacon_loss = false;
+        let mut txe_packets = None;
+        let mut txe_rate = 0;
+        let mut txe_interval = 0;
+        let (_, attrs) = netlink::Attribute::unpack_all(data);
+        for attr in attrs {
+            match ConnectionQualityMonitorAttribute::convert_from(attr.identifier) {
+                Some(ConnectionQualityMonitorAttribute::RssiThresholdEvent) => {
+                    threshold_event = Some(attr.as_u32()?);
+                }
+                Some(ConnectionQualityMonitorAttribute::RssiLevel) => {
+                    level = Some(attr.as_i32()?);
+                }
+                Some(ConnectionQualityMonitorAttribute::PacketLossEvent) => {
+                    packet_loss = Some(attr.as_u32()?);
+                }
+                Some(ConnectionQualityMonitorAttribute::BeaconLossEvent) => {
+                    beacon_loss = true;
+                }
+                Some(ConnectionQualityMonitorAttribute::TransmitErrorPackets) => {
+                    txe_packets = Some(attr.as_u32()?);
+                }
+                Some(ConnectionQualityMonitorAttribute::TransmitErrorRate) => {
+                    txe_rate = attr.as_u32()?;
+                }
+                Some(ConnectionQualityMonitorAttribute::TransmitErrorInterval) => {
+                    txe_interval = attr.as_u32()?;
+                }
+                _ => (),
+            }
+        }
+        let event = match threshold_event {
+            // NL80211_CQM_RSSI_THRESHOLD_EVENT_LOW
+            Some(0) => CqmEvent::RssiLow { level },
+            // NL80211_CQM_RSSI_THRESHOLD_EVENT_HIGH
+            Some(1) => CqmEvent::RssiHigh { level },
+            // NL80211_CQM_RSSI_BEACON_LOSS_EVENT
+            Some(2) => CqmEvent::BeaconLoss,
+            _ => {
+                if let Some(packets) = packet_loss {
+                    CqmEvent::PacketLoss { peer, packets }
+                } else if beacon_loss {
+                    CqmEvent::BeaconLoss
+                } else if let Some(packets) = txe_packets {
+                    CqmEvent::TransmitError {
+                        peer,
+                        packets,
+                        rate: txe_rate,
+                        interval: txe_interval,
+                    }
+                } else {
+                    return Err(
+                        io::Error::new(io::ErrorKind::InvalidData, "Unknown CQM event").into(),
+                    );
+                }
+            }
+        };
+        Ok(event)
+    }
+}
+
+impl fmt::Display for CqmEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CqmEvent::RssiLow { level } => {
+                write!(f, "RSSI Low")?;
+                if let Some(level) = level {
+                    write!(f, " {} dBm", level)?;
+                }
+                Ok(())
+            }
+            CqmEvent::RssiHigh { level } => {
+                write!(f, "RSSI High")?;
+                if let Some(level) = level {
+                    write!(f, " {} dBm", level)?;
+                }
+                Ok(())
+            }
+            CqmEvent::PacketLoss { ref peer, packets } => {
+                write!(f, "Packet Loss {} packets", packets)?;
+                if let Some(ref peer) = *peer {
+                    write!(f, " Peer {}", peer)?;
+                }
+                Ok(())
+            }
+            CqmEvent::BeaconLoss => write!(f, "Beacon Loss"),
+            CqmEvent::TransmitError {
+                ref peer,
+                packets,
+                rate,
+                interval,
+            } => {
+                write!(
+                    f,
+                    "Transmit Error {} packets {}% {} s",
+                    packets, rate, interval
+                )?;
+                if let Some(ref peer) = *peer {
+                    write!(f, " Peer {}", peer)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::pack_attributes;
+
+    #[test]
+    fn rssi_thresholds_increasing() {
+        let monitor = ConnectionQualityMonitor::new().rssi_range(&[-60, -70], 2);
+        assert!(monitor.validate().is_err());
+        let monitor = ConnectionQualityMonitor::new().rssi_range(&[-80, -70, -60], 2);
+        assert!(monitor.validate().is_ok());
+        assert_eq!(monitor.attributes().len(), 1);
+    }
+
+    #[test]
+    fn transmit_error_rate_percent() {
+        let monitor = ConnectionQualityMonitor::new().transmit_error(50, 100, 10);
+        assert!(monitor.validate().is_ok());
+        let monitor = ConnectionQualityMonitor::new().transmit_error(50, 101, 10);
+        assert!(monitor.validate().is_err());
+    }
+
+    #[test]
+    fn separate_requests() {
+        let monitor = ConnectionQualityMonitor::new()
+            .rssi(-70, 4)
+            .transmit_error(50, 20, 10);
+        let requests = monitor.attributes();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0][0].as_bytes(), (-70i32).to_ne_bytes().to_vec());
+        assert_eq!(requests[1].len(), 3);
+    }
+
+    #[test]
+    fn decode_rssi_low() {
+        let data = pack_attributes(&[
+            netlink::Attribute::new_bytes(
+                ConnectionQualityMonitorAttribute::RssiThresholdEvent,
+                &0u32.to_ne_bytes(),
+            ),
+            netlink::Attribute::new_bytes(
+                ConnectionQualityMonitorAttribute::RssiLevel,
+                &(-75i32).to_ne_bytes(),
+            ),
+        ]);
+        let event = CqmEvent::from_attributes(&data, None).unwrap();
+        assert_eq!(event, CqmEvent::RssiLow { level: Some(-75) });
+    }
+}
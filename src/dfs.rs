@@ -0,0 +1,235 @@
+//! ## Dynamic Frequency Selection
+//!
+//! Radar detection on channels requiring dynamic frequency selection (DFS). Before operating
+//! on such a channel a channel availability check (CAC) is done, listening for radars during
+//! the CAC time. With background radar detection the CAC is done by a dedicated radio
+//! while the interface keeps operating on its current channel.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::{Attribute, DfsState, FrequencyAttribute, RadarEvent};
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::pack::flag_attribute;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, MessageMode, Socket};
+
+/// DFS information of a channel of a wiphy band
+#[derive(Clone, Debug, PartialEq)]
+pub struct DfsChannel {
+    /// Frequency in MHz
+    pub frequency: u32,
+    /// Radar detection is required on the channel
+    pub radar: bool,
+    /// DFS state of the channel
+    pub state: Option<DfsState>,
+    /// Time in milliseconds since the channel entered the DFS state
+    pub time_in_state: Option<u32>,
+    /// Channel availability check time in milliseconds
+    pub cac_time: Option<u32>,
+}
+
+impl DfsChannel {
+    /// Decode DFS information from the frequency attributes of a band
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<DfsChannel, Error> {
+        let mut frequency = None;
+        let mut channel = DfsChannel {
+            frequency: 0,
+            radar: false,
+            state: None,
+            time_in_state: None,
+            cac_time: None,
+        };
+        for attr in attributes {
+            match FrequencyAttribute::convert_from(attr.identifier) {
+                Some(FrequencyAttribute::Frequency) => {
+                    frequency = Some(attr.as_u32()?);
+                }
+                Some(FrequencyAttribute::Radar) => {
+                    channel.radar = true;
+                }
+                Some(FrequencyAttribute::DfsState) => {
+                    channel.state = DfsState::convert_from(attr.as_u32()?);
+                }
+                Some(FrequencyAttribute::DfsTime) => {
+                    channel.time_in_state = Some(attr.as_u32()?);
+                }
+                Some(FrequencyAttribute::DfsCacTime) => {
+                    channel.cac_time = Some(attr.as_u32()?);
+                }
+                _ => (),
+            }
+        }
+        channel.frequency = frequency
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing frequency"))?;
+        Ok(channel)
+    }
+}
+
+impl fmt::Display for DfsChannel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} MHz", self.frequency)?;
+        if let Some(ref state) = self.state {
+            write!(f, " {}", state)?;
+        }
+        if let Some(time) = self.time_in_state {
+            write!(f, " for {} ms", time)?;
+        }
+        if let Some(time) = self.cac_time {
+            write!(f, " CAC {} ms", time)?;
+        }
+        Ok(())
+    }
+}
+
+/// Channel availability check time in milliseconds of the channel, the longest CAC time of
+/// the DFS channels covered, none if no radar detection is required
+pub(crate) fn cac_time(channels: &[DfsChannel], channel: &ChannelDefinition) -> Option<u32> {
+    let segments = channel.segments();
+    channels
+        .iter()
+        .filter(|c| c.radar)
+        .filter(|c| {
+            segments
+                .iter()
+                .any(|&(start, end)| start < c.frequency && c.frequency < end)
+        })
+        .map(|c| c.cac_time.unwrap_or(0))
+        .max()
+}
+
+impl WirelessInterface {
+    fn radar_detection(
+        &self,
+        socket: &mut Socket,
+        channel: &ChannelDefinition,
+        background: bool,
+    ) -> Result<(), Error> {
+        let mut msg = self.prepare_message(Command::RadarDetect, MessageMode::Acknowledge)?;
+        for attribute in channel.attributes() {
+            msg.append_attribute(attribute);
+        }
+        if background {
+            msg.append_attribute(flag_attribute(Attribute::RadarBackground));
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Start the channel availability check on the channel
+    ///
+    /// The CAC time of the channel is given by the regulatory domain, see
+    /// `WirelessPhy::cac_time`. The result is reported with a CAC finished or CAC aborted
+    /// event, or with a radar detected event.
+    pub fn start_radar_detection(
+        &self,
+        socket: &mut Socket,
+        channel: &ChannelDefinition,
+    ) -> Result<(), Error> {
+        self.radar_detection(socket, channel, false)
+    }
+
+    /// Start the channel availability check on the channel using the background radar
+    /// detection radio of the wiphy, the interface keeps operating on its current channel
+    ///
+    /// Starting a check on another channel replaces the ongoing background check.
+    pub fn start_background_radar_detection(
+        &self,
+        socket: &mut Socket,
+        channel: &ChannelDefinition,
+    ) -> Result<(), Error> {
+        self.radar_detection(socket, channel, true)
+    }
+}
+
+/// Radar detection event
+pub struct DfsEvent {
+    /// Radar detection event
+    pub event: RadarEvent,
+    /// Channel the event applies to
+    pub channel: ChannelDefinition,
+    /// The event is from the background radar detection radio
+    pub background: bool,
+}
+
+impl DfsEvent {
+    /// Decode a RadarDetect message
+    pub fn from_message(message: &generic::Message) -> Result<DfsEvent, Error> {
+        if Command::convert_from(message.command) != Some(Command::RadarDetect) {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidData, "Not a radar detect event").into(),
+            );
+        }
+        let mut event = None;
+        let mut background = false;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::RadarEvent) => {
+                    event = RadarEvent::convert_from(attr.as_u32()?);
+                }
+                Some(Attribute::RadarBackground) => {
+                    background = true;
+                }
+                _ => (),
+            }
+        }
+        let event = event
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing radar event"))?;
+        let channel = ChannelDefinition::from_attributes(&message.attributes)?;
+        Ok(DfsEvent {
+            event,
+            channel,
+            background,
+        })
+    }
+}
+
+impl fmt::Display for DfsEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Radar {} {}", self.event, self.channel)?;
+        if self.background {
+            write!(f, " Background")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::ChannelWidth;
+
+    #[test]
+    fn channel_availability_check_time() {
+        let attributes = [
+            netlink::Attribute::new(FrequencyAttribute::Frequency, 5260u32),
+            flag_attribute(FrequencyAttribute::Radar),
+            netlink::Attribute::new(FrequencyAttribute::DfsState, 0u32),
+            netlink::Attribute::new(FrequencyAttribute::DfsCacTime, 60000u32),
+        ];
+        let channel = DfsChannel::from_attributes(&attributes).unwrap();
+        assert_eq!(channel.state, Some(DfsState::Usable));
+        assert_eq!(channel.cac_time, Some(60000));
+        let channels = [
+            DfsChannel::from_attributes(&attributes[..1]).unwrap(),
+            channel,
+        ];
+        assert_eq!(cac_time(&channels, &ChannelDefinition::new(5180)), None);
+        assert_eq!(
+            cac_time(&channels, &ChannelDefinition::new(5260)),
+            Some(60000)
+        );
+        let channel = ChannelDefinition::with_width(5180, ChannelWidth::Width160, 5250);
+        assert_eq!(cac_time(&channels, &channel), Some(60000));
+    }
+}
@@ -0,0 +1,834 @@
+//! ## Events
+//!
+//! Typed nl80211 events and a listener subscribing to the nl80211 multicast groups.
+//!
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+use std::os::unix::io::{AsRawFd, RawFd};
+
+use crate::attributes::{Attribute, InterfaceType};
+use crate::channel_switch::ChannelSwitchEvent;
+use crate::commands::Command;
+use crate::connection_quality_monitor::CqmEvent;
+use crate::dfs::DfsEvent;
+use crate::fast_transition::FastTransitionEvent;
+use crate::management_frame::{FrameEvent, FrameTxStatusEvent};
+use crate::regulatory::RegulatoryChange;
+use crate::remain_on_channel::RemainOnChannelEvent;
+use crate::roaming::RoamEvent;
+use crate::wireless_interface::{WirelessDeviceId, WirelessInterface};
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, HardwareAddress, Protocol, Socket};
+
+/// nl80211 multicast group
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MulticastGroup {
+    /// Wiphy and interface configuration changes
+    Config,
+    /// Scan events
+    Scan,
+    /// Regulatory domain changes
+    Regulatory,
+    /// MLME events, such as connect, disconnect and frames
+    Mlme,
+    /// Vendor specific events
+    Vendor,
+    /// Neighbor awareness networking (NAN) events
+    Nan,
+    /// Test mode events
+    Testmode,
+}
+
+impl MulticastGroup {
+    /// Name of the multicast group of the nl80211 family
+    pub fn name(&self) -> &'static str {
+        match *self {
+            MulticastGroup::Config => "config",
+            MulticastGroup::Scan => "scan",
+            MulticastGroup::Regulatory => "regulatory",
+            MulticastGroup::Mlme => "mlme",
+            MulticastGroup::Vendor => "vendor",
+            MulticastGroup::Nan => "nan",
+            MulticastGroup::Testmode => "testmode",
+        }
+    }
+}
+
+/// Connect event, the result of a connection attempt
+pub struct ConnectEvent {
+    /// BSSID of the access point
+    pub bssid: Option<HardwareAddress>,
+    /// Status code of the association, zero on success
+    pub status_code: u16,
+    /// The connection attempt timed out, no response was received from the access point
+    pub timed_out: bool,
+    /// Information elements of the (re)association request
+    pub request_ies: Vec<u8>,
+    /// Information elements of the (re)association response
+    pub response_ies: Vec<u8>,
+}
+
+impl ConnectEvent {
+    /// Decode a Connect message
+    pub fn from_message(message: &generic::Message) -> Result<ConnectEvent, Error> {
+        let mut bssid = None;
+        let mut status_code = None;
+        let mut timed_out = false;
+        let mut request_ies = vec![];
+        let mut response_ies = vec![];
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Mac) => {
+                    bssid = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::StatusCode) => {
+                    status_code = Some(attr.as_u16()?);
+                }
+                Some(Attribute::TimedOut) => {
+                    timed_out = true;
+                }
+                Some(Attribute::ReqIe) => {
+                    request_ies = attr.as_bytes();
+                }
+                Some(Attribute::RespIe) => {
+                    response_ies = attr.as_bytes();
+                }
+                _ => (),
+            }
+        }
+        let status_code = status_code
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing status code"))?;
+        Ok(ConnectEvent {
+            bssid,
+            status_code,
+            timed_out,
+            request_ies,
+            response_ies,
+        })
+    }
+}
+
+impl fmt::Display for ConnectEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Connect")?;
+        if let Some(bssid) = self.bssid {
+            write!(f, " {}", bssid)?;
+        }
+        if self.status_code == 0 {
+            write!(f, " Status: OK")
+        } else if self.timed_out {
+            write!(f, " Status: {} Timed Out", self.status_code)
+        } else {
+            write!(f, " Status: {}", self.status_code)
+        }
+    }
+}
+
+/// Disconnect event
+pub struct DisconnectEvent {
+    /// Reason code of the disconnection
+    pub reason_code: Option<u16>,
+    /// The disconnection was initiated by the access point
+    pub by_ap: bool,
+}
+
+impl DisconnectEvent {
+    /// Decode a Disconnect message
+    pub fn from_message(message: &generic::Message) -> Result<DisconnectEvent, Error> {
+        let mut reason_code = None;
+        let mut by_ap = false;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::ReasonCode) => {
+                    reason_code = Some(attr.as_u16()?);
+                }
+                Some(Attribute::DisconnectedByAp) => {
+                    by_ap = true;
+                }
+                _ => (),
+            }
+        }
+        Ok(DisconnectEvent { reason_code, by_ap })
+    }
+}
+
+impl fmt::Display for DisconnectEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Disconnect")?;
+        if let Some(reason_code) = self.reason_code {
+            write!(f, " Reason: {}", reason_code)?;
+        }
+        if self.by_ap {
+            write!(f, " By AP")?;
+        }
+        Ok(())
+    }
+}
+
+/// nl80211 event
+pub enum Nl80211Event {
+    /// A scan has been started
+    ScanStarted,
+    /// A scan is done, the scan results are available
+    ScanDone,
+    /// A scan has been aborted
+    ScanAborted,
+    /// Result of a connection attempt
+    Connect(ConnectEvent),
+    /// Disconnected from the network
+    Disconnect(DisconnectEvent),
+    /// Roamed to another access point
+    Roam(RoamEvent),
+    /// Fast BSS transition to another access point
+    FastTransition(FastTransitionEvent),
+    /// The regulatory domain has changed
+    RegulatoryChange(RegulatoryChange),
+    /// A station has been added
+    NewStation(HardwareAddress),
+    /// A station has been removed
+    DelStation(HardwareAddress),
+    /// An interface has been created
+    NewInterface {
+        name: Option<String>,
+        interface_type: Option<InterfaceType>,
+    },
+    /// An interface has been removed
+    DelInterface {
+        name: Option<String>,
+        interface_type: Option<InterfaceType>,
+    },
+    /// A wiphy has been added or renamed
+    NewWiphy { name: Option<String> },
+    /// A wiphy has been removed
+    DelWiphy { name: Option<String> },
+    /// Channel switch started or completed
+    ChannelSwitch(ChannelSwitchEvent),
+    /// Radar detection event
+    Radar(DfsEvent),
+    /// Connection quality monitor notification
+    ConnectionQualityMonitor(CqmEvent),
+    /// Received management frame
+    FrameReceived(FrameEvent),
+    /// Transmit status of a transmitted management frame
+    FrameTxStatus(FrameTxStatusEvent),
+    /// Remain on channel started or ended
+    RemainOnChannel(RemainOnChannelEvent),
+    /// Vendor specific event
+    Vendor {
+        vendor_id: u32,
+        subcommand: u32,
+        data: Vec<u8>,
+    },
+    /// Event without a typed representation
+    Other { command: u8 },
+}
+
+impl Nl80211Event {
+    /// Decode an nl80211 event message
+    pub fn from_message(message: &generic::Message) -> Result<Nl80211Event, Error> {
+        let command = match Command::convert_from(message.command) {
+            Some(command) => command,
+            None => {
+                return Ok(Nl80211Event::Other {
+                    command: message.command,
+                });
+            }
+        };
+        let event = match command {
+            Command::TriggerScan => Nl80211Event::ScanStarted,
+            Command::NewScanResults => Nl80211Event::ScanDone,
+            Command::ScanAborted => Nl80211Event::ScanAborted,
+            Command::Connect => Nl80211Event::Connect(ConnectEvent::from_message(message)?),
+            Command::Disconnect => {
+                Nl80211Event::Disconnect(DisconnectEvent::from_message(message)?)
+            }
+            Command::Roam => Nl80211Event::Roam(RoamEvent::from_message(message)?),
+            Command::FastTransitionEvent => {
+                Nl80211Event::FastTransition(FastTransitionEvent::from_message(message)?)
+            }
+            Command::RegulatoryChange | Command::WiphyRegulatoryChange => {
+                Nl80211Event::RegulatoryChange(RegulatoryChange::from_message(message)?)
+            }
+            Command::NewStation => Nl80211Event::NewStation(station_address(message)?),
+            Command::DelStation => Nl80211Event::DelStation(station_address(message)?),
+            Command::NewInterface => {
+                let (name, interface_type) = interface_information(message)?;
+                Nl80211Event::NewInterface {
+                    name,
+                    interface_type,
+                }
+            }
+            Command::DelInterface => {
+                let (name, interface_type) = interface_information(message)?;
+                Nl80211Event::DelInterface {
+                    name,
+                    interface_type,
+                }
+            }
+            Command::NewWiphy => Nl80211Event::NewWiphy {
+                name: wiphy_name(message)?,
+            },
+            Command::DelWiphy => Nl80211Event::DelWiphy {
+                name: wiphy_name(message)?,
+            },
+            Command::ChannelSwitchStartedNotify | Command::ChannelSwitchNotify => {
+                Nl80211Event::ChannelSwitch(ChannelSwitchEvent::from_message(message)?)
+            }
+            Command::RadarDetect => Nl80211Event::Radar(DfsEvent::from_message(message)?),
+            Command::NotifyConnectionQualityMonitor => {
+                Nl80211Event::ConnectionQualityMonitor(CqmEvent::from_message(message)?)
+            }
+            Command::Frame => Nl80211Event::FrameReceived(FrameEvent::from_message(message)?),
+            Command::FrameTxStatus => {
+                Nl80211Event::FrameTxStatus(FrameTxStatusEvent::from_message(message)?)
+            }
+            Command::RemainOnChannel | Command::CancelRemainOnChannel => {
+                Nl80211Event::RemainOnChannel(RemainOnChannelEvent::from_message(message)?)
+            }
+            Command::Vendor => {
+                let mut vendor_id = None;
+                let mut subcommand = None;
+                let mut data = vec![];
+                for attr in &message.attributes {
+                    match Attribute::convert_from(attr.identifier) {
+                        Some(Attribute::VendorId) => {
+                            vendor_id = Some(attr.as_u32()?);
+                        }
+                        Some(Attribute::VendorSubcmd) => {
+                            subcommand = Some(attr.as_u32()?);
+                        }
+                        Some(Attribute::VendorData) => {
+                            data = attr.as_bytes();
+                        }
+                        _ => (),
+                    }
+                }
+                match (vendor_id, subcommand) {
+                    (Some(vendor_id), Some(subcommand)) => Nl80211Event::Vendor {
+                        vendor_id,
+                        subcommand,
+                        data,
+                    },
+                    _ => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "Missing vendor identifier",
+                        )
+                        .into());
+                    }
+                }
+            }
+            _ => Nl80211Event::Other {
+                command: message.command,
+            },
+        };
+        Ok(event)
+    }
+}
+
+fn station_address(message: &generic::Message) -> Result<HardwareAddress, Error> {
+    for attr in &message.attributes {
+        if Attribute::convert_from(attr.identifier) == Some(Attribute::Mac) {
+            return attr.as_hardware_address();
+        }
+    }
+    Err(io::Error::new(io::ErrorKind::InvalidData, "Missing station address").into())
+}
+
+fn interface_information(
+    message: &generic::Message,
+) -> Result<(Option<String>, Option<InterfaceType>), Error> {
+    let mut name = None;
+    let mut interface_type = None;
+    for attr in &message.attributes {
+        match Attribute::convert_from(attr.identifier) {
+            Some(Attribute::Ifname) => {
+                name = Some(attr.as_string()?);
+            }
+            Some(Attribute::Iftype) => {
+                interface_type = InterfaceType::convert_from(attr.as_u32()?);
+            }
+            _ => (),
+        }
+    }
+    Ok((name, interface_type))
+}
+
+fn wiphy_name(message: &generic::Message) -> Result<Option<String>, Error> {
+    for attr in &message.attributes {
+        if Attribute::convert_from(attr.identifier) == Some(Attribute::WiphyName) {
+            return Ok(Some(attr.as_string()?));
+        }
+    }
+    Ok(None)
+}
+
+impl fmt::Display for Nl80211Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Nl80211Event::ScanStarted => write!(f, "Scan Started"),
+            Nl80211Event::ScanDone => write!(f, "Scan Done"),
+            Nl80211Event::ScanAborted => write!(f, "Scan Aborted"),
+            Nl80211Event::Connect(ref event) => write!(f, "{}", event),
+            Nl80211Event::Disconnect(ref event) => write!(f, "{}", event),
+            Nl80211Event::Roam(ref event) => write!(f, "{}", event),
+            Nl80211Event::FastTransition(ref event) => write!(f, "{}", event),
+            Nl80211Event::RegulatoryChange(ref event) => write!(f, "Regulatory Change {}", event),
+            Nl80211Event::NewStation(address) => write!(f, "New Station {}", address),
+            Nl80211Event::DelStation(address) => write!(f, "Del Station {}", address),
+            Nl80211Event::NewInterface {
+                ref name,
+                ref interface_type,
+            } => write!(f, "New Interface {:?} {:?}", name, interface_type),
+            Nl80211Event::DelInterface {
+                ref name,
+                ref interface_type,
+            } => write!(f, "Del Interface {:?} {:?}", name, interface_type),
+            Nl80211Event::NewWiphy { ref name } => write!(f, "New Wiphy {:?}", name),
+            Nl80211Event::DelWiphy { ref name } => write!(f, "Del Wiphy {:?}", name),
+            Nl80211Event::ChannelSwitch(ref event) => write!(f, "{}", event),
+            Nl80211Event::Radar(ref event) => write!(f, "{}", event),
+            Nl80211Event::ConnectionQualityMonitor(ref event) => {
+                write!(f, "Connection Quality {}", event)
+            }
+            Nl80211Event::FrameReceived(ref event) => write!(f, "{}", event),
+            Nl80211Event::FrameTxStatus(ref event) => write!(f, "{}", event),
+            Nl80211Event::RemainOnChannel(ref event) => write!(f, "{}", event),
+            Nl80211Event::Vendor {
+                vendor_id,
+                subcommand,
+                ref data,
+            } => write!(
+                f,
+                "Vendor {:06x} Subcommand {} Len: {}",
+                vendor_id,
+                subcommand,
+                data.len()
+            ),
+            Nl80211Event::Other { command } => match Command::convert_from(command) {
+                Some(command) => write!(f, "Event {:?}", command),
+                None => write!(f, "Event {}", command),
+            },
+        }
+    }
+}
+
+/// Device an event originates from
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EventOrigin {
+    /// Wiphy identifier
+    pub wiphy: Option<u32>,
+    /// Interface index
+    pub interface_index: Option<u32>,
+    /// Wireless device identifier
+    pub wireless_device: Option<u64>,
+    /// Link identifier of a multi-link device
+    pub link_id: Option<u8>,
+}
+
+impl EventOrigin {
+    /// Decode the origin from the attributes of an event message
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<EventOrigin, Error> {
+        let mut origin = EventOrigin::default();
+        for attr in attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Wiphy) => {
+                    origin.wiphy = Some(attr.as_u32()?);
+                }
+                Some(Attribute::Ifindex) => {
+                    origin.interface_index = Some(attr.as_u32()?);
+                }
+                Some(Attribute::Wdev) => {
+                    origin.wireless_device = Some(attr.as_u64()?);
+                }
+                Some(Attribute::MloLinkId) => {
+                    origin.link_id = Some(attr.as_u8()?);
+                }
+                _ => (),
+            }
+        }
+        Ok(origin)
+    }
+
+    /// Device identifier of the origin, the wireless device identifier is preferred over the
+    /// interface index
+    pub fn device_id(&self) -> WirelessDeviceId {
+        match (self.wireless_device, self.interface_index) {
+            (Some(id), _) => WirelessDeviceId::DeviceIdentifier(id),
+            (None, Some(index)) => WirelessDeviceId::InterfaceIndex(index),
+            (None, None) => WirelessDeviceId::None,
+        }
+    }
+}
+
+impl fmt::Display for EventOrigin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.wiphy {
+            Some(wiphy) => write!(f, "phy#{}", wiphy)?,
+            None => write!(f, "phy#?")?,
+        }
+        if let Some(index) = self.interface_index {
+            write!(f, " if {}", index)?;
+        }
+        if let Some(wdev) = self.wireless_device {
+            write!(f, " wdev 0x{:x}", wdev)?;
+        }
+        if let Some(link_id) = self.link_id {
+            write!(f, " link {}", link_id)?;
+        }
+        Ok(())
+    }
+}
+
+/// Filter on the origin of events
+///
+/// Every criterion given must be present in the origin of an event and be equal, an empty
+/// filter matches all events.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct EventFilter {
+    wiphy: Option<u32>,
+    interface_index: Option<u32>,
+    wireless_device: Option<u64>,
+    link_id: Option<u8>,
+}
+
+impl EventFilter {
+    /// Create filter matching all events
+    pub fn new() -> EventFilter {
+        EventFilter::default()
+    }
+
+    /// Create filter matching the events of the interface
+    ///
+    /// Interfaces without a network device, such as P2P devices, are matched on the wireless
+    /// device identifier.
+    pub fn for_interface(interface: &WirelessInterface) -> EventFilter {
+        let filter = EventFilter::new();
+        match interface.device_id {
+            Some(wdev) if interface.interface_index == 0 => filter.wireless_device(wdev),
+            _ => filter.interface_index(interface.interface_index),
+        }
+    }
+
+    /// Only match events of the wiphy
+    pub fn wiphy(mut self, wiphy: u32) -> Self {
+        self.wiphy = Some(wiphy);
+        self
+    }
+
+    /// Only match events of the interface index
+    pub fn interface_index(mut self, index: u32) -> Self {
+        self.interface_index = Some(index);
+        self
+    }
+
+    /// Only match events of the wireless device
+    pub fn wireless_device(mut self, wdev: u64) -> Self {
+        self.wireless_device = Some(wdev);
+        self
+    }
+
+    /// Only match events of the link of a multi-link device
+    pub fn link_id(mut self, link_id: u8) -> Self {
+        self.link_id = Some(link_id);
+        self
+    }
+
+    /// Check if the origin matches the filter
+    pub fn matches(&self, origin: &EventOrigin) -> bool {
+        fn matches<T: PartialEq>(criterion: Option<T>, value: Option<T>) -> bool {
+            criterion.is_none() || criterion == value
+        }
+        matches(self.wiphy, origin.wiphy)
+            && matches(self.interface_index, origin.interface_index)
+            && matches(self.wireless_device, origin.wireless_device)
+            && matches(self.link_id, origin.link_id)
+    }
+}
+
+/// nl80211 event with its origin
+pub struct Event {
+    /// Device the event originates from
+    pub origin: EventOrigin,
+    /// Event
+    pub event: Nl80211Event,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}] {}", self.origin, self.event)
+    }
+}
+
+/// Listener for nl80211 events of the subscribed multicast groups
+pub struct EventListener {
+    socket: Socket,
+    family_id: u16,
+    filter: EventFilter,
+    events: VecDeque<Result<Event, Error>>,
+}
+
+impl EventListener {
+    /// Create listener subscribed to the multicast groups of the nl80211 family
+    pub fn new(
+        family: &generic::Family,
+        groups: &[MulticastGroup],
+    ) -> Result<EventListener, Error> {
+        let mut socket = Socket::new(Protocol::Generic)?;
+        subscribe(&mut socket, family, groups)?;
+        Ok(EventListener {
+            socket,
+            family_id: family.id,
+            filter: EventFilter::new(),
+            events: VecDeque::new(),
+        })
+    }
+
+    /// Only deliver events with an origin matching the filter
+    pub fn filter(mut self, filter: EventFilter) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Receive the pending events, blocks until messages are received
+    pub fn receive(&mut self) -> Result<Vec<Result<Event, Error>>, Error> {
+        let messages = self.socket.receive_messages()?;
+        Ok(events_from_messages(self.family_id, &self.filter, messages))
+    }
+}
+
+/// Subscribe the socket to the multicast groups of the nl80211 family
+pub(crate) fn subscribe(
+    socket: &mut Socket,
+    family: &generic::Family,
+    groups: &[MulticastGroup],
+) -> Result<(), Error> {
+    for group in groups {
+        let id = family
+            .multicast_groups
+            .iter()
+            .find(|g| g.name == group.name())
+            .map(|g| g.id)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Multicast group not found"))?;
+        socket.multicast_group_subscribe(id)?;
+    }
+    Ok(())
+}
+
+fn event_from_message(
+    message: &generic::Message,
+    filter: &EventFilter,
+) -> Result<Option<Event>, Error> {
+    let origin = EventOrigin::from_attributes(&message.attributes)?;
+    if !filter.matches(&origin) {
+        return Ok(None);
+    }
+    let event = Nl80211Event::from_message(message)?;
+    Ok(Some(Event { origin, event }))
+}
+
+/// Decode the messages of the nl80211 family with an origin matching the filter into events
+pub(crate) fn events_from_messages(
+    family_id: u16,
+    filter: &EventFilter,
+    messages: Vec<netlink::Message>,
+) -> Vec<Result<Event, Error>> {
+    messages
+        .into_iter()
+        .filter(|m| m.header.identifier == family_id)
+        .filter_map(|m| {
+            generic::Message::unpack(&m.data)
+                .and_then(|(_, msg)| event_from_message(&msg, filter))
+                .transpose()
+        })
+        .collect()
+}
+
+impl Iterator for EventListener {
+    type Item = Result<Event, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(event);
+            }
+            match self.receive() {
+                Ok(events) => self.events.extend(events),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+}
+
+impl AsRawFd for EventListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::flag_attribute;
+    use netlink_rust::MessageMode;
+
+    #[test]
+    fn multicast_group_names() {
+        assert_eq!(MulticastGroup::Config.name(), "config");
+        assert_eq!(MulticastGroup::Mlme.name(), "mlme");
+        assert_eq!(MulticastGroup::Testmode.name(), "testmode");
+    }
+
+    #[test]
+    fn event_filter() {
+        let attributes = [
+            netlink::Attribute::new(Attribute::Wiphy, 1u32),
+            netlink::Attribute::new(Attribute::Ifindex, 5u32),
+            netlink::Attribute::new(Attribute::Wdev, 0x1_0000_0001u64),
+        ];
+        let origin = EventOrigin::from_attributes(&attributes).unwrap();
+        assert_eq!(origin.wiphy, Some(1));
+        assert_eq!(origin.interface_index, Some(5));
+        assert_eq!(origin.link_id, None);
+        assert!(origin.device_id() == WirelessDeviceId::DeviceIdentifier(0x1_0000_0001));
+        assert!(EventFilter::new().matches(&origin));
+        assert!(EventFilter::new()
+            .wiphy(1)
+            .interface_index(5)
+            .matches(&origin));
+        assert!(!EventFilter::new().interface_index(6).matches(&origin));
+        assert!(!EventFilter::new().link_id(0).matches(&origin));
+        let origin = EventOrigin::from_attributes(&attributes[..1]).unwrap();
+        assert!(origin.device_id() == WirelessDeviceId::None);
+        assert!(!EventFilter::new().interface_index(5).matches(&origin));
+    }
+
+    fn event_message(command: Command, attributes: Vec<netlink::Attribute>) -> generic::Message {
+        let mut message = generic::Message::new(0u16, command, MessageMode::None);
+        for attribute in attributes {
+            message.append_attribute(attribute);
+        }
+        message
+    }
+
+    #[test]
+    fn connect_event() {
+        let bssid = [2, 0, 0, 0, 0, 1];
+        let message = event_message(
+            Command::Connect,
+            vec![
+                netlink::Attribute::new_bytes(Attribute::Mac, &bssid),
+                netlink::Attribute::new(Attribute::StatusCode, 1u16),
+                flag_attribute(Attribute::TimedOut),
+                netlink::Attribute::new_bytes(Attribute::ReqIe, &[0, 2, b'a', b'p']),
+            ],
+        );
+        let event = match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Connect(event) => event,
+            _ => panic!("Expected connect event"),
+        };
+        assert_eq!(event.bssid, Some(HardwareAddress::from(&bssid[..])));
+        assert_eq!(event.status_code, 1);
+        assert!(event.timed_out);
+        assert_eq!(event.request_ies, vec![0, 2, b'a', b'p']);
+        assert!(event.response_ies.is_empty());
+        assert_eq!(
+            format!("{}", event),
+            format!("Connect {} Status: 1 Timed Out", event.bssid.unwrap())
+        );
+
+        let message = event_message(
+            Command::Connect,
+            vec![netlink::Attribute::new(Attribute::StatusCode, 0u16)],
+        );
+        match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Connect(event) => {
+                assert_eq!(event.status_code, 0);
+                assert!(!event.timed_out);
+                assert_eq!(event.bssid, None);
+            }
+            _ => panic!("Expected connect event"),
+        }
+        let message = event_message(Command::Connect, vec![]);
+        assert!(Nl80211Event::from_message(&message).is_err());
+    }
+
+    #[test]
+    fn disconnect_event() {
+        let message = event_message(
+            Command::Disconnect,
+            vec![
+                netlink::Attribute::new(Attribute::ReasonCode, 3u16),
+                flag_attribute(Attribute::DisconnectedByAp),
+            ],
+        );
+        let event = match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Disconnect(event) => event,
+            _ => panic!("Expected disconnect event"),
+        };
+        assert_eq!(event.reason_code, Some(3));
+        assert!(event.by_ap);
+        assert_eq!(format!("{}", event), "Disconnect Reason: 3 By AP");
+
+        let message = event_message(Command::Disconnect, vec![]);
+        match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Disconnect(event) => {
+                assert_eq!(event.reason_code, None);
+                assert!(!event.by_ap);
+            }
+            _ => panic!("Expected disconnect event"),
+        }
+    }
+
+    #[test]
+    fn vendor_event() {
+        let message = event_message(
+            Command::Vendor,
+            vec![
+                netlink::Attribute::new(Attribute::VendorId, 0x001374u32),
+                netlink::Attribute::new(Attribute::VendorSubcmd, 7u32),
+                netlink::Attribute::new_bytes(Attribute::VendorData, &[1, 2, 3]),
+            ],
+        );
+        match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Vendor {
+                vendor_id,
+                subcommand,
+                data,
+            } => {
+                assert_eq!(vendor_id, 0x001374);
+                assert_eq!(subcommand, 7);
+                assert_eq!(data, vec![1, 2, 3]);
+            }
+            _ => panic!("Expected vendor event"),
+        }
+        let message = event_message(
+            Command::Vendor,
+            vec![netlink::Attribute::new(Attribute::VendorId, 0x001374u32)],
+        );
+        assert!(Nl80211Event::from_message(&message).is_err());
+        let message = event_message(
+            Command::Vendor,
+            vec![netlink::Attribute::new(Attribute::VendorSubcmd, 7u32)],
+        );
+        assert!(Nl80211Event::from_message(&message).is_err());
+    }
+
+    #[test]
+    fn other_event() {
+        let mut message = event_message(Command::GetWiphy, vec![]);
+        match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Other { command } => assert_eq!(command, u8::from(Command::GetWiphy)),
+            _ => panic!("Expected other event"),
+        }
+        message.command = 0xfe;
+        assert_eq!(Command::convert_from(message.command), None);
+        match Nl80211Event::from_message(&message).unwrap() {
+            Nl80211Event::Other { command } => assert_eq!(command, 0xfe),
+            _ => panic!("Expected other event"),
+        }
+    }
+}
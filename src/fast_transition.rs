@@ -0,0 +1,231 @@
+//! ## Fast BSS Transition
+//!
+//! Fast BSS transition (802.11r) information element updates and events for drivers with
+//! station management (SME) in user space.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::Attribute;
+use crate::commands::Command;
+use crate::information_element::{
+    AuthenticationKeyManagement, FastBssTransition, InformationElements, MobilityDomain,
+};
+use crate::information_element_ids::InformationElementId;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust::generic;
+use netlink_rust::{Attribute as NetlinkAttribute, ConvertFrom, Error, HardwareAddress};
+use netlink_rust::{MessageMode, Socket};
+
+impl WirelessInterface {
+    /// Update the fast BSS transition information elements used by the driver
+    ///
+    /// The information elements should include the mobility domain (MDE) and fast BSS
+    /// transition (FTE) elements for the next reassociation.
+    pub fn update_fast_transition_ies(
+        &self,
+        socket: &mut Socket,
+        mobility_domain: u16,
+        ies: &[u8],
+    ) -> Result<(), Error> {
+        let mut msg = self.prepare_message(
+            Command::UpdateFastTransitionInformationElements,
+            MessageMode::Acknowledge,
+        )?;
+        for attr in fast_transition_ies_attributes(mobility_domain, ies)? {
+            msg.append_attribute(attr);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Attributes for updating the fast BSS transition information elements, the mobility
+/// domain element of the information elements must match the mobility domain
+fn fast_transition_ies_attributes(
+    mobility_domain: u16,
+    ies: &[u8],
+) -> Result<Vec<NetlinkAttribute>, Error> {
+    let mde = InformationElements::parse(ies)
+        .elements
+        .iter()
+        .filter(|ie| ie.ie_id() == Some(InformationElementId::MobilityDomain))
+        .find_map(|ie| MobilityDomain::parse(ie.data).ok());
+    match mde {
+        Some(ref mde) if mde.identifier == mobility_domain => (),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Mobility domain element does not match",
+            )
+            .into());
+        }
+    }
+    Ok(vec![
+        NetlinkAttribute::new(Attribute::Mdid, mobility_domain),
+        NetlinkAttribute::new_bytes(Attribute::InformationElement, ies),
+    ])
+}
+
+/// Fast BSS transition event, sent when the driver has received the FT authentication
+/// response from the target access point
+pub struct FastTransitionEvent {
+    /// BSSID of the target access point
+    pub target_ap: HardwareAddress,
+    /// Information elements of the authentication response
+    pub ies: Vec<u8>,
+    /// Resource information container (RIC) information elements
+    pub ric_ies: Vec<u8>,
+}
+
+impl FastTransitionEvent {
+    /// Decode a FastTransitionEvent message
+    pub fn from_message(message: &generic::Message) -> Result<FastTransitionEvent, Error> {
+        let mut target_ap = None;
+        let mut ies = vec![];
+        let mut ric_ies = vec![];
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Mac) => {
+                    target_ap = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::InformationElement) => {
+                    ies = attr.as_bytes();
+                }
+                Some(Attribute::IeRic) => {
+                    ric_ies = attr.as_bytes();
+                }
+                _ => (),
+            }
+        }
+        let target_ap = target_ap
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing target AP"))?;
+        Ok(FastTransitionEvent {
+            target_ap,
+            ies,
+            ric_ies,
+        })
+    }
+
+    /// Information elements of the authentication response
+    pub fn information_elements(&self) -> InformationElements<'_> {
+        InformationElements::parse(&self.ies)
+    }
+
+    /// Mobility domain element of the authentication response
+    pub fn mobility_domain(&self) -> Option<MobilityDomain> {
+        self.information_elements()
+            .elements
+            .iter()
+            .filter(|ie| ie.ie_id() == Some(InformationElementId::MobilityDomain))
+            .find_map(|ie| MobilityDomain::parse(ie.data).ok())
+    }
+
+    /// Fast BSS transition element of the authentication response, parsed with the MIC
+    /// length of the AKM in use
+    pub fn fast_bss_transition(
+        &self,
+        akm: &AuthenticationKeyManagement,
+    ) -> Option<FastBssTransition> {
+        let mic_length = FastBssTransition::mic_length(akm);
+        self.information_elements()
+            .elements
+            .iter()
+            .filter(|ie| ie.ie_id() == Some(InformationElementId::FastBssTransition))
+            .find_map(|ie| FastBssTransition::parse(ie.data, mic_length).ok())
+    }
+}
+
+impl fmt::Display for FastTransitionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fast Transition to {}", self.target_ap)?;
+        if let Some(mde) = self.mobility_domain() {
+            write!(f, " {}", mde)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::information_element::FastTransitionCapability;
+
+    fn information_elements(mic_length: usize) -> Vec<u8> {
+        let mde = MobilityDomain {
+            identifier: 0x1234,
+            capability: FastTransitionCapability::OVER_DISTRIBUTION_SYSTEM,
+        };
+        let mut fte = FastBssTransition::new([0x5a; 32], b"r0kh");
+        fte.mic = vec![0; mic_length];
+        fte.anonce = [0xa5; 32];
+        fte.r1kh_id = Some(HardwareAddress::from(&[2, 0, 0, 0, 0, 2][..]));
+        let mut ies = mde.pack();
+        ies.extend(fte.pack());
+        ies
+    }
+
+    #[test]
+    fn fast_transition_event() {
+        let target_ap = [2, 0, 0, 0, 0, 1];
+        let ies = information_elements(24);
+        let mut message =
+            generic::Message::new(0u16, Command::FastTransitionEvent, MessageMode::Acknowledge);
+        message.append_attribute(NetlinkAttribute::new_bytes(Attribute::Mac, &target_ap));
+        message.append_attribute(NetlinkAttribute::new_bytes(
+            Attribute::InformationElement,
+            &ies,
+        ));
+        message.append_attribute(NetlinkAttribute::new_bytes(Attribute::IeRic, &[57, 0]));
+        let event = FastTransitionEvent::from_message(&message).unwrap();
+        assert_eq!(event.target_ap, HardwareAddress::from(&target_ap[..]));
+        assert_eq!(event.ies, ies);
+        assert_eq!(event.ric_ies, vec![57, 0]);
+        assert_eq!(event.mobility_domain().unwrap().identifier, 0x1234);
+
+        let akm = AuthenticationKeyManagement::from(0x13ac_0f00);
+        let fte = event.fast_bss_transition(&akm).unwrap();
+        assert_eq!(fte.mic, vec![0; 24]);
+        assert_eq!(fte.anonce, [0xa5; 32]);
+        assert_eq!(fte.snonce, [0x5a; 32]);
+        assert_eq!(fte.r0kh_id, Some(b"r0kh".to_vec()));
+
+        let message =
+            generic::Message::new(0u16, Command::FastTransitionEvent, MessageMode::Acknowledge);
+        assert!(FastTransitionEvent::from_message(&message).is_err());
+    }
+
+    #[test]
+    fn update_fast_transition_ies() {
+        let ies = information_elements(16);
+        let attributes = fast_transition_ies_attributes(0x1234, &ies).unwrap();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(
+            Attribute::convert_from(attributes[0].identifier),
+            Some(Attribute::Mdid)
+        );
+        assert_eq!(attributes[0].as_u16().unwrap(), 0x1234);
+        assert_eq!(
+            Attribute::convert_from(attributes[1].identifier),
+            Some(Attribute::InformationElement)
+        );
+        let bytes = attributes[1].as_bytes();
+        let ies = InformationElements::parse(&bytes);
+        assert_eq!(ies.elements.len(), 2);
+        let fte = FastBssTransition::parse(ies.elements[1].data, 16).unwrap();
+        assert_eq!(
+            fte.r1kh_id,
+            Some(HardwareAddress::from(&[2, 0, 0, 0, 0, 2][..]))
+        );
+
+        assert!(fast_transition_ies_attributes(0x4321, &bytes).is_err());
+        assert!(fast_transition_ies_attributes(0x1234, &[]).is_err());
+    }
+}
@@ -2,10 +2,20 @@ use std::convert::From;
 use std::fmt;
 use std::io;
 
+use byteorder::{BigEndian, ByteOrder};
+
 use netlink_rust::{HardwareAddress, Result};
 
+use crate::information_element::InformationElements;
 use crate::unpack::LittleUnpack;
 
+fn truncated(name: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("Truncated {} frame body", name),
+    )
+}
+
 #[derive(Debug, PartialEq)]
 pub enum FrameType {
     Management,
@@ -61,6 +71,54 @@ pub enum FrameSubtype {
     NullQualityOfServiceContentionFreeAcknowledgePoll,
 }
 
+impl FrameSubtype {
+    /// Frame control field of a management frame of the subtype, none if not a management
+    /// frame subtype
+    pub fn management_frame_control(&self) -> Option<u16> {
+        let subtype: u16 = match *self {
+            FrameSubtype::AssociationRequest => 0b0000,
+            FrameSubtype::AssociationResponse => 0b0001,
+            FrameSubtype::ReassociationRequest => 0b0010,
+            FrameSubtype::ReassociationResponse => 0b0011,
+            FrameSubtype::ProbeRequest => 0b0100,
+            FrameSubtype::ProbeResponse => 0b0101,
+            FrameSubtype::TimingAdvertisment => 0b0110,
+            FrameSubtype::Beacon => 0b1000,
+            FrameSubtype::AnnouncementTrafficIndication => 0b1001,
+            FrameSubtype::Disassociation => 0b1010,
+            FrameSubtype::Authentication => 0b1011,
+            FrameSubtype::Deauthentication => 0b1100,
+            FrameSubtype::Action => 0b1101,
+            FrameSubtype::ActionNoAcknowledge => 0b1110,
+            _ => return None,
+        };
+        Some(subtype << 4)
+    }
+}
+
+bitflags! {
+    /// Capability information field of beacon, probe response and association frames
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct CapabilityInformation: u16 {
+        /// Transmitted by an access point
+        const ESS = 0x0001;
+        /// Transmitted by a station in an independent BSS
+        const IBSS = 0x0002;
+        const CF_POLLABLE = 0x0004;
+        const CF_POLL_REQUEST = 0x0008;
+        /// Data confidentiality is required
+        const PRIVACY = 0x0010;
+        const SHORT_PREAMBLE = 0x0020;
+        const SPECTRUM_MANAGEMENT = 0x0100;
+        const QOS = 0x0200;
+        const SHORT_SLOT_TIME = 0x0400;
+        const APSD = 0x0800;
+        const RADIO_MEASUREMENT = 0x1000;
+        const DELAYED_BLOCK_ACK = 0x4000;
+        const IMMEDIATE_BLOCK_ACK = 0x8000;
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct FrameControl {
     field: u16,
@@ -207,6 +265,423 @@ impl From<u16> for FrameSequence {
     }
 }
 
+/// Beacon and probe response frame body
+#[derive(Debug, PartialEq)]
+pub struct BeaconBody {
+    /// Timer synchronization function (TSF) timer value in microseconds
+    pub timestamp: u64,
+    /// Beacon interval in time units (1024 microseconds)
+    pub beacon_interval: u16,
+    /// Capability information
+    pub capability: CapabilityInformation,
+    /// Information elements
+    pub elements: Vec<u8>,
+}
+
+impl BeaconBody {
+    fn unpack(buffer: &[u8]) -> Result<Self> {
+        if buffer.len() < 12 {
+            return Err(truncated("beacon").into());
+        }
+        Ok(BeaconBody {
+            timestamp: u64::unpack_unchecked(buffer),
+            beacon_interval: u16::unpack_unchecked(&buffer[8..]),
+            capability: CapabilityInformation::from_bits_retain(u16::unpack_unchecked(
+                &buffer[10..],
+            )),
+            elements: buffer[12..].to_vec(),
+        })
+    }
+}
+
+/// Simultaneous authentication of equals (SAE) fields of an authentication frame
+#[derive(Debug, PartialEq)]
+pub enum SaeFields {
+    /// Commit message, scalar and element are empty for groups not known by this crate and
+    /// for commit messages with a failure status
+    Commit {
+        /// Finite cyclic group
+        group: u16,
+        /// Anti-clogging token, either requested by an anti-clogging token request or
+        /// repeated in a commit message without hash-to-element. With hash-to-element the
+        /// token is in a container element in the remainder.
+        token: Vec<u8>,
+        /// Scalar
+        scalar: Vec<u8>,
+        /// Finite field element
+        element: Vec<u8>,
+        /// Remaining fields, password identifier, rejected groups and anti-clogging token
+        /// container elements
+        remainder: Vec<u8>,
+    },
+    /// Confirm message
+    Confirm {
+        /// Send confirm counter
+        send_confirm: u16,
+        /// Confirm
+        confirm: Vec<u8>,
+    },
+}
+
+impl SaeFields {
+    // Minimum length of an anti-clogging token, the length of a SHA-256 MAC, as in hostapd
+    const MINIMUM_TOKEN_LENGTH: usize = 32;
+
+    // Scalar length in octets of the elliptic curve groups
+    fn scalar_length(group: u16) -> Option<usize> {
+        match group {
+            19 => Some(32),
+            20 => Some(48),
+            21 => Some(66),
+            _ => None,
+        }
+    }
+
+    // SAE fields are only present in full with a success status, frames with other status
+    // codes may have no SAE fields at all
+    fn unpack(sequence: u16, status: u16, buffer: &[u8]) -> Result<Option<Self>> {
+        let complete = match status {
+            AuthenticationBody::SUCCESS | AuthenticationBody::SAE_HASH_TO_ELEMENT => true,
+            _ if buffer.len() < 2 => return Ok(None),
+            _ => false,
+        };
+        if buffer.len() < 2 {
+            return Err(truncated("SAE").into());
+        }
+        let value = u16::unpack_unchecked(buffer);
+        let data = &buffer[2..];
+        if sequence != 1 {
+            return Ok(Some(SaeFields::Confirm {
+                send_confirm: value,
+                confirm: data.to_vec(),
+            }));
+        }
+        let length = match SaeFields::scalar_length(value) {
+            Some(length) if complete => length,
+            _ => {
+                let token = if status == AuthenticationBody::ANTI_CLOGGING_TOKEN_REQUIRED {
+                    data.to_vec()
+                } else {
+                    vec![]
+                };
+                let remainder = if token.is_empty() {
+                    data.to_vec()
+                } else {
+                    vec![]
+                };
+                return Ok(Some(SaeFields::Commit {
+                    group: value,
+                    token,
+                    scalar: vec![],
+                    element: vec![],
+                    remainder,
+                }));
+            }
+        };
+        if data.len() < length * 3 {
+            return Err(truncated("SAE commit").into());
+        }
+        // Without hash-to-element a repeated anti-clogging token precedes the scalar. Like
+        // hostapd, any data beyond scalar and element long enough to be a token is taken as
+        // token.
+        let extra = data.len() - length * 3;
+        let token_length =
+            if status == AuthenticationBody::SUCCESS && extra >= SaeFields::MINIMUM_TOKEN_LENGTH {
+                extra
+            } else {
+                0
+            };
+        let (token, data) = data.split_at(token_length);
+        Ok(Some(SaeFields::Commit {
+            group: value,
+            token: token.to_vec(),
+            scalar: data[..length].to_vec(),
+            element: data[length..length * 3].to_vec(),
+            remainder: data[length * 3..].to_vec(),
+        }))
+    }
+}
+
+/// Authentication frame body
+#[derive(Debug, PartialEq)]
+pub struct AuthenticationBody {
+    /// Authentication algorithm number
+    pub algorithm: u16,
+    /// Authentication transaction sequence number
+    pub sequence: u16,
+    /// Status code
+    pub status_code: u16,
+    /// SAE fields when using SAE authentication, none for frames with a failure status
+    /// without SAE fields
+    pub sae: Option<SaeFields>,
+    /// Information elements, such as the challenge text or fast BSS transition elements
+    pub elements: Vec<u8>,
+}
+
+impl AuthenticationBody {
+    /// Open system authentication algorithm
+    pub const OPEN_SYSTEM: u16 = 0;
+    /// Shared key authentication algorithm
+    pub const SHARED_KEY: u16 = 1;
+    /// Fast BSS transition authentication algorithm
+    pub const FAST_BSS_TRANSITION: u16 = 2;
+    /// Simultaneous authentication of equals (SAE) authentication algorithm
+    pub const SAE: u16 = 3;
+
+    const SUCCESS: u16 = 0;
+    const ANTI_CLOGGING_TOKEN_REQUIRED: u16 = 76;
+    const SAE_HASH_TO_ELEMENT: u16 = 126;
+
+    fn unpack(buffer: &[u8]) -> Result<Self> {
+        if buffer.len() < 6 {
+            return Err(truncated("authentication").into());
+        }
+        let algorithm = u16::unpack_unchecked(buffer);
+        let sequence = u16::unpack_unchecked(&buffer[2..]);
+        let status_code = u16::unpack_unchecked(&buffer[4..]);
+        let (sae, elements) = if algorithm == AuthenticationBody::SAE {
+            let sae = SaeFields::unpack(sequence, status_code, &buffer[6..])?;
+            (sae, vec![])
+        } else {
+            (None, buffer[6..].to_vec())
+        };
+        Ok(AuthenticationBody {
+            algorithm,
+            sequence,
+            status_code,
+            sae,
+            elements,
+        })
+    }
+}
+
+/// Association and reassociation request frame body
+#[derive(Debug, PartialEq)]
+pub struct AssociationRequestBody {
+    /// Capability information
+    pub capability: CapabilityInformation,
+    /// Listen interval in beacon intervals
+    pub listen_interval: u16,
+    /// Address of the access point currently associated with, only in reassociation requests
+    pub current_access_point: Option<HardwareAddress>,
+    /// Information elements
+    pub elements: Vec<u8>,
+}
+
+impl AssociationRequestBody {
+    fn unpack(buffer: &[u8], reassociation: bool) -> Result<Self> {
+        let length = if reassociation { 10 } else { 4 };
+        if buffer.len() < length {
+            return Err(truncated("association request").into());
+        }
+        let current_access_point = if reassociation {
+            Some(HardwareAddress::unpack_unchecked(&buffer[4..]))
+        } else {
+            None
+        };
+        Ok(AssociationRequestBody {
+            capability: CapabilityInformation::from_bits_retain(u16::unpack_unchecked(buffer)),
+            listen_interval: u16::unpack_unchecked(&buffer[2..]),
+            current_access_point,
+            elements: buffer[length..].to_vec(),
+        })
+    }
+}
+
+/// Association and reassociation response frame body
+#[derive(Debug, PartialEq)]
+pub struct AssociationResponseBody {
+    /// Capability information
+    pub capability: CapabilityInformation,
+    /// Status code
+    pub status_code: u16,
+    /// Association identifier
+    pub association_id: u16,
+    /// Information elements
+    pub elements: Vec<u8>,
+}
+
+impl AssociationResponseBody {
+    fn unpack(buffer: &[u8]) -> Result<Self> {
+        if buffer.len() < 6 {
+            return Err(truncated("association response").into());
+        }
+        Ok(AssociationResponseBody {
+            capability: CapabilityInformation::from_bits_retain(u16::unpack_unchecked(buffer)),
+            status_code: u16::unpack_unchecked(&buffer[2..]),
+            association_id: u16::unpack_unchecked(&buffer[4..]) & 0x3fff,
+            elements: buffer[6..].to_vec(),
+        })
+    }
+}
+
+/// Action frame body
+#[derive(Debug, PartialEq)]
+pub struct ActionBody {
+    /// Action category
+    pub category: u8,
+    /// Category specific action details
+    pub data: Vec<u8>,
+}
+
+impl ActionBody {
+    /// Protected vendor specific action category
+    pub const VENDOR_SPECIFIC_PROTECTED: u8 = 126;
+    /// Vendor specific action category
+    pub const VENDOR_SPECIFIC: u8 = 127;
+
+    fn unpack(buffer: &[u8]) -> Result<Self> {
+        if buffer.is_empty() {
+            return Err(truncated("action").into());
+        }
+        Ok(ActionBody {
+            category: buffer[0] & 0x7f,
+            data: buffer[1..].to_vec(),
+        })
+    }
+
+    /// Action within the category, none for vendor specific actions
+    pub fn action(&self) -> Option<u8> {
+        match self.category {
+            ActionBody::VENDOR_SPECIFIC | ActionBody::VENDOR_SPECIFIC_PROTECTED => None,
+            _ => self.data.first().cloned(),
+        }
+    }
+}
+
+/// Management frame body
+#[derive(Debug, PartialEq)]
+pub enum ManagementFrameBody {
+    /// Beacon
+    Beacon(BeaconBody),
+    /// Probe response
+    ProbeResponse(BeaconBody),
+    /// Probe request, only carrying information elements
+    ProbeRequest(Vec<u8>),
+    /// Authentication
+    Authentication(AuthenticationBody),
+    /// Association request
+    AssociationRequest(AssociationRequestBody),
+    /// Reassociation request
+    ReassociationRequest(AssociationRequestBody),
+    /// Association response
+    AssociationResponse(AssociationResponseBody),
+    /// Reassociation response
+    ReassociationResponse(AssociationResponseBody),
+    /// Deauthentication with reason code
+    Deauthentication(u16),
+    /// Disassociation with reason code
+    Disassociation(u16),
+    /// Action
+    Action(ActionBody),
+    /// Action not to be acknowledged
+    ActionNoAcknowledge(ActionBody),
+    /// Body of other subtypes or protected body, not parsed
+    Other(Vec<u8>),
+}
+
+impl ManagementFrameBody {
+    fn unpack(control: &FrameControl, buffer: &[u8]) -> Result<Self> {
+        // Protected bodies are encrypted as a whole, including the fixed fields of the shared
+        // key authentication frame carrying the challenge text
+        if control.get_protected() {
+            return Ok(ManagementFrameBody::Other(buffer.to_vec()));
+        }
+        let subtype = control.get_subtype();
+        let reason_code = |name| {
+            if buffer.len() < 2 {
+                return Err(truncated(name));
+            }
+            Ok(u16::unpack_unchecked(buffer))
+        };
+        let body = match subtype {
+            FrameSubtype::Beacon => ManagementFrameBody::Beacon(BeaconBody::unpack(buffer)?),
+            FrameSubtype::ProbeResponse => {
+                ManagementFrameBody::ProbeResponse(BeaconBody::unpack(buffer)?)
+            }
+            FrameSubtype::ProbeRequest => ManagementFrameBody::ProbeRequest(buffer.to_vec()),
+            FrameSubtype::Authentication => {
+                ManagementFrameBody::Authentication(AuthenticationBody::unpack(buffer)?)
+            }
+            FrameSubtype::AssociationRequest => ManagementFrameBody::AssociationRequest(
+                AssociationRequestBody::unpack(buffer, false)?,
+            ),
+            FrameSubtype::ReassociationRequest => ManagementFrameBody::ReassociationRequest(
+                AssociationRequestBody::unpack(buffer, true)?,
+            ),
+            FrameSubtype::AssociationResponse => {
+                ManagementFrameBody::AssociationResponse(AssociationResponseBody::unpack(buffer)?)
+            }
+            FrameSubtype::ReassociationResponse => {
+                ManagementFrameBody::ReassociationResponse(AssociationResponseBody::unpack(buffer)?)
+            }
+            FrameSubtype::Deauthentication => {
+                ManagementFrameBody::Deauthentication(reason_code("deauthentication")?)
+            }
+            FrameSubtype::Disassociation => {
+                ManagementFrameBody::Disassociation(reason_code("disassociation")?)
+            }
+            FrameSubtype::Action => ManagementFrameBody::Action(ActionBody::unpack(buffer)?),
+            FrameSubtype::ActionNoAcknowledge => {
+                ManagementFrameBody::ActionNoAcknowledge(ActionBody::unpack(buffer)?)
+            }
+            _ => ManagementFrameBody::Other(buffer.to_vec()),
+        };
+        Ok(body)
+    }
+
+    /// Information elements of the body, empty for bodies without information elements
+    pub fn information_elements(&self) -> InformationElements<'_> {
+        let elements: &[u8] = match *self {
+            ManagementFrameBody::Beacon(ref body)
+            | ManagementFrameBody::ProbeResponse(ref body) => &body.elements,
+            ManagementFrameBody::ProbeRequest(ref elements) => elements,
+            ManagementFrameBody::Authentication(ref body) => &body.elements,
+            ManagementFrameBody::AssociationRequest(ref body)
+            | ManagementFrameBody::ReassociationRequest(ref body) => &body.elements,
+            ManagementFrameBody::AssociationResponse(ref body)
+            | ManagementFrameBody::ReassociationResponse(ref body) => &body.elements,
+            _ => &[],
+        };
+        InformationElements::parse(elements)
+    }
+}
+
+impl fmt::Display for ManagementFrameBody {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ManagementFrameBody::Beacon(ref body)
+            | ManagementFrameBody::ProbeResponse(ref body) => {
+                write!(
+                    f,
+                    "Interval {} Capability {:?}",
+                    body.beacon_interval, body.capability
+                )
+            }
+            ManagementFrameBody::Authentication(ref body) => write!(
+                f,
+                "Algorithm {} Sequence {} Status {}",
+                body.algorithm, body.sequence, body.status_code
+            ),
+            ManagementFrameBody::AssociationRequest(ref body)
+            | ManagementFrameBody::ReassociationRequest(ref body) => {
+                write!(f, "Listen Interval {}", body.listen_interval)
+            }
+            ManagementFrameBody::AssociationResponse(ref body)
+            | ManagementFrameBody::ReassociationResponse(ref body) => {
+                write!(f, "Status {} AID {}", body.status_code, body.association_id)
+            }
+            ManagementFrameBody::Deauthentication(reason)
+            | ManagementFrameBody::Disassociation(reason) => write!(f, "Reason {}", reason),
+            ManagementFrameBody::Action(ref body)
+            | ManagementFrameBody::ActionNoAcknowledge(ref body) => {
+                write!(f, "Category {}", body.category)
+            }
+            ManagementFrameBody::ProbeRequest(_) | ManagementFrameBody::Other(_) => Ok(()),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ManagementFrame {
     control: FrameControl,
@@ -216,13 +691,14 @@ pub struct ManagementFrame {
     address3: HardwareAddress,
     sequence: FrameSequence,
     high_throughput_control: Option<u32>,
+    body: ManagementFrameBody,
 }
 
 impl ManagementFrame {
     fn unpack(control: FrameControl, duration: FrameDuration, buffer: &[u8]) -> Result<Self> {
         let order = control.get_order();
         let length = if order { 24 } else { 20 };
-        if buffer.len() > length {
+        if buffer.len() >= length {
             let a1 = HardwareAddress::unpack_unchecked(&buffer[..]);
             let a2 = HardwareAddress::unpack_unchecked(&buffer[6..]);
             let a3 = HardwareAddress::unpack_unchecked(&buffer[12..]);
@@ -232,6 +708,7 @@ impl ManagementFrame {
             } else {
                 None
             };
+            let body = ManagementFrameBody::unpack(&control, &buffer[length..])?;
             return Ok(ManagementFrame {
                 control,
                 duration,
@@ -240,10 +717,36 @@ impl ManagementFrame {
                 address3: a3,
                 sequence,
                 high_throughput_control: htc,
+                body,
             });
         }
         Err(io::Error::new(io::ErrorKind::InvalidData, "").into())
     }
+
+    /// Frame subtype
+    pub fn subtype(&self) -> FrameSubtype {
+        self.control.get_subtype()
+    }
+
+    /// Destination address
+    pub fn destination(&self) -> HardwareAddress {
+        self.address1
+    }
+
+    /// Source address
+    pub fn source(&self) -> HardwareAddress {
+        self.address2
+    }
+
+    /// BSS identifier
+    pub fn bssid(&self) -> HardwareAddress {
+        self.address3
+    }
+
+    /// Frame body
+    pub fn body(&self) -> &ManagementFrameBody {
+        &self.body
+    }
 }
 
 impl fmt::Display for ManagementFrame {
@@ -252,7 +755,12 @@ impl fmt::Display for ManagementFrame {
             f,
             "{} {} {} {}",
             self.control, self.address1, self.address2, self.address3
-        )
+        )?;
+        let body = self.body.to_string();
+        if !body.is_empty() {
+            write!(f, " {}", body)?;
+        }
+        Ok(())
     }
 }
 
@@ -283,6 +791,33 @@ impl ControlFrame {
     }
 }
 
+/// Logical link control (LLC) header with subnetwork access protocol (SNAP) extension
+#[derive(Debug, PartialEq)]
+pub struct LlcSnap<'a> {
+    /// Organizationally unique identifier, zero for encapsulated Ethernet frames
+    pub oui: [u8; 3],
+    /// Protocol identifier, the EtherType for encapsulated Ethernet frames
+    pub ethertype: u16,
+    /// Payload following the header
+    pub payload: &'a [u8],
+}
+
+impl<'a> LlcSnap<'a> {
+    const HEADER: [u8; 3] = [0xaa, 0xaa, 0x03];
+
+    /// Decode LLC/SNAP header, none if the data does not start with a SNAP header
+    pub fn parse(data: &'a [u8]) -> Option<LlcSnap<'a>> {
+        if data.len() < 8 || data[..3] != LlcSnap::HEADER {
+            return None;
+        }
+        Some(LlcSnap {
+            oui: [data[3], data[4], data[5]],
+            ethertype: BigEndian::read_u16(&data[6..]),
+            payload: &data[8..],
+        })
+    }
+}
+
 #[derive(Debug, PartialEq)]
 pub struct DataFrame {
     control: FrameControl,
@@ -291,40 +826,158 @@ pub struct DataFrame {
     address2: HardwareAddress,
     address3: HardwareAddress,
     sequence: FrameSequence,
-    address4: HardwareAddress,
-    quality_of_service_control: u16,
-    high_throughput_control: u32,
+    address4: Option<HardwareAddress>,
+    quality_of_service_control: Option<u16>,
+    high_throughput_control: Option<u32>,
+    payload: Vec<u8>,
 }
 
 impl fmt::Display for DataFrame {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{} {}", self.control, self.address1)
+        write!(
+            f,
+            "{} {} -> {}",
+            self.control,
+            self.source(),
+            self.destination()
+        )
     }
 }
 
 impl DataFrame {
+    // Subtypes with the QoS control field
+    fn has_quality_of_service(control: &FrameControl) -> bool {
+        control.field & 0x0080 == 0x0080
+    }
+
+    // Subtypes without frame body
+    fn is_null(control: &FrameControl) -> bool {
+        control.field & 0x0040 == 0x0040
+    }
+
     fn unpack(control: FrameControl, duration: FrameDuration, buffer: &[u8]) -> Result<Self> {
-        if buffer.len() > 32 {
-            let a1 = HardwareAddress::unpack_unchecked(&buffer[..]);
-            let a2 = HardwareAddress::unpack_unchecked(&buffer[6..]);
-            let a3 = HardwareAddress::unpack_unchecked(&buffer[12..]);
-            let sequence = u16::unpack_unchecked(&buffer[18..]);
-            let a4 = HardwareAddress::unpack_unchecked(&buffer[20..]);
-            let qos = u16::unpack_unchecked(&buffer[26..]);
-            let ht = u32::unpack_unchecked(&buffer[28..]);
-            return Ok(DataFrame {
-                control,
-                duration,
-                address1: a1,
-                address2: a2,
-                address3: a3,
-                sequence: FrameSequence::from(sequence),
-                address4: a4,
-                quality_of_service_control: qos,
-                high_throughput_control: ht,
-            });
+        let four_address = control.get_to_ds() && control.get_from_ds();
+        let qos = DataFrame::has_quality_of_service(&control);
+        // The order bit signals a HT control field in QoS data frames only
+        let htc = qos && control.get_order();
+        let mut length = 20;
+        let a4_offset = length;
+        if four_address {
+            length += 6;
         }
-        Err(io::Error::new(io::ErrorKind::InvalidData, "").into())
+        let qos_offset = length;
+        if qos {
+            length += 2;
+        }
+        let htc_offset = length;
+        if htc {
+            length += 4;
+        }
+        if buffer.len() < length {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Truncated data frame").into());
+        }
+        let payload = if DataFrame::is_null(&control) {
+            vec![]
+        } else {
+            buffer[length..].to_vec()
+        };
+        Ok(DataFrame {
+            address1: HardwareAddress::unpack_unchecked(buffer),
+            address2: HardwareAddress::unpack_unchecked(&buffer[6..]),
+            address3: HardwareAddress::unpack_unchecked(&buffer[12..]),
+            sequence: FrameSequence::from(u16::unpack_unchecked(&buffer[18..])),
+            address4: if four_address {
+                Some(HardwareAddress::unpack_unchecked(&buffer[a4_offset..]))
+            } else {
+                None
+            },
+            quality_of_service_control: if qos {
+                Some(u16::unpack_unchecked(&buffer[qos_offset..]))
+            } else {
+                None
+            },
+            high_throughput_control: if htc {
+                Some(u32::unpack_unchecked(&buffer[htc_offset..]))
+            } else {
+                None
+            },
+            control,
+            duration,
+            payload,
+        })
+    }
+
+    /// Receiver address
+    pub fn receiver(&self) -> HardwareAddress {
+        self.address1
+    }
+
+    /// Transmitter address
+    pub fn transmitter(&self) -> HardwareAddress {
+        self.address2
+    }
+
+    /// Destination address
+    pub fn destination(&self) -> HardwareAddress {
+        if self.control.get_to_ds() {
+            self.address3
+        } else {
+            self.address1
+        }
+    }
+
+    /// Source address
+    pub fn source(&self) -> HardwareAddress {
+        match (self.control.get_to_ds(), self.control.get_from_ds()) {
+            (true, true) => self.address4.unwrap_or(self.address2),
+            (false, true) => self.address3,
+            _ => self.address2,
+        }
+    }
+
+    /// BSS identifier, none for frames within the distribution system
+    pub fn bssid(&self) -> Option<HardwareAddress> {
+        match (self.control.get_to_ds(), self.control.get_from_ds()) {
+            (false, false) => Some(self.address3),
+            (false, true) => Some(self.address2),
+            (true, false) => Some(self.address1),
+            (true, true) => None,
+        }
+    }
+
+    /// QoS control field, only present in QoS data frames
+    pub fn quality_of_service_control(&self) -> Option<u16> {
+        self.quality_of_service_control
+    }
+
+    /// Traffic identifier of QoS data frames
+    pub fn traffic_identifier(&self) -> Option<u8> {
+        self.quality_of_service_control
+            .map(|qos| (qos & 0x000f) as u8)
+    }
+
+    /// The payload is an aggregate MAC service data unit (A-MSDU)
+    pub fn is_aggregate(&self) -> bool {
+        self.quality_of_service_control.unwrap_or(0) & 0x0080 == 0x0080
+    }
+
+    /// HT control field
+    pub fn high_throughput_control(&self) -> Option<u32> {
+        self.high_throughput_control
+    }
+
+    /// Frame body, encrypted for protected frames
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// LLC/SNAP header of the payload, none for protected frames, aggregates and payloads
+    /// without SNAP header
+    pub fn llc_snap(&self) -> Option<LlcSnap<'_>> {
+        if self.control.get_protected() || self.is_aggregate() {
+            return None;
+        }
+        LlcSnap::parse(&self.payload)
     }
 }
 
@@ -369,3 +1022,235 @@ impl fmt::Display for Frame {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn management_header(subtype: FrameSubtype) -> Vec<u8> {
+        let control = subtype.management_frame_control().unwrap();
+        let mut frame = vec![control as u8, (control >> 8) as u8, 0, 0];
+        frame.extend(&[0xff; 6]);
+        frame.extend(&[0x02, 0, 0, 0, 0, 0x01]);
+        frame.extend(&[0x02, 0, 0, 0, 0, 0x01]);
+        frame.extend(&[0x10, 0x00]);
+        frame
+    }
+
+    fn management_body(frame: &Frame) -> &ManagementFrameBody {
+        match *frame {
+            Frame::Management(ref frame) => frame.body(),
+            _ => panic!("Expected management frame"),
+        }
+    }
+
+    #[test]
+    fn beacon_body() {
+        let mut bytes = management_header(FrameSubtype::Beacon);
+        bytes.extend(&[1, 2, 3, 4, 5, 6, 7, 8, 100, 0, 0x11, 0x04]);
+        bytes.extend(&[0, 4, b't', b'e', b's', b't', 3, 1, 6]);
+        let frame = Frame::unpack(&bytes).unwrap();
+        let body = management_body(&frame);
+        match *body {
+            ManagementFrameBody::Beacon(ref beacon) => {
+                assert_eq!(beacon.timestamp, 0x0807060504030201);
+                assert_eq!(beacon.beacon_interval, 100);
+                assert_eq!(
+                    beacon.capability,
+                    CapabilityInformation::ESS
+                        | CapabilityInformation::PRIVACY
+                        | CapabilityInformation::SHORT_SLOT_TIME
+                );
+            }
+            _ => panic!("Expected beacon"),
+        }
+        assert_eq!(body.information_elements().elements.len(), 2);
+    }
+
+    #[test]
+    fn authentication_body() {
+        let mut bytes = management_header(FrameSubtype::Authentication);
+        bytes.extend(&[3, 0, 1, 0, 0, 0, 19, 0]);
+        bytes.extend(&[0x5a; 96]);
+        let frame = Frame::unpack(&bytes).unwrap();
+        match *management_body(&frame) {
+            ManagementFrameBody::Authentication(ref auth) => {
+                assert_eq!(auth.algorithm, AuthenticationBody::SAE);
+                match auth.sae {
+                    Some(SaeFields::Commit {
+                        group,
+                        ref token,
+                        ref scalar,
+                        ref element,
+                        ref remainder,
+                    }) => {
+                        assert_eq!(group, 19);
+                        assert!(token.is_empty());
+                        assert_eq!(scalar.len(), 32);
+                        assert_eq!(element.len(), 64);
+                        assert!(remainder.is_empty());
+                    }
+                    _ => panic!("Expected SAE commit"),
+                }
+            }
+            _ => panic!("Expected authentication"),
+        }
+        let sae = |fields: &[u8]| {
+            let mut bytes = management_header(FrameSubtype::Authentication);
+            bytes.extend(fields);
+            match *management_body(&Frame::unpack(&bytes).unwrap()) {
+                ManagementFrameBody::Authentication(ref auth) => {
+                    auth.sae.as_ref().map(|sae| match *sae {
+                        SaeFields::Commit {
+                            ref token,
+                            ref scalar,
+                            ref element,
+                            ref remainder,
+                            ..
+                        } => (
+                            token.clone(),
+                            scalar.clone(),
+                            element.clone(),
+                            remainder.clone(),
+                        ),
+                        _ => panic!("Expected SAE commit"),
+                    })
+                }
+                _ => panic!("Expected authentication"),
+            }
+        };
+        // Commit and confirm rejected with an empty body
+        assert_eq!(sae(&[3, 0, 1, 0, 1, 0]), None);
+        assert_eq!(sae(&[3, 0, 2, 0, 15, 0]), None);
+        // Anti-clogging token request
+        let mut fields = vec![3, 0, 1, 0, 76, 0, 19, 0];
+        fields.extend(&[0xa5; 32]);
+        let (token, scalar, _, remainder) = sae(&fields).unwrap();
+        assert_eq!(token, vec![0xa5; 32]);
+        assert!(scalar.is_empty());
+        assert!(remainder.is_empty());
+        // Commit repeating the anti-clogging token ahead of scalar and element
+        fields[4] = 0;
+        fields.extend(&[0x11; 32]);
+        fields.extend(&[0x22; 64]);
+        let (token, scalar, element, remainder) = sae(&fields).unwrap();
+        assert_eq!(token, vec![0xa5; 32]);
+        assert_eq!(scalar, vec![0x11; 32]);
+        assert_eq!(element, vec![0x22; 64]);
+        assert!(remainder.is_empty());
+        // With hash-to-element the token is in a container element after scalar and element
+        let mut fields = vec![3, 0, 1, 0, 126, 0, 19, 0];
+        fields.extend(&[0x11; 32]);
+        fields.extend(&[0x22; 64]);
+        fields.extend(&[255, 33, 93]);
+        fields.extend(&[0xa5; 32]);
+        let (token, scalar, _, remainder) = sae(&fields).unwrap();
+        assert!(token.is_empty());
+        assert_eq!(scalar, vec![0x11; 32]);
+        assert_eq!(remainder.len(), 35);
+        // A successful commit must include scalar and element
+        let mut bytes = management_header(FrameSubtype::Authentication);
+        bytes.extend(&[3, 0, 1, 0, 0, 0, 19, 0, 0x11]);
+        assert!(Frame::unpack(&bytes).is_err());
+        // Shared key authentication frame 3 with WEP encrypted body
+        let mut bytes = management_header(FrameSubtype::Authentication);
+        bytes[1] |= 0x40;
+        bytes.extend(&[0x12, 0x34, 0x56, 0x00, 0xaa, 0xbb, 0xcc, 0xdd]);
+        let frame = Frame::unpack(&bytes).unwrap();
+        assert_eq!(
+            *management_body(&frame),
+            ManagementFrameBody::Other(vec![0x12, 0x34, 0x56, 0x00, 0xaa, 0xbb, 0xcc, 0xdd])
+        );
+        let mut bytes = management_header(FrameSubtype::Deauthentication);
+        bytes.extend(&[3, 0]);
+        let frame = Frame::unpack(&bytes).unwrap();
+        assert_eq!(
+            *management_body(&frame),
+            ManagementFrameBody::Deauthentication(3)
+        );
+        bytes.truncate(bytes.len() - 1);
+        assert!(Frame::unpack(&bytes).is_err());
+    }
+
+    #[test]
+    fn association_body() {
+        let mut bytes = management_header(FrameSubtype::ReassociationRequest);
+        bytes.extend(&[0x01, 0x00, 10, 0, 0x02, 0, 0, 0, 0, 0x02, 0, 1, b'a']);
+        let frame = Frame::unpack(&bytes).unwrap();
+        match *management_body(&frame) {
+            ManagementFrameBody::ReassociationRequest(ref request) => {
+                assert_eq!(request.listen_interval, 10);
+                assert_eq!(
+                    request.current_access_point,
+                    Some(HardwareAddress::from(&[2, 0, 0, 0, 0, 2][..]))
+                );
+            }
+            _ => panic!("Expected reassociation request"),
+        }
+        let mut bytes = management_header(FrameSubtype::AssociationResponse);
+        bytes.extend(&[0x01, 0x00, 0, 0, 0x01, 0xc0]);
+        let frame = Frame::unpack(&bytes).unwrap();
+        match *management_body(&frame) {
+            ManagementFrameBody::AssociationResponse(ref response) => {
+                assert_eq!(response.status_code, 0);
+                assert_eq!(response.association_id, 1);
+            }
+            _ => panic!("Expected association response"),
+        }
+        let mut bytes = management_header(FrameSubtype::Action);
+        bytes.extend(&[10, 7, 1]);
+        let frame = Frame::unpack(&bytes).unwrap();
+        match *management_body(&frame) {
+            ManagementFrameBody::Action(ref action) => {
+                assert_eq!(action.category, 10);
+                assert_eq!(action.action(), Some(7));
+            }
+            _ => panic!("Expected action"),
+        }
+    }
+
+    #[test]
+    fn data_frame() {
+        let station = HardwareAddress::from(&[2, 0, 0, 0, 0, 1][..]);
+        let access_point = HardwareAddress::from(&[2, 0, 0, 0, 0, 2][..]);
+        let destination = HardwareAddress::from(&[2, 0, 0, 0, 0, 3][..]);
+        // QoS data to the distribution system
+        let mut bytes = vec![0x88, 0x01, 0, 0];
+        bytes.extend(&[
+            2, 0, 0, 0, 0, 2, 2, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 3, 0x10, 0,
+        ]);
+        bytes.extend(&[0x05, 0x00]);
+        bytes.extend(&[0xaa, 0xaa, 0x03, 0, 0, 0, 0x88, 0x8e, 1, 2]);
+        let frame = match Frame::unpack(&bytes).unwrap() {
+            Frame::Data(frame) => frame,
+            _ => panic!("Expected data frame"),
+        };
+        assert_eq!(frame.receiver(), access_point);
+        assert_eq!(frame.bssid(), Some(access_point));
+        assert_eq!(frame.source(), station);
+        assert_eq!(frame.destination(), destination);
+        assert_eq!(frame.traffic_identifier(), Some(5));
+        assert_eq!(frame.high_throughput_control(), None);
+        let snap = frame.llc_snap().unwrap();
+        assert_eq!(snap.ethertype, 0x888e);
+        assert_eq!(snap.payload, &[1, 2]);
+        // Four address data frame
+        let mut bytes = vec![0x08, 0x03, 0, 0];
+        bytes.extend(&[
+            2, 0, 0, 0, 0, 2, 2, 0, 0, 0, 0, 1, 2, 0, 0, 0, 0, 3, 0x10, 0,
+        ]);
+        bytes.extend(&[2, 0, 0, 0, 0, 4]);
+        let frame = match Frame::unpack(&bytes).unwrap() {
+            Frame::Data(frame) => frame,
+            _ => panic!("Expected data frame"),
+        };
+        assert_eq!(frame.transmitter(), station);
+        assert_eq!(
+            frame.source(),
+            HardwareAddress::from(&[2, 0, 0, 0, 0, 4][..])
+        );
+        assert_eq!(frame.bssid(), None);
+        assert!(frame.payload().is_empty());
+        assert!(Frame::unpack(&bytes[..28]).is_err());
+    }
+}
@@ -0,0 +1,206 @@
+//! ## Independent BSS
+//!
+//! Joining and leaving independent BSS (IBSS) networks, also known as ad-hoc networks.
+//!
+
+use std::io;
+
+use crate::attributes::Attribute;
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::pack::flag_attribute;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::{Error, HardwareAddress, MessageMode, Socket};
+
+/// Request to join or create an IBSS network
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinIbssRequest {
+    ssid: Vec<u8>,
+    channel: ChannelDefinition,
+    fixed_frequency: bool,
+    bssid: Option<HardwareAddress>,
+    beacon_interval: Option<u32>,
+    basic_rates: Option<Vec<u8>>,
+    privacy: bool,
+    control_port: bool,
+    multicast_rate: Option<u32>,
+    information_elements: Option<Vec<u8>>,
+}
+
+impl JoinIbssRequest {
+    /// Create request to join the IBSS network with the SSID on the channel
+    pub fn new(ssid: &[u8], channel: ChannelDefinition) -> JoinIbssRequest {
+        JoinIbssRequest {
+            ssid: ssid.to_vec(),
+            channel,
+            fixed_frequency: false,
+            bssid: None,
+            beacon_interval: None,
+            basic_rates: None,
+            privacy: false,
+            control_port: false,
+            multicast_rate: None,
+            information_elements: None,
+        }
+    }
+
+    /// Only join networks on the given channel, otherwise networks found on other channels
+    /// may be joined
+    pub fn fixed_frequency(mut self, fixed: bool) -> Self {
+        self.fixed_frequency = fixed;
+        self
+    }
+
+    /// Only join the network with the BSSID
+    pub fn bssid(mut self, bssid: HardwareAddress) -> Self {
+        self.bssid = Some(bssid);
+        self
+    }
+
+    /// Beacon interval in time units, used when creating the network
+    pub fn beacon_interval(mut self, interval: u32) -> Self {
+        self.beacon_interval = Some(interval);
+        self
+    }
+
+    /// Basic rates in units of 500 kbit/s, used when creating the network
+    pub fn basic_rates(mut self, rates: &[u8]) -> Self {
+        self.basic_rates = Some(rates.to_vec());
+        self
+    }
+
+    /// Network uses encryption
+    pub fn privacy(mut self, privacy: bool) -> Self {
+        self.privacy = privacy;
+        self
+    }
+
+    /// Data frames are blocked until the peers are authorized by user space
+    pub fn control_port(mut self, enabled: bool) -> Self {
+        self.control_port = enabled;
+        self
+    }
+
+    /// Rate of multicast frames in units of 100 kbit/s
+    pub fn multicast_rate(mut self, rate: u32) -> Self {
+        self.multicast_rate = Some(rate);
+        self
+    }
+
+    /// Information elements added to beacons and probe responses
+    pub fn information_elements(mut self, ies: &[u8]) -> Self {
+        self.information_elements = Some(ies.to_vec());
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.ssid.is_empty() || self.ssid.len() > 32 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid SSID length").into());
+        }
+        if let Some(interval) = self.beacon_interval {
+            if interval == 0 || interval > 10000 {
+                return Err(
+                    io::Error::new(io::ErrorKind::InvalidInput, "Invalid beacon interval").into(),
+                );
+            }
+        }
+        if let Some(ref rates) = self.basic_rates {
+            if rates.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, "No basic rates").into());
+            }
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![netlink::Attribute::new_bytes(Attribute::Ssid, &self.ssid)];
+        attributes.extend(self.channel.attributes());
+        if self.fixed_frequency {
+            attributes.push(flag_attribute(Attribute::FreqFixed));
+        }
+        if let Some(bssid) = self.bssid {
+            attributes.push(netlink::Attribute::new(Attribute::Mac, bssid));
+        }
+        if let Some(interval) = self.beacon_interval {
+            attributes.push(netlink::Attribute::new(Attribute::BeaconInterval, interval));
+        }
+        if let Some(ref rates) = self.basic_rates {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::BssBasicRates,
+                rates,
+            ));
+        }
+        if self.privacy {
+            attributes.push(flag_attribute(Attribute::Privacy));
+        }
+        if self.control_port {
+            attributes.push(flag_attribute(Attribute::ControlPort));
+        }
+        if let Some(rate) = self.multicast_rate {
+            attributes.push(netlink::Attribute::new(Attribute::McastRate, rate));
+        }
+        if let Some(ref ies) = self.information_elements {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::InformationElement,
+                ies,
+            ));
+        }
+        attributes
+    }
+}
+
+impl WirelessInterface {
+    /// Join or create an IBSS network
+    pub fn join_ibss(&self, socket: &mut Socket, request: &JoinIbssRequest) -> Result<(), Error> {
+        request.validate()?;
+        let mut msg = self.prepare_message(Command::JoinIbss, MessageMode::Acknowledge)?;
+        for attribute in request.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Leave the IBSS network
+    pub fn leave_ibss(&self, socket: &mut Socket) -> Result<(), Error> {
+        let msg = self.prepare_message(Command::LeaveIbss, MessageMode::Acknowledge)?;
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use netlink_rust::ConvertFrom;
+
+    #[test]
+    fn join_ibss_request() {
+        let request = JoinIbssRequest::new(b"adhoc", ChannelDefinition::new(2412))
+            .fixed_frequency(true)
+            .multicast_rate(60);
+        assert!(request.validate().is_ok());
+        let attributes = request.attributes();
+        assert_eq!(attributes.len(), 5);
+        assert_eq!(
+            Attribute::convert_from(attributes[3].identifier),
+            Some(Attribute::FreqFixed)
+        );
+        let request = JoinIbssRequest::new(b"adhoc", ChannelDefinition::new(2412));
+        assert!(request.clone().beacon_interval(0).validate().is_err());
+        assert!(request.basic_rates(&[]).validate().is_err());
+    }
+}
@@ -15,9 +15,27 @@ use std::io;
 use encoding::all::ISO_8859_1;
 use encoding::{DecoderTrap, Encoding};
 
+use crate::channel::{operating_class, OperatingClass};
+use crate::channel_definition::ChannelDefinition;
 use crate::information_element_ids::InformationElementId;
+use crate::pack::hardware_address_bytes;
 use crate::unpack::{unpack_vec, LittleUnpack};
-use netlink_rust::{ConvertFrom, Error};
+use netlink_rust::{ConvertFrom, Error, HardwareAddress};
+
+/// Pack identifier and payload into an information element
+///
+/// Panics if the payload is longer than 255 octets, the callers limit the payload length.
+fn pack_element(identifier: u8, payload: &[u8]) -> Vec<u8> {
+    assert!(
+        payload.len() <= usize::from(u8::MAX),
+        "Information element payload longer than 255 octets"
+    );
+    let mut data = Vec::with_capacity(payload.len() + 2);
+    data.push(identifier);
+    data.push(payload.len() as u8);
+    data.extend_from_slice(payload);
+    data
+}
 
 /// Unprocessed information element
 ///
@@ -57,6 +75,10 @@ impl<'a> RawInformationElement<'a> {
     pub fn ie_id(&self) -> Option<InformationElementId> {
         InformationElementId::convert_from(self.identifier)
     }
+    /// Pack information element into a byte vector, including identifier and length
+    pub fn pack(&self) -> Vec<u8> {
+        pack_element(self.identifier, self.data)
+    }
 }
 
 /// Multiple information elements
@@ -84,6 +106,9 @@ pub struct Ssid {
 }
 
 impl Ssid {
+    /// Maximum length of a SSID in octets
+    pub const MAX_LENGTH: usize = 32;
+
     /// Parse information payload as SSID
     ///
     /// This function will try to decode the string as UTF-8 first, if UTF-8 decoding fails
@@ -98,6 +123,17 @@ impl Ssid {
         let ssid = ssid.trim_end_matches('\0').to_string();
         Ok(Ssid { ssid })
     }
+
+    /// Pack SSID into information element, the SSID can be at most 32 octets long
+    pub fn pack(&self) -> Result<Vec<u8>, Error> {
+        if self.ssid.len() > Ssid::MAX_LENGTH {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "SSID too long").into());
+        }
+        Ok(pack_element(
+            InformationElementId::Ssid.into(),
+            self.ssid.as_bytes(),
+        ))
+    }
 }
 
 impl fmt::Display for Ssid {
@@ -106,6 +142,94 @@ impl fmt::Display for Ssid {
     }
 }
 
+/// Supported rates information element data
+///
+/// Rates are in units of 500 kbps, the most significant bit marks a basic rate.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupportedRates {
+    pub rates: Vec<u8>,
+}
+
+impl SupportedRates {
+    /// Flag marking a rate as basic rate
+    pub const BASIC_RATE: u8 = 0x80;
+
+    /// Create supported rates from rates in units of 500 kbps, marking the basic rates
+    pub fn new(rates: &[u8], basic_rates: &[u8]) -> SupportedRates {
+        let rates = rates
+            .iter()
+            .map(|r| {
+                if basic_rates.contains(r) {
+                    r | Self::BASIC_RATE
+                } else {
+                    *r
+                }
+            })
+            .collect();
+        SupportedRates { rates }
+    }
+
+    /// Parse supported rates from information element payload
+    pub fn parse(data: &[u8]) -> Result<SupportedRates, Error> {
+        if !data.is_empty() {
+            return Ok(SupportedRates {
+                rates: data.to_vec(),
+            });
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Invalid supported rates element",
+        )
+        .into())
+    }
+
+    /// Basic rates in units of 500 kbps
+    pub fn basic_rates(&self) -> Vec<u8> {
+        self.rates
+            .iter()
+            .filter(|r| *r & Self::BASIC_RATE == Self::BASIC_RATE)
+            .map(|r| r & !Self::BASIC_RATE)
+            .collect()
+    }
+
+    /// Pack the first eight rates into a supported rates information element
+    pub fn pack(&self) -> Vec<u8> {
+        let count = self.rates.len().min(8);
+        pack_element(
+            InformationElementId::SupportRates.into(),
+            &self.rates[..count],
+        )
+    }
+
+    /// Pack rates beyond the first eight into an extended supported rates information element
+    pub fn pack_extended(&self) -> Option<Vec<u8>> {
+        if self.rates.len() > 8 {
+            return Some(pack_element(
+                InformationElementId::ExtededSupportRates.into(),
+                &self.rates[8..],
+            ));
+        }
+        None
+    }
+}
+
+impl fmt::Display for SupportedRates {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, rate) in self.rates.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            let value = f64::from(rate & !Self::BASIC_RATE) / 2.0;
+            if rate & Self::BASIC_RATE == Self::BASIC_RATE {
+                write!(f, "{}*", value)?;
+            } else {
+                write!(f, "{}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Cipher suites used in 802.11
 #[derive(Debug, PartialEq, Clone)]
 pub enum CipherSuite {
@@ -341,6 +465,24 @@ pub struct RobustSecurityNetwork {
 }
 
 impl RobustSecurityNetwork {
+    /// Create robust security network element with one replay counter per security association
+    pub fn new(
+        group_cipher: CipherSuite,
+        ciphers: &[CipherSuite],
+        akms: &[AuthenticationKeyManagement],
+        capabilities: RsnCapabilities,
+    ) -> RobustSecurityNetwork {
+        RobustSecurityNetwork {
+            version: 1,
+            cipher_suite: group_cipher,
+            ciphers: ciphers.to_vec(),
+            akms: akms.to_vec(),
+            capabilities,
+            ptksa_counters: 1,
+            gtksa_counters: 1,
+        }
+    }
+
     /// Parse robust security network from information element payload
     pub fn parse(data: &[u8]) -> Result<RobustSecurityNetwork, Error> {
         if data.len() > 8 {
@@ -408,6 +550,43 @@ impl RobustSecurityNetwork {
     pub fn group_temporal_key_security_association_replay_counters(&self) -> u8 {
         self.gtksa_counters
     }
+
+    /// Group data cipher suite
+    pub fn group_cipher(&self) -> CipherSuite {
+        self.cipher_suite.clone()
+    }
+
+    /// Capabilities
+    pub fn capabilities(&self) -> RsnCapabilities {
+        self.capabilities
+    }
+
+    /// Pack robust security network into information element
+    pub fn pack(&self) -> Vec<u8> {
+        fn replay_counter_bits(counters: u8) -> u16 {
+            match counters {
+                2 => 1,
+                4 => 2,
+                16 => 3,
+                _ => 0,
+            }
+        }
+        let mut payload = self.version.to_le_bytes().to_vec();
+        payload.extend_from_slice(&u32::from(self.cipher_suite.clone()).to_le_bytes());
+        payload.extend_from_slice(&(self.ciphers.len() as u16).to_le_bytes());
+        for cipher in &self.ciphers {
+            payload.extend_from_slice(&u32::from(cipher.clone()).to_le_bytes());
+        }
+        payload.extend_from_slice(&(self.akms.len() as u16).to_le_bytes());
+        for akm in &self.akms {
+            payload.extend_from_slice(&u32::from(akm.clone()).to_le_bytes());
+        }
+        let capabilities = self.capabilities.bits()
+            | replay_counter_bits(self.ptksa_counters) << 2
+            | replay_counter_bits(self.gtksa_counters) << 4;
+        payload.extend_from_slice(&capabilities.to_le_bytes());
+        pack_element(InformationElementId::RobustSecurityNetwork.into(), &payload)
+    }
 }
 
 impl fmt::Display for RobustSecurityNetwork {
@@ -539,6 +718,7 @@ impl fmt::Display for VeryHighThroughputOperation {
 }
 
 /// Channel switch mode information element data
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum ChannelSwitchMode {
     /// No restrictions during channel switch
     NoRestriction = 0,
@@ -556,6 +736,7 @@ impl From<u8> for ChannelSwitchMode {
 }
 
 /// Channel switch announcement (CSA) information element data
+#[derive(Clone, Debug, PartialEq)]
 pub struct ChannelSwitchAnnouncement {
     /// Channel switch mode
     pub switch_mode: ChannelSwitchMode,
@@ -566,9 +747,12 @@ pub struct ChannelSwitchAnnouncement {
 }
 
 impl ChannelSwitchAnnouncement {
+    /// Offset of the switch count in the packed information element
+    pub const SWITCH_COUNT_OFFSET: usize = 4;
+
     /// Parse channel switch announcement from information element payload
     pub fn parse(data: &[u8]) -> Result<Self, Error> {
-        if data.len() == 4 {
+        if data.len() == 3 {
             return Ok(ChannelSwitchAnnouncement {
                 switch_mode: ChannelSwitchMode::from(data[0]),
                 new_channel: data[1],
@@ -577,9 +761,18 @@ impl ChannelSwitchAnnouncement {
         }
         Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid CSA element").into())
     }
+
+    /// Pack channel switch announcement into information element
+    pub fn pack(&self) -> Vec<u8> {
+        pack_element(
+            InformationElementId::ChannelSwitchAnnouncement.into(),
+            &[self.switch_mode as u8, self.new_channel, self.switch_count],
+        )
+    }
 }
 
 /// Extended channel switch (ECSA) information element data
+#[derive(Clone, Debug, PartialEq)]
 pub struct ExtendedChannelSwitchAnnouncement {
     /// Channel switch mode
     pub switch_mode: ChannelSwitchMode,
@@ -592,6 +785,9 @@ pub struct ExtendedChannelSwitchAnnouncement {
 }
 
 impl ExtendedChannelSwitchAnnouncement {
+    /// Offset of the switch count in the packed information element
+    pub const SWITCH_COUNT_OFFSET: usize = 5;
+
     /// Parse extended channel switch announcement from information element payload
     pub fn parse(data: &[u8]) -> Result<ExtendedChannelSwitchAnnouncement, Error> {
         if data.len() == 4 {
@@ -604,6 +800,86 @@ impl ExtendedChannelSwitchAnnouncement {
         }
         Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid ECSA element").into())
     }
+
+    /// Pack extended channel switch announcement into information element
+    pub fn pack(&self) -> Vec<u8> {
+        pack_element(
+            InformationElementId::ExtendedChannelSwitchAnnouncement.into(),
+            &[
+                self.switch_mode as u8,
+                self.new_operating_class,
+                self.new_channel,
+                self.switch_count,
+            ],
+        )
+    }
+
+    /// Frequency in MHz of the new channel given by the global operating class
+    pub fn new_frequency(&self) -> Option<u32> {
+        operating_class(self.new_operating_class)?.frequency(self.new_channel)
+    }
+
+    /// Channel definition of the new channel given by the global operating class
+    pub fn new_channel_definition(&self) -> Option<ChannelDefinition> {
+        operating_class(self.new_operating_class)?.channel_definition(self.new_channel)
+    }
+}
+
+/// Supported operating classes information element data
+#[derive(Clone, Debug, PartialEq)]
+pub struct SupportedOperatingClasses {
+    /// Current operating class
+    pub current: u8,
+    /// Supported operating classes
+    pub classes: Vec<u8>,
+}
+
+impl SupportedOperatingClasses {
+    // Delimiter of the current operating class extension sequence
+    const EXTENSION_DELIMITER: u8 = 130;
+    // Delimiter of the operating class duple sequence
+    const DUPLE_DELIMITER: u8 = 0;
+
+    /// Parse supported operating classes from information element payload
+    ///
+    /// The optional extension and duple sequences following the operating classes are
+    /// ignored.
+    pub fn parse(data: &[u8]) -> Result<SupportedOperatingClasses, Error> {
+        if data.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid supported operating classes element",
+            )
+            .into());
+        }
+        let classes = data[1..]
+            .iter()
+            .cloned()
+            .take_while(|&c| c != Self::EXTENSION_DELIMITER && c != Self::DUPLE_DELIMITER)
+            .collect();
+        Ok(SupportedOperatingClasses {
+            current: data[0],
+            classes,
+        })
+    }
+
+    /// Pack supported operating classes into information element
+    pub fn pack(&self) -> Vec<u8> {
+        let mut payload = vec![self.current];
+        payload.extend(&self.classes);
+        pack_element(
+            InformationElementId::SupportedOperatingClasses.into(),
+            &payload,
+        )
+    }
+
+    /// Global operating classes known by this crate
+    pub fn operating_classes(&self) -> Vec<&'static OperatingClass> {
+        self.classes
+            .iter()
+            .filter_map(|&c| operating_class(c))
+            .collect()
+    }
 }
 
 /// Country information element data
@@ -624,10 +900,352 @@ impl Country {
     }
 }
 
+bitflags! {
+    /// BSSID information of a neighbor report
+    ///
+    /// The two least significant bits hold the reachability, see `reachability`.
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct BssidInformation: u32 {
+        /// Access point supports the same security as the current connection
+        const SECURITY = 0x0004;
+        /// Access point has the same authenticator as the current access point
+        const KEY_SCOPE = 0x0008;
+        /// Spectrum management capability
+        const SPECTRUM_MANAGEMENT = 0x0010;
+        /// Quality of service capability
+        const QUALITY_OF_SERVICE = 0x0020;
+        /// Automatic power save delivery capability
+        const AUTOMATIC_POWER_SAVE_DELIVERY = 0x0040;
+        /// Radio measurement capability
+        const RADIO_MEASUREMENT = 0x0080;
+        /// Delayed block acknowledge capability
+        const DELAYED_BLOCK_ACKNOWLEDGE = 0x0100;
+        /// Immediate block acknowledge capability
+        const IMMEDIATE_BLOCK_ACKNOWLEDGE = 0x0200;
+        /// Access point is in the same mobility domain
+        const MOBILITY_DOMAIN = 0x0400;
+        /// High throughput capability
+        const HIGH_THROUGHPUT = 0x0800;
+        /// Very high throughput capability
+        const VERY_HIGH_THROUGHPUT = 0x1000;
+        /// Fine timing measurement responder
+        const FINE_TIMING_MEASUREMENT = 0x2000;
+    }
+}
+
+/// Reachability of a neighbor access point for pre-authentication
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reachability {
+    /// Reserved value
+    Reserved,
+    /// Access point is not reachable
+    NotReachable,
+    /// Reachability of the access point is unknown
+    Unknown,
+    /// Access point is reachable
+    Reachable,
+}
+
+impl BssidInformation {
+    const REACHABILITY_MASK: u32 = 0x0003;
+
+    /// Reachability of the access point
+    pub fn reachability(&self) -> Reachability {
+        match self.bits() & Self::REACHABILITY_MASK {
+            1 => Reachability::NotReachable,
+            2 => Reachability::Unknown,
+            3 => Reachability::Reachable,
+            _ => Reachability::Reserved,
+        }
+    }
+
+    /// BSSID information with the reachability replaced
+    pub fn with_reachability(self, reachability: Reachability) -> Self {
+        let value = match reachability {
+            Reachability::Reserved => 0,
+            Reachability::NotReachable => 1,
+            Reachability::Unknown => 2,
+            Reachability::Reachable => 3,
+        };
+        BssidInformation::from_bits_retain((self.bits() & !Self::REACHABILITY_MASK) | value)
+    }
+}
+
+/// Neighbor report information element data
+///
+/// ```notrust
+/// +-------+-------------------+-----------------+---------+----------+----------------+
+/// | BSSID | BSSID information | operating class | channel | PHY type | subelements... |
+/// +-------+-------------------+-----------------+---------+----------+----------------+
+///     6            4                  1              1          1           n            octets
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NeighborReport {
+    /// BSSID of the neighbor
+    pub bssid: HardwareAddress,
+    /// BSSID information
+    pub bssid_information: BssidInformation,
+    /// Operating class of the neighbor
+    pub operating_class: u8,
+    /// Channel number of the neighbor
+    pub channel: u8,
+    /// PHY type of the neighbor
+    pub phy_type: u8,
+    /// BSS transition candidate preference, zero means excluded and 255 most preferred
+    pub preference: Option<u8>,
+}
+
+impl NeighborReport {
+    const PREFERENCE_SUBELEMENT: u8 = 3;
+
+    /// Parse neighbor report from information element payload
+    pub fn parse(data: &[u8]) -> Result<NeighborReport, Error> {
+        if data.len() >= 13 {
+            let bssid = HardwareAddress::unpack_unchecked(data);
+            let info = u32::unpack_unchecked(&data[6..]);
+            let mut preference = None;
+            for element in InformationElements::parse(&data[13..]).elements {
+                if element.identifier == Self::PREFERENCE_SUBELEMENT && !element.data.is_empty() {
+                    preference = Some(element.data[0]);
+                }
+            }
+            return Ok(NeighborReport {
+                bssid,
+                bssid_information: BssidInformation::from_bits_retain(info),
+                operating_class: data[10],
+                channel: data[11],
+                phy_type: data[12],
+                preference,
+            });
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Invalid neighbor report element",
+        )
+        .into())
+    }
+
+    /// Pack neighbor report into information element
+    pub fn pack(&self) -> Vec<u8> {
+        let mut payload = hardware_address_bytes(self.bssid);
+        payload.extend_from_slice(&self.bssid_information.bits().to_le_bytes());
+        payload.push(self.operating_class);
+        payload.push(self.channel);
+        payload.push(self.phy_type);
+        if let Some(preference) = self.preference {
+            payload.extend(pack_element(Self::PREFERENCE_SUBELEMENT, &[preference]));
+        }
+        pack_element(InformationElementId::NeighborReport.into(), &payload)
+    }
+}
+
+impl fmt::Display for NeighborReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} Operating Class {} Channel {}",
+            self.bssid, self.operating_class, self.channel
+        )?;
+        if let Some(preference) = self.preference {
+            write!(f, " Preference {}", preference)?;
+        }
+        Ok(())
+    }
+}
+
+bitflags! {
+    /// Fast BSS transition capability and policy
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct FastTransitionCapability: u8 {
+        /// Fast BSS transition over the distribution system (DS) is supported
+        const OVER_DISTRIBUTION_SYSTEM = 0x01;
+        /// Resource request protocol is supported
+        const RESOURCE_REQUEST_PROTOCOL = 0x02;
+    }
+}
+
+/// Mobility domain (MDE) information element data
+///
+/// ```notrust
+/// +------+-------------------------------------+
+/// | MDID | fast transition capability & policy |
+/// +------+-------------------------------------+
+///    2                     1                      octets
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct MobilityDomain {
+    /// Mobility domain identifier (MDID)
+    pub identifier: u16,
+    /// Fast BSS transition capability and policy
+    pub capability: FastTransitionCapability,
+}
+
+impl MobilityDomain {
+    /// Parse mobility domain from information element payload
+    pub fn parse(data: &[u8]) -> Result<MobilityDomain, Error> {
+        if data.len() == 3 {
+            return Ok(MobilityDomain {
+                identifier: u16::unpack_unchecked(data),
+                capability: FastTransitionCapability::from_bits_retain(data[2]),
+            });
+        }
+        Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid MDE element").into())
+    }
+
+    /// Pack mobility domain into information element
+    pub fn pack(&self) -> Vec<u8> {
+        let mut payload = self.identifier.to_le_bytes().to_vec();
+        payload.push(self.capability.bits());
+        pack_element(InformationElementId::MobilityDomain.into(), &payload)
+    }
+}
+
+impl fmt::Display for MobilityDomain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "MDID {:04x} {:?}", self.identifier, self.capability)
+    }
+}
+
+/// Fast BSS transition (FTE) information element data
+///
+/// ```notrust
+/// +-------------+-----+--------+--------+----------------+
+/// | MIC control | MIC | ANonce | SNonce | subelements... |
+/// +-------------+-----+--------+--------+----------------+
+///        2        16      32       32            n          octets
+/// ```
+/// The MIC is 24 octets for the SHA-384 based AKMs. As the MIC length cannot be known from
+/// the element itself, `InformationElement` leaves the element unprocessed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FastBssTransition {
+    /// MIC control, the upper octet holds the number of elements protected by the MIC
+    pub mic_control: u16,
+    /// Message integrity code (MIC)
+    pub mic: Vec<u8>,
+    /// Authenticator nonce
+    pub anonce: [u8; 32],
+    /// Supplicant nonce
+    pub snonce: [u8; 32],
+    /// PMK-R1 key holder identifier (R1KH-ID)
+    pub r1kh_id: Option<HardwareAddress>,
+    /// PMK-R0 key holder identifier (R0KH-ID)
+    pub r0kh_id: Option<Vec<u8>>,
+    /// Wrapped group temporal key (GTK)
+    pub gtk: Option<Vec<u8>>,
+    /// Wrapped integrity group temporal key (IGTK)
+    pub igtk: Option<Vec<u8>>,
+}
+
+impl FastBssTransition {
+    const R1KH_ID_SUBELEMENT: u8 = 1;
+    const GTK_SUBELEMENT: u8 = 2;
+    const R0KH_ID_SUBELEMENT: u8 = 3;
+    const IGTK_SUBELEMENT: u8 = 4;
+
+    /// Create fast BSS transition element with zeroed MIC, as sent in the first message
+    pub fn new(snonce: [u8; 32], r0kh_id: &[u8]) -> FastBssTransition {
+        FastBssTransition {
+            mic_control: 0,
+            mic: vec![0; 16],
+            anonce: [0; 32],
+            snonce,
+            r1kh_id: None,
+            r0kh_id: Some(r0kh_id.to_vec()),
+            gtk: None,
+            igtk: None,
+        }
+    }
+
+    /// MIC length in octets used with the given AKM
+    pub fn mic_length(akm: &AuthenticationKeyManagement) -> usize {
+        match *akm {
+            // FT-802.1X-SHA384, FT-FILS-SHA384 and FT-PSK-SHA384
+            AuthenticationKeyManagement::Reserved(13)
+            | AuthenticationKeyManagement::Reserved(17)
+            | AuthenticationKeyManagement::Reserved(19) => 24,
+            _ => 16,
+        }
+    }
+
+    /// Parse fast BSS transition from information element payload
+    ///
+    /// The MIC length depends on the negotiated AKM, see `mic_length`.
+    pub fn parse(data: &[u8], mic_length: usize) -> Result<FastBssTransition, Error> {
+        let nonce_offset = 2 + mic_length;
+        let length = nonce_offset + 64;
+        if data.len() >= length {
+            let mut anonce = [0u8; 32];
+            let mut snonce = [0u8; 32];
+            anonce.copy_from_slice(&data[nonce_offset..nonce_offset + 32]);
+            snonce.copy_from_slice(&data[nonce_offset + 32..length]);
+            let mut fte = FastBssTransition {
+                mic_control: u16::unpack_unchecked(data),
+                mic: data[2..nonce_offset].to_vec(),
+                anonce,
+                snonce,
+                r1kh_id: None,
+                r0kh_id: None,
+                gtk: None,
+                igtk: None,
+            };
+            for element in InformationElements::parse(&data[length..]).elements {
+                match element.identifier {
+                    Self::R1KH_ID_SUBELEMENT => {
+                        fte.r1kh_id = Some(HardwareAddress::unpack(element.data)?);
+                    }
+                    Self::GTK_SUBELEMENT => {
+                        fte.gtk = Some(element.data.to_vec());
+                    }
+                    Self::R0KH_ID_SUBELEMENT => {
+                        fte.r0kh_id = Some(element.data.to_vec());
+                    }
+                    Self::IGTK_SUBELEMENT => {
+                        fte.igtk = Some(element.data.to_vec());
+                    }
+                    _ => (),
+                }
+            }
+            return Ok(fte);
+        }
+        Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid FTE element").into())
+    }
+
+    /// Number of elements protected by the MIC
+    pub fn element_count(&self) -> u8 {
+        (self.mic_control >> 8) as u8
+    }
+
+    /// Pack fast BSS transition into information element
+    pub fn pack(&self) -> Vec<u8> {
+        let mut payload = self.mic_control.to_le_bytes().to_vec();
+        payload.extend_from_slice(&self.mic);
+        payload.extend_from_slice(&self.anonce);
+        payload.extend_from_slice(&self.snonce);
+        if let Some(r1kh_id) = self.r1kh_id {
+            payload.extend(pack_element(
+                Self::R1KH_ID_SUBELEMENT,
+                &hardware_address_bytes(r1kh_id),
+            ));
+        }
+        if let Some(ref gtk) = self.gtk {
+            payload.extend(pack_element(Self::GTK_SUBELEMENT, gtk));
+        }
+        if let Some(ref r0kh_id) = self.r0kh_id {
+            payload.extend(pack_element(Self::R0KH_ID_SUBELEMENT, r0kh_id));
+        }
+        if let Some(ref igtk) = self.igtk {
+            payload.extend(pack_element(Self::IGTK_SUBELEMENT, igtk));
+        }
+        pack_element(InformationElementId::FastBssTransition.into(), &payload)
+    }
+}
+
 /// Information element with processed payload
 pub enum InformationElement<'a> {
     /// SSID information element
     Ssid(Ssid),
+    /// Supported rates information element
+    SupportedRates(SupportedRates),
     /// Country information element
     Country(Country),
     /// Channel switsh announcement information element
@@ -636,10 +1254,16 @@ pub enum InformationElement<'a> {
     RobustSecurityNetwork(RobustSecurityNetwork),
     /// Extended channel switsh announcement information element
     ExtendedChannelSwitchAnnouncement(ExtendedChannelSwitchAnnouncement),
+    /// Supported operating classes information element
+    SupportedOperatingClasses(SupportedOperatingClasses),
     /// High throughput operation information element
     HighThroughputOperation(HighThroughputOperation),
     /// Very high throughput operation information element
     VeryHighThroughputOperation(VeryHighThroughputOperation),
+    /// Neighbor report information element
+    NeighborReport(NeighborReport),
+    /// Mobility domain information element
+    MobilityDomain(MobilityDomain),
     /// Unprocessed information element
     Other(RawInformationElement<'a>),
 }
@@ -662,6 +1286,10 @@ impl<'a> InformationElement<'a> {
                 let ie = Ssid::parse(data)?;
                 InformationElement::Ssid(ie)
             }
+            InformationElementId::SupportRates => {
+                let ie = SupportedRates::parse(data)?;
+                InformationElement::SupportedRates(ie)
+            }
             InformationElementId::Country => {
                 let ie = Country::parse(data)?;
                 InformationElement::Country(ie)
@@ -678,6 +1306,10 @@ impl<'a> InformationElement<'a> {
                 let ie = ExtendedChannelSwitchAnnouncement::parse(data)?;
                 InformationElement::ExtendedChannelSwitchAnnouncement(ie)
             }
+            InformationElementId::SupportedOperatingClasses => {
+                let ie = SupportedOperatingClasses::parse(data)?;
+                InformationElement::SupportedOperatingClasses(ie)
+            }
             InformationElementId::HighThroughputOperation => {
                 let ie = HighThroughputOperation::parse(data)?;
                 InformationElement::HighThroughputOperation(ie)
@@ -686,6 +1318,14 @@ impl<'a> InformationElement<'a> {
                 let ie = VeryHighThroughputOperation::parse(data)?;
                 InformationElement::VeryHighThroughputOperation(ie)
             }
+            InformationElementId::NeighborReport => {
+                let ie = NeighborReport::parse(data)?;
+                InformationElement::NeighborReport(ie)
+            }
+            InformationElementId::MobilityDomain => {
+                let ie = MobilityDomain::parse(data)?;
+                InformationElement::MobilityDomain(ie)
+            }
             _ => InformationElement::Other(RawInformationElement {
                 identifier: id.into(),
                 data,
@@ -697,6 +1337,7 @@ impl<'a> InformationElement<'a> {
     pub fn identifier(&self) -> Option<InformationElementId> {
         let id = match *self {
             InformationElement::Ssid(_) => InformationElementId::Ssid,
+            InformationElement::SupportedRates(_) => InformationElementId::SupportRates,
             InformationElement::Country(_) => InformationElementId::Country,
             InformationElement::ChannelSwitchAnnouncement(_) => {
                 InformationElementId::ChannelSwitchAnnouncement
@@ -707,12 +1348,17 @@ impl<'a> InformationElement<'a> {
             InformationElement::ExtendedChannelSwitchAnnouncement(_) => {
                 InformationElementId::ExtendedChannelSwitchAnnouncement
             }
+            InformationElement::SupportedOperatingClasses(_) => {
+                InformationElementId::SupportedOperatingClasses
+            }
             InformationElement::HighThroughputOperation(_) => {
                 InformationElementId::HighThroughputOperation
             }
             InformationElement::VeryHighThroughputOperation(_) => {
                 InformationElementId::VeryHighThroughputOperation
             }
+            InformationElement::NeighborReport(_) => InformationElementId::NeighborReport,
+            InformationElement::MobilityDomain(_) => InformationElementId::MobilityDomain,
             InformationElement::Other(ref ie) => InformationElementId::from(ie.identifier),
         };
         Some(id)
@@ -756,4 +1402,147 @@ mod tests {
         let ies = InformationElements::parse(&bytes);
         assert_eq!(ies.elements.len(), 3);
     }
+
+    #[test]
+    fn test_supported_rates() {
+        let rates = SupportedRates::new(
+            &[2, 4, 11, 22, 12, 18, 24, 36, 48, 72, 96, 108],
+            &[2, 4, 11, 22],
+        );
+        assert_eq!(rates.basic_rates(), vec![2, 4, 11, 22]);
+        let bytes = rates.pack();
+        assert_eq!(bytes, vec![1, 8, 0x82, 0x84, 0x8b, 0x96, 12, 18, 24, 36]);
+        assert_eq!(rates.pack_extended(), Some(vec![50, 4, 48, 72, 96, 108]));
+    }
+
+    #[test]
+    fn test_robust_security_network() {
+        let bytes = [
+            48, 20, 0x01, 0x00, 0x00, 0x0f, 0xac, 0x04, 0x01, 0x00, 0x00, 0x0f, 0xac, 0x04, 0x01,
+            0x00, 0x00, 0x0f, 0xac, 0x02, 0x88, 0x00,
+        ];
+        let rsn = RobustSecurityNetwork::parse(&bytes[2..]).unwrap();
+        assert_eq!(rsn.akms, vec![AuthenticationKeyManagement::PreSharedKey]);
+        assert_eq!(
+            rsn.pairwise_transient_key_security_association_replay_counters(),
+            4
+        );
+        assert_eq!(rsn.pack(), bytes.to_vec());
+        let rsn = RobustSecurityNetwork::new(
+            CipherSuite::CounterModeCbcMacProtocol,
+            &[CipherSuite::CounterModeCbcMacProtocol],
+            &[AuthenticationKeyManagement::PreSharedKey],
+            RsnCapabilities::PMF_CAPABLE,
+        );
+        assert_eq!(rsn.pack()[2..20], bytes[2..20]);
+    }
+
+    #[test]
+    fn test_channel_switch_announcement() {
+        let bytes = [37, 3, 1, 36, 10];
+        let csa = match InformationElement::parse(&bytes).unwrap() {
+            InformationElement::ChannelSwitchAnnouncement(csa) => csa,
+            _ => panic!("Expected channel switch announcement"),
+        };
+        assert_eq!(csa.switch_mode, ChannelSwitchMode::NoTransmission);
+        assert_eq!(csa.new_channel, 36);
+        assert_eq!(csa.pack(), bytes.to_vec());
+        assert_eq!(bytes[ChannelSwitchAnnouncement::SWITCH_COUNT_OFFSET], 10);
+    }
+
+    #[test]
+    fn test_neighbor_report() {
+        let bytes = [
+            52, 16, 0x02, 0x11, 0x22, 0x33, 0x44, 0x55, 0x0f, 0x04, 0x00, 0x00, 115, 36, 9, 3, 1,
+            200,
+        ];
+        let ie = InformationElement::parse(&bytes).unwrap();
+        let report = match ie {
+            InformationElement::NeighborReport(report) => report,
+            _ => panic!("Expected neighbor report"),
+        };
+        assert_eq!(report.operating_class, 115);
+        assert_eq!(report.channel, 36);
+        assert_eq!(report.phy_type, 9);
+        assert_eq!(report.preference, Some(200));
+        assert_eq!(
+            report.bssid_information.reachability(),
+            Reachability::Reachable
+        );
+        assert!(report
+            .bssid_information
+            .contains(BssidInformation::MOBILITY_DOMAIN));
+        assert_eq!(report.pack(), bytes.to_vec());
+        let information = BssidInformation::SECURITY.with_reachability(Reachability::Unknown);
+        assert_eq!(information.bits(), 0x0006);
+        assert_eq!(information.reachability(), Reachability::Unknown);
+        let information = information.with_reachability(Reachability::NotReachable);
+        assert_eq!(information.reachability(), Reachability::NotReachable);
+        assert!(information.contains(BssidInformation::SECURITY));
+        assert_eq!(
+            BssidInformation::empty().reachability(),
+            Reachability::Reserved
+        );
+    }
+
+    #[test]
+    fn test_mobility_domain() {
+        let bytes = [54, 3, 0x34, 0x12, 0x01];
+        let mde = match InformationElement::parse(&bytes).unwrap() {
+            InformationElement::MobilityDomain(mde) => mde,
+            _ => panic!("Expected mobility domain"),
+        };
+        assert_eq!(mde.identifier, 0x1234);
+        assert_eq!(
+            mde.capability,
+            FastTransitionCapability::OVER_DISTRIBUTION_SYSTEM
+        );
+        assert_eq!(mde.pack(), bytes.to_vec());
+    }
+
+    #[test]
+    fn test_fast_bss_transition() {
+        let mut fte = FastBssTransition::new([0x5a; 32], b"r0kh");
+        fte.r1kh_id = Some(HardwareAddress::from(&[2, 0, 0, 0, 0, 1][..]));
+        let bytes = fte.pack();
+        assert_eq!(bytes[0], 55);
+        assert_eq!(bytes[1] as usize, 82 + 8 + 6);
+        let parsed = FastBssTransition::parse(&bytes[2..], 16).unwrap();
+        assert_eq!(parsed, fte);
+        assert_eq!(parsed.element_count(), 0);
+        match InformationElement::parse(&bytes).unwrap() {
+            InformationElement::Other(ie) => assert_eq!(ie.data, &bytes[2..]),
+            _ => panic!("Expected unprocessed fast BSS transition"),
+        }
+
+        let akm = AuthenticationKeyManagement::from(0x13ac_0f00);
+        assert_eq!(FastBssTransition::mic_length(&akm), 24);
+        fte.mic = vec![0xa5; 24];
+        fte.mic_control = 0x0300;
+        let bytes = fte.pack();
+        let parsed = FastBssTransition::parse(&bytes[2..], 24).unwrap();
+        assert_eq!(parsed, fte);
+        assert_eq!(parsed.element_count(), 3);
+        let parsed = FastBssTransition::parse(&bytes[2..], 16).unwrap();
+        assert_ne!(parsed.snonce, fte.snonce);
+        let akm = AuthenticationKeyManagement::FastTransitionPreSharedKey;
+        assert_eq!(FastBssTransition::mic_length(&akm), 16);
+    }
+
+    #[test]
+    fn test_supported_operating_classes() {
+        let bytes = [59, 6, 115, 81, 115, 128, 130, 128];
+        let soc = match InformationElement::parse(&bytes).unwrap() {
+            InformationElement::SupportedOperatingClasses(soc) => soc,
+            _ => panic!("Expected supported operating classes"),
+        };
+        assert_eq!(soc.current, 115);
+        assert_eq!(soc.classes, vec![81, 115, 128]);
+        assert_eq!(soc.operating_classes().len(), 3);
+        assert_eq!(soc.pack(), vec![59, 4, 115, 81, 115, 128]);
+        let ecsa = ExtendedChannelSwitchAnnouncement::parse(&[0, 128, 44, 5]).unwrap();
+        assert_eq!(ecsa.new_frequency(), Some(5220));
+        let channel = ecsa.new_channel_definition().unwrap();
+        assert_eq!(channel.center_frequency1, Some(5210));
+    }
 }
@@ -10,25 +10,90 @@ extern crate bitflags;
 extern crate encoding;
 extern crate netlink_rust;
 
+mod access_control;
+mod access_point;
+#[cfg(feature = "tokio")]
+mod asynchronous;
 mod attributes;
+mod bss;
+pub mod channel;
+mod channel_definition;
+mod channel_switch;
 mod commands;
+mod connection_quality_monitor;
+mod dfs;
+mod event;
+mod fast_transition;
 mod frame;
+mod ibss;
 pub mod information_element;
 mod information_element_ids;
+mod management_frame;
+mod mesh;
+mod mesh_path;
+mod ocb;
+mod pack;
 mod regulatory;
+mod regulatory_database;
+mod remain_on_channel;
+mod roaming;
+mod station;
 mod unpack;
 mod wireless_interface;
 mod wireless_phy;
 
-pub use crate::attributes::{Attribute, BssAttribute, InterfaceType};
+pub use crate::access_control::MacAccessControlList;
+pub use crate::access_point::{
+    BeaconData, BeaconTemplate, CryptoSettings, StartApRequest, WpaVersions,
+};
+#[cfg(feature = "tokio")]
+pub use crate::asynchronous::{AsyncSocket, EventStream};
+pub use crate::attributes::{
+    AclPolicy, Attribute, AuthenticationType, BssAttribute, ChannelWidth, DfsState,
+    ExternalAuthenticationAction, HiddenSsid, InterfaceType, MeshPowerMode, PeerLinkState,
+    RadarEvent, UserRegulatoryHintType,
+};
+pub use crate::bss::{BssColorChangeRequest, BssParameters};
+pub use crate::channel::{Band, OperatingClass};
+pub use crate::channel_definition::{ChannelDefinition, ChannelFlags, PhyChannel};
+pub use crate::channel_switch::{ChannelSwitchEvent, ChannelSwitchRequest};
 pub use crate::commands::Command;
-pub use crate::frame::Frame;
+pub use crate::connection_quality_monitor::{ConnectionQualityMonitor, CqmEvent};
+pub use crate::dfs::{DfsChannel, DfsEvent};
+pub use crate::event::{
+    ConnectEvent, DisconnectEvent, Event, EventFilter, EventListener, EventOrigin, MulticastGroup,
+    Nl80211Event,
+};
+pub use crate::fast_transition::FastTransitionEvent;
+pub use crate::frame::{
+    ActionBody, AssociationRequestBody, AssociationResponseBody, AuthenticationBody, BeaconBody,
+    CapabilityInformation, DataFrame, Frame, FrameSubtype, LlcSnap, ManagementFrame,
+    ManagementFrameBody, SaeFields,
+};
+pub use crate::ibss::JoinIbssRequest;
 pub use crate::information_element_ids::InformationElementId;
+pub use crate::management_frame::{FrameEvent, FrameTransmission, FrameTxStatusEvent};
+pub use crate::mesh::{JoinMeshRequest, MeshConfig};
+pub use crate::mesh_path::{MeshPath, MeshPathFlags, MeshPaths};
 pub use crate::regulatory::{
-    RegulatoryChange, RegulatoryInformation, RegulatoryInitiator, RegulatoryRegion,
+    set_regulatory, RegulatoryChange, RegulatoryFlags, RegulatoryHint, RegulatoryInformation,
+    RegulatoryInitiator, RegulatoryLimits, RegulatoryOrganization, RegulatoryRegion,
+    RegulatoryRule, WmmRule,
+};
+pub use crate::regulatory_database::RegulatoryDatabase;
+pub use crate::remain_on_channel::RemainOnChannelEvent;
+pub use crate::roaming::{
+    BssTransitionManagementQuery, BssTransitionManagementRequest, BssTransitionManagementResponse,
+    BssTransitionRequestMode, BssTransitionStatus, ConnectParameters,
+    ExternalAuthenticationRequest, RoamEvent,
+};
+pub use crate::station::{
+    StationFlagUpdate, StationFlags, StationInformation, StationParameters, StationRemoval,
+};
+pub use crate::wireless_interface::{
+    get_wireless_interfaces, new_wireless_interface, WirelessDeviceId, WirelessInterface,
 };
-pub use crate::wireless_interface::{get_wireless_interfaces, WirelessDeviceId, WirelessInterface};
-pub use crate::wireless_phy::get_wireless_phys;
+pub use crate::wireless_phy::{get_wireless_phys, WirelessPhy};
 
 fn join_to_string<T>(values: T, separator: &str) -> String
 where
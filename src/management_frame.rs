@@ -0,0 +1,350 @@
+//! ## Management Frame Exchange
+//!
+//! Registration for received management frames, transmission of management frames and the
+//! transmit status of transmitted frames.
+//!
+//! Frame registrations belong to the netlink socket used for the registration, received frames
+//! are delivered to that socket until it is closed.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::Attribute;
+use crate::commands::Command;
+use crate::frame::{Frame, FrameSubtype};
+use crate::pack::flag_attribute;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, MessageMode, Socket};
+
+/// Management frame to transmit
+#[derive(Clone, Debug, PartialEq)]
+pub struct FrameTransmission {
+    frame: Vec<u8>,
+    frequency: Option<u32>,
+    wait: Option<u32>,
+    offchannel: bool,
+    no_cck_rate: bool,
+    dont_wait_for_ack: bool,
+}
+
+impl FrameTransmission {
+    /// Create transmission of the frame, including the management frame header
+    pub fn new(frame: &[u8]) -> FrameTransmission {
+        FrameTransmission {
+            frame: frame.to_vec(),
+            frequency: None,
+            wait: None,
+            offchannel: false,
+            no_cck_rate: false,
+            dont_wait_for_ack: false,
+        }
+    }
+
+    /// Frequency in MHz to transmit on, the operating channel is used if not given
+    pub fn frequency(mut self, frequency: u32) -> Self {
+        self.frequency = Some(frequency);
+        self
+    }
+
+    /// Time in milliseconds to wait on the channel for a response after the transmission
+    pub fn wait(mut self, wait: u32) -> Self {
+        self.wait = Some(wait);
+        self
+    }
+
+    /// Allow transmission on another channel than the operating channel
+    pub fn offchannel(mut self, offchannel: bool) -> Self {
+        self.offchannel = offchannel;
+        self
+    }
+
+    /// Do not use CCK rates for the transmission
+    pub fn no_cck_rate(mut self, no_cck_rate: bool) -> Self {
+        self.no_cck_rate = no_cck_rate;
+        self
+    }
+
+    /// Do not wait for an acknowledgement, no transmit status is reported
+    pub fn dont_wait_for_ack(mut self, dont_wait_for_ack: bool) -> Self {
+        self.dont_wait_for_ack = dont_wait_for_ack;
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        // Frame control, duration, three addresses and sequence control
+        if self.frame.len() < 24 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Frame too short").into());
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![netlink::Attribute::new_bytes(Attribute::Frame, &self.frame)];
+        if let Some(frequency) = self.frequency {
+            attributes.push(netlink::Attribute::new(Attribute::WiphyFreq, frequency));
+        }
+        if let Some(wait) = self.wait {
+            attributes.push(netlink::Attribute::new(Attribute::Duration, wait));
+        }
+        if self.offchannel {
+            attributes.push(flag_attribute(Attribute::OffchannelTxOk));
+        }
+        if self.no_cck_rate {
+            attributes.push(flag_attribute(Attribute::TxNoCckRate));
+        }
+        if self.dont_wait_for_ack {
+            attributes.push(flag_attribute(Attribute::DontWaitForAck));
+        }
+        attributes
+    }
+}
+
+/// Received management frame
+pub struct FrameEvent {
+    /// Frequency in MHz the frame was received on
+    pub frequency: Option<u32>,
+    /// Signal strength of the frame in dBm
+    pub signal: Option<i32>,
+    /// Frame, including the management frame header
+    pub data: Vec<u8>,
+    /// Unpacked frame, none if the frame could not be unpacked
+    pub frame: Option<Frame>,
+}
+
+impl FrameEvent {
+    /// Decode a Frame message
+    pub fn from_message(message: &generic::Message) -> Result<FrameEvent, Error> {
+        if Command::convert_from(message.command) != Some(Command::Frame) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "Not a frame").into());
+        }
+        let mut frequency = None;
+        let mut signal = None;
+        let mut data = None;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::WiphyFreq) => {
+                    frequency = Some(attr.as_u32()?);
+                }
+                Some(Attribute::RxSignalDbm) => {
+                    signal = Some(attr.as_i32()?);
+                }
+                Some(Attribute::Frame) => {
+                    data = Some(attr.as_bytes());
+                }
+                _ => (),
+            }
+        }
+        let data =
+            data.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing frame"))?;
+        let frame = Frame::unpack(&data).ok();
+        Ok(FrameEvent {
+            frequency,
+            signal,
+            data,
+            frame,
+        })
+    }
+}
+
+impl fmt::Display for FrameEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.frame {
+            Some(ref frame) => write!(f, "Frame {}", frame)?,
+            None => write!(f, "Frame {} bytes", self.data.len())?,
+        }
+        if let Some(frequency) = self.frequency {
+            write!(f, " Frequency {} MHz", frequency)?;
+        }
+        if let Some(signal) = self.signal {
+            write!(f, " Signal {} dBm", signal)?;
+        }
+        Ok(())
+    }
+}
+
+/// Transmit status of a transmitted management frame
+pub struct FrameTxStatusEvent {
+    /// Cookie returned when the frame was transmitted
+    pub cookie: u64,
+    /// The frame was acknowledged by the receiver
+    pub ack: bool,
+    /// Transmitted frame
+    pub data: Vec<u8>,
+}
+
+impl FrameTxStatusEvent {
+    /// Decode a FrameTxStatus message
+    pub fn from_message(message: &generic::Message) -> Result<FrameTxStatusEvent, Error> {
+        if Command::convert_from(message.command) != Some(Command::FrameTxStatus) {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidData, "Not a frame transmit status").into(),
+            );
+        }
+        let mut cookie = None;
+        let mut ack = false;
+        let mut data = vec![];
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Cookie) => {
+                    cookie = Some(attr.as_u64()?);
+                }
+                Some(Attribute::Ack) => {
+                    ack = true;
+                }
+                Some(Attribute::Frame) => {
+                    data = attr.as_bytes();
+                }
+                _ => (),
+            }
+        }
+        let cookie =
+            cookie.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing cookie"))?;
+        Ok(FrameTxStatusEvent { cookie, ack, data })
+    }
+}
+
+impl fmt::Display for FrameTxStatusEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Frame Transmit Status Cookie {} {}",
+            self.cookie,
+            if self.ack {
+                "Acknowledged"
+            } else {
+                "Not Acknowledged"
+            }
+        )
+    }
+}
+
+impl WirelessInterface {
+    /// Register for received management frames of the subtype starting with the match prefix
+    ///
+    /// For action frames the match prefix is compared with the frame body, starting with the
+    /// category, an empty prefix matches all frames of the subtype.
+    pub fn register_frame(
+        &self,
+        socket: &mut Socket,
+        subtype: FrameSubtype,
+        match_prefix: &[u8],
+    ) -> Result<(), Error> {
+        let frame_type = subtype.management_frame_control().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Not a management frame subtype",
+            )
+        })?;
+        let mut msg = self.prepare_message(Command::RegisterFrame, MessageMode::Acknowledge)?;
+        msg.append_attribute(netlink::Attribute::new(Attribute::FrameType, frame_type));
+        msg.append_attribute(netlink::Attribute::new_bytes(
+            Attribute::FrameMatch,
+            match_prefix,
+        ));
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Transmit a management frame, the cookie identifies the transmit status event
+    ///
+    /// No cookie is returned when not waiting for an acknowledgement.
+    pub fn transmit_frame(
+        &self,
+        socket: &mut Socket,
+        transmission: &FrameTransmission,
+    ) -> Result<Option<u64>, Error> {
+        transmission.validate()?;
+        let mut msg = self.prepare_message(Command::Frame, MessageMode::Acknowledge)?;
+        for attribute in transmission.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        let mut cookie = None;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+            for m in messages {
+                let (_, msg) = generic::Message::unpack(&m.data)?;
+                for attr in &msg.attributes {
+                    if Attribute::convert_from(attr.identifier) == Some(Attribute::Cookie) {
+                        cookie = Some(attr.as_u64()?);
+                    }
+                }
+            }
+        }
+        Ok(cookie)
+    }
+
+    /// Stop waiting on the channel for a response to the transmitted frame
+    pub fn cancel_frame_wait(&self, socket: &mut Socket, cookie: u64) -> Result<(), Error> {
+        let mut msg = self.prepare_message(Command::FrameWaitCancel, MessageMode::Acknowledge)?;
+        msg.append_attribute(netlink::Attribute::new(Attribute::Cookie, cookie));
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_transmission() {
+        let mut frame = vec![0xd0, 0x00, 0x00, 0x00];
+        frame.extend_from_slice(&[0xff; 18]);
+        assert!(FrameTransmission::new(&frame).validate().is_err());
+        frame.extend_from_slice(&[0x00, 0x00, 0x04, 0x0a]);
+        let transmission = FrameTransmission::new(&frame)
+            .frequency(2437)
+            .wait(200)
+            .offchannel(true);
+        assert!(transmission.validate().is_ok());
+        assert_eq!(transmission.attributes().len(), 4);
+        assert_eq!(
+            FrameSubtype::Action.management_frame_control(),
+            Some(0x00d0)
+        );
+        assert_eq!(FrameSubtype::Data.management_frame_control(), None);
+    }
+
+    #[test]
+    fn frame_event() {
+        let mut data = vec![0x40, 0x00, 0x00, 0x00];
+        data.extend_from_slice(&[0xff; 6]);
+        data.extend_from_slice(&[0x02, 0, 0, 0, 0, 0x01]);
+        data.extend_from_slice(&[0xff; 6]);
+        data.extend_from_slice(&[0x00, 0x00]);
+        let mut message = generic::Message::new(0u16, Command::Frame, MessageMode::None);
+        message.append_attribute(netlink::Attribute::new(Attribute::WiphyFreq, 2437u32));
+        message.append_attribute(netlink::Attribute::new_bytes(Attribute::Frame, &data));
+        let event = FrameEvent::from_message(&message).unwrap();
+        assert_eq!(event.frequency, Some(2437));
+        assert_eq!(event.data, data);
+        assert!(event.frame.is_some());
+
+        // A frame that can not be unpacked is still delivered as bytes
+        let mut message = generic::Message::new(0u16, Command::Frame, MessageMode::None);
+        message.append_attribute(netlink::Attribute::new_bytes(Attribute::Frame, &data[..10]));
+        let event = FrameEvent::from_message(&message).unwrap();
+        assert_eq!(event.data, data[..10].to_vec());
+        assert!(event.frame.is_none());
+        assert_eq!(format!("{}", event), "Frame 10 bytes");
+    }
+}
@@ -0,0 +1,517 @@
+//! ## Mesh Networking
+//!
+//! Joining and leaving 802.11s mesh networks and the mesh configuration of mesh interfaces.
+//!
+
+use std::io;
+
+use crate::attributes::{Attribute, MeshConfigAttribute, MeshPowerMode, MeshSetupAttribute};
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::pack::{flag_attribute, nested_attribute};
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, MessageMode, Socket};
+
+/// Mesh configuration, only the given parameters are changed when setting the configuration
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MeshConfig {
+    /// Initial retry timeout of peer link open messages in milliseconds
+    pub retry_timeout: Option<u16>,
+    /// Confirm timeout of peer link confirm messages in milliseconds
+    pub confirm_timeout: Option<u16>,
+    /// Holding timeout of peer link close messages in milliseconds
+    pub holding_timeout: Option<u16>,
+    /// Maximum number of peer links
+    pub max_peer_links: Option<u16>,
+    /// Maximum number of peer link open retries
+    pub max_retries: Option<u8>,
+    /// Time to live of mesh frames
+    pub ttl: Option<u8>,
+    /// Time to live of path selection elements
+    pub element_ttl: Option<u8>,
+    /// Open peer links with new candidates automatically
+    pub auto_open_peer_links: Option<bool>,
+    /// Maximum number of path request retries
+    pub hwmp_max_preq_retries: Option<u8>,
+    /// Interval of path refreshes in milliseconds
+    pub path_refresh_time: Option<u32>,
+    /// Minimum path discovery timeout in milliseconds
+    pub min_discovery_timeout: Option<u16>,
+    /// Time a discovered path is valid in time units
+    pub hwmp_active_path_timeout: Option<u32>,
+    /// Minimum interval between path requests in time units
+    pub hwmp_preq_min_interval: Option<u16>,
+    /// Time for path selection frames to traverse the mesh in time units
+    pub hwmp_net_diameter_traversal_time: Option<u16>,
+    /// Root mode of the mesh station, 0 when not a root
+    pub hwmp_root_mode: Option<u8>,
+    /// Interval of root announcements in time units
+    pub hwmp_rann_interval: Option<u16>,
+    /// Minimum interval between path errors in time units
+    pub hwmp_perr_min_interval: Option<u16>,
+    /// Time a path to the root is valid in time units
+    pub hwmp_path_to_root_timeout: Option<u32>,
+    /// Interval of proactive path requests of a root in time units
+    pub hwmp_root_interval: Option<u16>,
+    /// Minimum interval between proactive path requests in time units
+    pub hwmp_confirmation_interval: Option<u16>,
+    /// Announce that the mesh station is a gate to other networks
+    pub gate_announcements: Option<bool>,
+    /// Forward mesh frames between peers
+    pub forwarding: Option<bool>,
+    /// Minimum signal in dBm of candidate peers
+    pub rssi_threshold: Option<i32>,
+    /// High throughput (HT) operation mode
+    pub ht_opmode: Option<u16>,
+    /// Default power mode of new peer links
+    pub power_mode: Option<MeshPowerMode>,
+    /// Awake window duration in time units
+    pub awake_window: Option<u16>,
+    /// Inactivity time in seconds before peer links are closed
+    pub peer_link_timeout: Option<u32>,
+}
+
+impl MeshConfig {
+    pub fn new() -> MeshConfig {
+        MeshConfig::default()
+    }
+
+    /// Decode mesh configuration from mesh configuration attributes
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<MeshConfig, Error> {
+        let mut config = MeshConfig::default();
+        for attr in attributes {
+            match MeshConfigAttribute::convert_from(attr.identifier) {
+                Some(MeshConfigAttribute::RetryTimeout) => {
+                    config.retry_timeout = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::ConfirmTimeout) => {
+                    config.confirm_timeout = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HoldingTimeout) => {
+                    config.holding_timeout = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::MaxPeerLinks) => {
+                    config.max_peer_links = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::MaxRetries) => {
+                    config.max_retries = Some(attr.as_u8()?);
+                }
+                Some(MeshConfigAttribute::Ttl) => {
+                    config.ttl = Some(attr.as_u8()?);
+                }
+                Some(MeshConfigAttribute::ElementTtl) => {
+                    config.element_ttl = Some(attr.as_u8()?);
+                }
+                Some(MeshConfigAttribute::AutoOpenPeerLinks) => {
+                    config.auto_open_peer_links = Some(attr.as_u8()? != 0);
+                }
+                Some(MeshConfigAttribute::HwmpMaxPreqRetries) => {
+                    config.hwmp_max_preq_retries = Some(attr.as_u8()?);
+                }
+                Some(MeshConfigAttribute::PathRefreshTime) => {
+                    config.path_refresh_time = Some(attr.as_u32()?);
+                }
+                Some(MeshConfigAttribute::MinDiscoveryTimeout) => {
+                    config.min_discovery_timeout = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HwmpActivePathTimeout) => {
+                    config.hwmp_active_path_timeout = Some(attr.as_u32()?);
+                }
+                Some(MeshConfigAttribute::HwmpPreqMinInterval) => {
+                    config.hwmp_preq_min_interval = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HwmpNetDiameterTraversalTime) => {
+                    config.hwmp_net_diameter_traversal_time = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HwmpRootMode) => {
+                    config.hwmp_root_mode = Some(attr.as_u8()?);
+                }
+                Some(MeshConfigAttribute::HwmpRannInterval) => {
+                    config.hwmp_rann_interval = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HwmpPerrMinInterval) => {
+                    config.hwmp_perr_min_interval = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HwmpPathToRootTimeout) => {
+                    config.hwmp_path_to_root_timeout = Some(attr.as_u32()?);
+                }
+                Some(MeshConfigAttribute::HwmpRootInterval) => {
+                    config.hwmp_root_interval = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::HwmpConfirmationInterval) => {
+                    config.hwmp_confirmation_interval = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::GateAnnouncements) => {
+                    config.gate_announcements = Some(attr.as_u8()? != 0);
+                }
+                Some(MeshConfigAttribute::Forwarding) => {
+                    config.forwarding = Some(attr.as_u8()? != 0);
+                }
+                Some(MeshConfigAttribute::RssiThreshold) => {
+                    config.rssi_threshold = Some(attr.as_i32()?);
+                }
+                Some(MeshConfigAttribute::HtOpmode) => {
+                    config.ht_opmode = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::PowerMode) => {
+                    config.power_mode = MeshPowerMode::convert_from(attr.as_u32()?);
+                }
+                Some(MeshConfigAttribute::AwakeWindow) => {
+                    config.awake_window = Some(attr.as_u16()?);
+                }
+                Some(MeshConfigAttribute::PeerLinkTimeout) => {
+                    config.peer_link_timeout = Some(attr.as_u32()?);
+                }
+                _ => (),
+            }
+        }
+        Ok(config)
+    }
+
+    /// Mesh configuration attributes
+    pub(crate) fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        let u8_values = [
+            (MeshConfigAttribute::MaxRetries, self.max_retries),
+            (MeshConfigAttribute::Ttl, self.ttl),
+            (MeshConfigAttribute::ElementTtl, self.element_ttl),
+            (
+                MeshConfigAttribute::AutoOpenPeerLinks,
+                self.auto_open_peer_links.map(u8::from),
+            ),
+            (
+                MeshConfigAttribute::HwmpMaxPreqRetries,
+                self.hwmp_max_preq_retries,
+            ),
+            (MeshConfigAttribute::HwmpRootMode, self.hwmp_root_mode),
+            (
+                MeshConfigAttribute::GateAnnouncements,
+                self.gate_announcements.map(u8::from),
+            ),
+            (
+                MeshConfigAttribute::Forwarding,
+                self.forwarding.map(u8::from),
+            ),
+        ];
+        for (id, value) in u8_values.iter() {
+            if let Some(value) = value {
+                attributes.push(netlink::Attribute::new(id.clone(), *value));
+            }
+        }
+        let u16_values = [
+            (MeshConfigAttribute::RetryTimeout, self.retry_timeout),
+            (MeshConfigAttribute::ConfirmTimeout, self.confirm_timeout),
+            (MeshConfigAttribute::HoldingTimeout, self.holding_timeout),
+            (MeshConfigAttribute::MaxPeerLinks, self.max_peer_links),
+            (
+                MeshConfigAttribute::MinDiscoveryTimeout,
+                self.min_discovery_timeout,
+            ),
+            (
+                MeshConfigAttribute::HwmpPreqMinInterval,
+                self.hwmp_preq_min_interval,
+            ),
+            (
+                MeshConfigAttribute::HwmpNetDiameterTraversalTime,
+                self.hwmp_net_diameter_traversal_time,
+            ),
+            (
+                MeshConfigAttribute::HwmpRannInterval,
+                self.hwmp_rann_interval,
+            ),
+            (
+                MeshConfigAttribute::HwmpPerrMinInterval,
+                self.hwmp_perr_min_interval,
+            ),
+            (
+                MeshConfigAttribute::HwmpRootInterval,
+                self.hwmp_root_interval,
+            ),
+            (
+                MeshConfigAttribute::HwmpConfirmationInterval,
+                self.hwmp_confirmation_interval,
+            ),
+            (MeshConfigAttribute::HtOpmode, self.ht_opmode),
+            (MeshConfigAttribute::AwakeWindow, self.awake_window),
+        ];
+        for (id, value) in u16_values.iter() {
+            if let Some(value) = value {
+                attributes.push(netlink::Attribute::new(id.clone(), *value));
+            }
+        }
+        let u32_values = [
+            (MeshConfigAttribute::PathRefreshTime, self.path_refresh_time),
+            (
+                MeshConfigAttribute::HwmpActivePathTimeout,
+                self.hwmp_active_path_timeout,
+            ),
+            (
+                MeshConfigAttribute::HwmpPathToRootTimeout,
+                self.hwmp_path_to_root_timeout,
+            ),
+            (
+                MeshConfigAttribute::PowerMode,
+                self.power_mode.clone().map(u32::from),
+            ),
+            (MeshConfigAttribute::PeerLinkTimeout, self.peer_link_timeout),
+        ];
+        for (id, value) in u32_values.iter() {
+            if let Some(value) = value {
+                attributes.push(netlink::Attribute::new(id.clone(), *value));
+            }
+        }
+        if let Some(threshold) = self.rssi_threshold {
+            attributes.push(netlink::Attribute::new(
+                MeshConfigAttribute::RssiThreshold,
+                threshold,
+            ));
+        }
+        attributes
+    }
+}
+
+/// Request to join a mesh network
+#[derive(Clone, Debug, PartialEq)]
+pub struct JoinMeshRequest {
+    mesh_id: Vec<u8>,
+    channel: Option<ChannelDefinition>,
+    basic_rates: Option<Vec<u8>>,
+    beacon_interval: Option<u32>,
+    dtim_period: Option<u32>,
+    config: MeshConfig,
+    information_elements: Option<Vec<u8>>,
+    user_space_security: bool,
+}
+
+impl JoinMeshRequest {
+    /// Create request to join the mesh network with the given mesh identifier
+    pub fn new(mesh_id: &[u8]) -> JoinMeshRequest {
+        JoinMeshRequest {
+            mesh_id: mesh_id.to_vec(),
+            channel: None,
+            basic_rates: None,
+            beacon_interval: None,
+            dtim_period: None,
+            config: MeshConfig::default(),
+            information_elements: None,
+            user_space_security: false,
+        }
+    }
+
+    /// Operating channel of the mesh
+    pub fn channel(mut self, channel: ChannelDefinition) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Basic rates in units of 500 kbit/s, requires the channel to be given
+    pub fn basic_rates(mut self, rates: &[u8]) -> Self {
+        self.basic_rates = Some(rates.to_vec());
+        self
+    }
+
+    /// Beacon interval in time units
+    pub fn beacon_interval(mut self, interval: u32) -> Self {
+        self.beacon_interval = Some(interval);
+        self
+    }
+
+    /// DTIM period in beacon intervals
+    pub fn dtim_period(mut self, period: u32) -> Self {
+        self.dtim_period = Some(period);
+        self
+    }
+
+    /// Mesh configuration used when joining
+    pub fn config(mut self, config: MeshConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Information elements added to beacons and peering frames
+    pub fn information_elements(mut self, ies: &[u8]) -> Self {
+        self.information_elements = Some(ies.to_vec());
+        self
+    }
+
+    /// Authentication, authenticated peering exchange and peer link management in user space,
+    /// as used for secure mesh networks
+    pub fn user_space_security(mut self, enabled: bool) -> Self {
+        self.user_space_security = enabled;
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if self.mesh_id.is_empty() || self.mesh_id.len() > 32 {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidInput, "Invalid mesh identifier").into(),
+            );
+        }
+        if self.basic_rates.is_some() && self.channel.is_none() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Basic rates require a channel",
+            )
+            .into());
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![netlink::Attribute::new_bytes(
+            Attribute::MeshId,
+            &self.mesh_id,
+        )];
+        if let Some(ref channel) = self.channel {
+            attributes.extend(channel.attributes());
+        }
+        if let Some(ref rates) = self.basic_rates {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::BssBasicRates,
+                rates,
+            ));
+        }
+        if let Some(interval) = self.beacon_interval {
+            attributes.push(netlink::Attribute::new(Attribute::BeaconInterval, interval));
+        }
+        if let Some(period) = self.dtim_period {
+            attributes.push(netlink::Attribute::new(Attribute::DtimPeriod, period));
+        }
+        let config = self.config.attributes();
+        if !config.is_empty() {
+            attributes.push(nested_attribute(Attribute::MeshConfig, &config));
+        }
+        let mut setup = vec![];
+        if let Some(ref ies) = self.information_elements {
+            setup.push(netlink::Attribute::new_bytes(
+                MeshSetupAttribute::InformationElement,
+                ies,
+            ));
+        }
+        if self.user_space_security {
+            setup.push(flag_attribute(MeshSetupAttribute::UserspaceAuthentication));
+            setup.push(flag_attribute(MeshSetupAttribute::UserspaceAmpe));
+            setup.push(flag_attribute(MeshSetupAttribute::UserspaceMpm));
+        }
+        if !setup.is_empty() {
+            attributes.push(nested_attribute(Attribute::MeshSetup, &setup));
+        }
+        attributes
+    }
+}
+
+impl WirelessInterface {
+    /// Join a mesh network
+    pub fn join_mesh(&self, socket: &mut Socket, request: &JoinMeshRequest) -> Result<(), Error> {
+        request.validate()?;
+        let mut msg = self.prepare_message(Command::JoinMesh, MessageMode::Acknowledge)?;
+        for attribute in request.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Leave the mesh network
+    pub fn leave_mesh(&self, socket: &mut Socket) -> Result<(), Error> {
+        let msg = self.prepare_message(Command::LeaveMesh, MessageMode::Acknowledge)?;
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the mesh configuration of the interface
+    pub fn get_mesh_config(&self, socket: &mut Socket) -> Result<MeshConfig, Error> {
+        let msg = self.prepare_message(Command::GetMeshConfig, MessageMode::Acknowledge)?;
+        socket.send_message(&msg)?;
+        let mut config = None;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+            for m in messages {
+                let (_, msg) = generic::Message::unpack(&m.data)?;
+                if Command::convert_from(msg.command) != Some(Command::GetMeshConfig) {
+                    continue;
+                }
+                for attr in &msg.attributes {
+                    if Attribute::convert_from(attr.identifier) == Some(Attribute::MeshConfig) {
+                        let (_, attrs) = netlink::Attribute::unpack_all(&attr.as_bytes());
+                        config = Some(MeshConfig::from_attributes(&attrs)?);
+                    }
+                }
+            }
+        }
+        config.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "Missing mesh configuration").into()
+        })
+    }
+
+    /// Change the mesh configuration of the interface
+    pub fn set_mesh_config(&self, socket: &mut Socket, config: &MeshConfig) -> Result<(), Error> {
+        let mut msg = self.prepare_message(Command::SetMeshConfig, MessageMode::Acknowledge)?;
+        msg.append_attribute(nested_attribute(
+            Attribute::MeshConfig,
+            &config.attributes(),
+        ));
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mesh_config() {
+        let config = MeshConfig {
+            max_peer_links: Some(16),
+            ttl: Some(31),
+            forwarding: Some(false),
+            rssi_threshold: Some(-80),
+            power_mode: Some(MeshPowerMode::LightSleep),
+            ..MeshConfig::default()
+        };
+        let attributes = config.attributes();
+        assert_eq!(attributes.len(), 5);
+        assert_eq!(MeshConfig::from_attributes(&attributes).unwrap(), config);
+    }
+
+    #[test]
+    fn join_mesh_request() {
+        let request = JoinMeshRequest::new(b"mesh").basic_rates(&[12, 24]);
+        assert!(request.validate().is_err());
+        let request = request.channel(ChannelDefinition::new(2412));
+        assert!(request.validate().is_ok());
+        assert!(JoinMeshRequest::new(&[]).validate().is_err());
+        let attributes = JoinMeshRequest::new(b"mesh")
+            .user_space_security(true)
+            .attributes();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(
+            Attribute::convert_from(attributes[1].identifier),
+            Some(Attribute::MeshSetup)
+        );
+    }
+}
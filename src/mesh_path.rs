@@ -0,0 +1,364 @@
+//! ## Mesh Paths
+//!
+//! Mesh path and mesh proxy path tables of mesh interfaces, the forwarding state of the mesh.
+//!
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::io;
+
+use crate::attributes::{Attribute, MeshPathInfoAttribute};
+use crate::commands::Command;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, HardwareAddress, MessageMode, Socket};
+
+bitflags! {
+    /// Mesh path flags
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct MeshPathFlags: u8 {
+        /// The path is active
+        const ACTIVE = 1 << 0;
+        /// The path discovery process is running
+        const RESOLVING = 1 << 1;
+        /// The sequence number is valid
+        const SEQUENCE_NUMBER_VALID = 1 << 2;
+        /// The path is fixed
+        const FIXED = 1 << 3;
+        /// The path discovery process succeeded
+        const RESOLVED = 1 << 4;
+    }
+}
+
+/// Mesh path or mesh proxy path
+#[derive(Clone, Debug, PartialEq)]
+pub struct MeshPath {
+    /// Destination of the path
+    pub destination: HardwareAddress,
+    /// Next hop towards the destination, or the mesh proxy of a proxy path
+    pub next_hop: HardwareAddress,
+    /// Number of frames queued for the destination
+    pub frame_queue_length: Option<u32>,
+    /// Destination sequence number
+    pub sequence_number: Option<u32>,
+    /// Path metric
+    pub metric: Option<u32>,
+    /// Time in milliseconds until the path expires
+    pub expiration_time: Option<u32>,
+    /// Path flags
+    pub flags: MeshPathFlags,
+    /// Path discovery timeout in milliseconds
+    pub discovery_timeout: Option<u32>,
+    /// Number of path discovery retries
+    pub discovery_retries: Option<u8>,
+    /// Number of hops to the destination
+    pub hop_count: Option<u8>,
+    /// Number of times the path has changed
+    pub path_change_count: Option<u32>,
+}
+
+impl MeshPath {
+    /// Decode mesh path from the attributes of a NewMeshPath message
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<MeshPath, Error> {
+        let mut destination = None;
+        let mut next_hop = None;
+        let mut information = vec![];
+        for attr in attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Mac) => {
+                    destination = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::MpathNextHop) => {
+                    next_hop = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::MpathInfo) => {
+                    let (_, attrs) = netlink::Attribute::unpack_all(&attr.as_bytes());
+                    information = attrs;
+                }
+                _ => (),
+            }
+        }
+        let (destination, next_hop) = match (destination, next_hop) {
+            (Some(destination), Some(next_hop)) => (destination, next_hop),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Missing mesh path destination or next hop",
+                )
+                .into());
+            }
+        };
+        let mut path = MeshPath {
+            destination,
+            next_hop,
+            frame_queue_length: None,
+            sequence_number: None,
+            metric: None,
+            expiration_time: None,
+            flags: MeshPathFlags::empty(),
+            discovery_timeout: None,
+            discovery_retries: None,
+            hop_count: None,
+            path_change_count: None,
+        };
+        path.parse_information(&information)?;
+        Ok(path)
+    }
+
+    fn parse_information(&mut self, attributes: &[netlink::Attribute]) -> Result<(), Error> {
+        for attr in attributes {
+            match MeshPathInfoAttribute::convert_from(attr.identifier) {
+                Some(MeshPathInfoAttribute::FrameQueueLength) => {
+                    self.frame_queue_length = Some(attr.as_u32()?);
+                }
+                Some(MeshPathInfoAttribute::SequenceNumber) => {
+                    self.sequence_number = Some(attr.as_u32()?);
+                }
+                Some(MeshPathInfoAttribute::Metric) => {
+                    self.metric = Some(attr.as_u32()?);
+                }
+                Some(MeshPathInfoAttribute::ExpirationTime) => {
+                    self.expiration_time = Some(attr.as_u32()?);
+                }
+                Some(MeshPathInfoAttribute::Flags) => {
+                    self.flags = MeshPathFlags::from_bits_truncate(attr.as_u8()?);
+                }
+                Some(MeshPathInfoAttribute::DiscoveryTimeout) => {
+                    self.discovery_timeout = Some(attr.as_u32()?);
+                }
+                Some(MeshPathInfoAttribute::DiscoveryRetries) => {
+                    self.discovery_retries = Some(attr.as_u8()?);
+                }
+                Some(MeshPathInfoAttribute::HopCount) => {
+                    self.hop_count = Some(attr.as_u8()?);
+                }
+                Some(MeshPathInfoAttribute::PathChange) => {
+                    self.path_change_count = Some(attr.as_u32()?);
+                }
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for MeshPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Mesh Path {} via {}", self.destination, self.next_hop)?;
+        if let Some(metric) = self.metric {
+            write!(f, " Metric {}", metric)?;
+        }
+        if let Some(hop_count) = self.hop_count {
+            write!(f, " Hops {}", hop_count)?;
+        }
+        write!(f, " {:?}", self.flags)
+    }
+}
+
+/// Iterator over the mesh paths of a dump, the paths are received as the iteration proceeds
+pub struct MeshPaths<'a> {
+    socket: &'a mut Socket,
+    paths: VecDeque<Result<MeshPath, Error>>,
+    done: bool,
+}
+
+impl<'a> Iterator for MeshPaths<'a> {
+    type Item = Result<MeshPath, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(path) = self.paths.pop_front() {
+                return Some(path);
+            }
+            if self.done {
+                return None;
+            }
+            let messages = match self.socket.receive_messages() {
+                Ok(messages) => messages,
+                Err(error) => {
+                    self.done = true;
+                    return Some(Err(error));
+                }
+            };
+            if messages.is_empty() {
+                self.done = true;
+            }
+            for m in messages {
+                let path = generic::Message::unpack(&m.data).and_then(|(_, msg)| {
+                    if Command::convert_from(msg.command) == Some(Command::NewMeshPath) {
+                        MeshPath::from_attributes(&msg.attributes).map(Some)
+                    } else {
+                        Ok(None)
+                    }
+                });
+                match path {
+                    Ok(Some(path)) => self.paths.push_back(Ok(path)),
+                    Ok(None) => (),
+                    Err(error) => self.paths.push_back(Err(error)),
+                }
+            }
+        }
+    }
+}
+
+impl WirelessInterface {
+    fn mesh_path_request(
+        &self,
+        socket: &mut Socket,
+        command: Command,
+        destination: Option<HardwareAddress>,
+        next_hop: Option<HardwareAddress>,
+    ) -> Result<(), Error> {
+        let mut msg = self.prepare_message(command, MessageMode::Acknowledge)?;
+        if let Some(destination) = destination {
+            msg.append_attribute(netlink::Attribute::new(Attribute::Mac, destination));
+        }
+        if let Some(next_hop) = next_hop {
+            msg.append_attribute(netlink::Attribute::new(Attribute::MpathNextHop, next_hop));
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn get_path(
+        &self,
+        socket: &mut Socket,
+        command: Command,
+        destination: HardwareAddress,
+    ) -> Result<MeshPath, Error> {
+        let mut msg = self.prepare_message(command, MessageMode::Acknowledge)?;
+        msg.append_attribute(netlink::Attribute::new(Attribute::Mac, destination));
+        socket.send_message(&msg)?;
+        let paths = MeshPaths {
+            socket,
+            paths: VecDeque::new(),
+            done: false,
+        };
+        let mut path = None;
+        for p in paths {
+            path = Some(p?);
+        }
+        path.ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No mesh path").into())
+    }
+
+    fn dump_paths<'a>(
+        &self,
+        socket: &'a mut Socket,
+        command: Command,
+    ) -> Result<MeshPaths<'a>, Error> {
+        let msg = self.prepare_message(command, MessageMode::Dump)?;
+        socket.send_message(&msg)?;
+        Ok(MeshPaths {
+            socket,
+            paths: VecDeque::new(),
+            done: false,
+        })
+    }
+
+    /// Get the mesh path to the destination
+    pub fn get_mesh_path(
+        &self,
+        socket: &mut Socket,
+        destination: HardwareAddress,
+    ) -> Result<MeshPath, Error> {
+        self.get_path(socket, Command::GetMeshPath, destination)
+    }
+
+    /// Dump the mesh path table
+    pub fn mesh_paths<'a>(&self, socket: &'a mut Socket) -> Result<MeshPaths<'a>, Error> {
+        self.dump_paths(socket, Command::GetMeshPath)
+    }
+
+    /// Add a mesh path to the destination through the next hop
+    pub fn new_mesh_path(
+        &self,
+        socket: &mut Socket,
+        destination: HardwareAddress,
+        next_hop: HardwareAddress,
+    ) -> Result<(), Error> {
+        self.mesh_path_request(
+            socket,
+            Command::NewMeshPath,
+            Some(destination),
+            Some(next_hop),
+        )
+    }
+
+    /// Change the next hop of the mesh path to the destination
+    pub fn set_mesh_path(
+        &self,
+        socket: &mut Socket,
+        destination: HardwareAddress,
+        next_hop: HardwareAddress,
+    ) -> Result<(), Error> {
+        self.mesh_path_request(
+            socket,
+            Command::SetMeshPath,
+            Some(destination),
+            Some(next_hop),
+        )
+    }
+
+    /// Remove the mesh path to the destination, all mesh paths are removed if no destination
+    /// is given
+    pub fn del_mesh_path(
+        &self,
+        socket: &mut Socket,
+        destination: Option<HardwareAddress>,
+    ) -> Result<(), Error> {
+        self.mesh_path_request(socket, Command::DelMeshPath, destination, None)
+    }
+
+    /// Get the mesh proxy path to the destination, the next hop is the mesh proxy
+    pub fn get_mesh_proxy_path(
+        &self,
+        socket: &mut Socket,
+        destination: HardwareAddress,
+    ) -> Result<MeshPath, Error> {
+        self.get_path(socket, Command::GetMeshProxyPath, destination)
+    }
+
+    /// Dump the mesh proxy path table
+    pub fn mesh_proxy_paths<'a>(&self, socket: &'a mut Socket) -> Result<MeshPaths<'a>, Error> {
+        self.dump_paths(socket, Command::GetMeshProxyPath)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pack::nested_attribute;
+
+    #[test]
+    fn mesh_path() {
+        let destination = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x02][..]);
+        let next_hop = HardwareAddress::from(&[0x02, 0, 0, 0, 0, 0x03][..]);
+        let info = [
+            netlink::Attribute::new(MeshPathInfoAttribute::Metric, 170u32),
+            netlink::Attribute::new(MeshPathInfoAttribute::Flags, 0x05u8),
+            netlink::Attribute::new(MeshPathInfoAttribute::HopCount, 2u8),
+        ];
+        let attributes = [
+            netlink::Attribute::new(Attribute::Mac, destination),
+            netlink::Attribute::new(Attribute::MpathNextHop, next_hop),
+            nested_attribute(Attribute::MpathInfo, &info),
+        ];
+        let path = MeshPath::from_attributes(&attributes).unwrap();
+        assert_eq!(path.destination, destination);
+        assert_eq!(path.next_hop, next_hop);
+        assert_eq!(path.metric, Some(170));
+        assert_eq!(path.hop_count, Some(2));
+        assert_eq!(
+            path.flags,
+            MeshPathFlags::ACTIVE | MeshPathFlags::SEQUENCE_NUMBER_VALID
+        );
+        assert!(MeshPath::from_attributes(&attributes[..1]).is_err());
+    }
+}
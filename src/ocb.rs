@@ -0,0 +1,111 @@
+//! ## Outside the Context of a BSS
+//!
+//! Communication outside the context of a BSS (OCB), as used by 802.11p radios for vehicular
+//! communication in the 5.9 GHz band, 5850 MHz to 5925 MHz. OCB requires an interface of the
+//! OCB type, which can be created with `new_wireless_interface`.
+//!
+
+use std::io;
+
+use crate::attributes::InterfaceType;
+use crate::channel::{self, Band};
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust::{Error, MessageMode, Socket};
+
+/// Lower edge in MHz of the 5.9 GHz band
+const OCB_BAND_START: u32 = 5850;
+/// Upper edge in MHz of the 5.9 GHz band
+const OCB_BAND_END: u32 = 5925;
+
+/// Check that the channel is a valid channel within the 5.9 GHz band
+fn check_ocb_channel(channel: &ChannelDefinition) -> Result<(), Error> {
+    channel.validate()?;
+    let in_band = match channel::frequency_to_channel(channel.frequency) {
+        Some((Band::Band5GHz, _)) => channel
+            .segments()
+            .iter()
+            .all(|&(start, end)| start >= OCB_BAND_START && end <= OCB_BAND_END),
+        _ => false,
+    };
+    if !in_band {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Channel outside of the 5.9 GHz band",
+        )
+        .into());
+    }
+    Ok(())
+}
+
+impl WirelessInterface {
+    /// Start communicating outside the context of a BSS on the channel
+    ///
+    /// The channel must be within the 5.9 GHz band.
+    pub fn join_ocb(&self, socket: &mut Socket, channel: &ChannelDefinition) -> Result<(), Error> {
+        if self.interface_type != InterfaceType::Ocb {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Not an OCB interface").into());
+        }
+        check_ocb_channel(channel)?;
+        let mut msg = self.prepare_message(Command::JoinOcb, MessageMode::Acknowledge)?;
+        for attribute in channel.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Stop communicating outside the context of a BSS
+    pub fn leave_ocb(&self, socket: &mut Socket) -> Result<(), Error> {
+        let msg = self.prepare_message(Command::LeaveOcb, MessageMode::Acknowledge)?;
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::ChannelWidth;
+
+    #[test]
+    fn ocb_channel() {
+        // 10 MHz channels 172 to 184
+        for frequency in (5860..=5920).step_by(10) {
+            let channel =
+                ChannelDefinition::with_width(frequency, ChannelWidth::Width10, frequency);
+            assert!(check_ocb_channel(&channel).is_ok());
+        }
+        assert!(check_ocb_channel(&ChannelDefinition::new(5900)).is_ok());
+        let channel = ChannelDefinition::with_width(5860, ChannelWidth::Width20, 5860);
+        assert!(check_ocb_channel(&channel).is_ok());
+
+        // Outside of the band
+        assert!(check_ocb_channel(&ChannelDefinition::new(5180)).is_err());
+        assert!(check_ocb_channel(&ChannelDefinition::new(5845)).is_err());
+        assert!(check_ocb_channel(&ChannelDefinition::new(5935)).is_err());
+        let channel = ChannelDefinition::with_width(5925, ChannelWidth::Width10, 5925);
+        assert!(check_ocb_channel(&channel).is_err());
+        // Extending beyond the edges of the band
+        assert!(check_ocb_channel(&ChannelDefinition::new(5920)).is_err());
+        let channel = ChannelDefinition::with_width(5850, ChannelWidth::Width10, 5850);
+        assert!(check_ocb_channel(&channel).is_err());
+        let channel = ChannelDefinition::with_width(5900, ChannelWidth::Width40, 5910);
+        assert!(check_ocb_channel(&channel).is_err());
+        // Not a channel center frequency
+        assert!(check_ocb_channel(&ChannelDefinition::new(5862)).is_err());
+    }
+}
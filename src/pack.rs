@@ -0,0 +1,88 @@
+use byteorder::{ByteOrder, NativeEndian};
+
+use netlink_rust::{Attribute, HardwareAddress};
+
+const ATTRIBUTE_HEADER_SIZE: usize = 4;
+
+fn align(size: usize) -> usize {
+    (size + 3) & !3
+}
+
+/// Pack attributes into a byte vector with netlink attribute headers and padding
+pub fn pack_attributes(attributes: &[Attribute]) -> Vec<u8> {
+    let mut data = vec![];
+    for attribute in attributes {
+        let payload = attribute.as_bytes();
+        let length = ATTRIBUTE_HEADER_SIZE + payload.len();
+        let mut header = [0u8; ATTRIBUTE_HEADER_SIZE];
+        NativeEndian::write_u16(&mut header[0..2], length as u16);
+        NativeEndian::write_u16(&mut header[2..4], attribute.identifier);
+        data.extend_from_slice(&header);
+        data.extend_from_slice(&payload);
+        data.resize(data.len() + align(length) - length, 0);
+    }
+    data
+}
+
+/// Create attribute with the provided attributes nested as payload
+pub fn nested_attribute<ID: Into<u16>>(identifier: ID, attributes: &[Attribute]) -> Attribute {
+    Attribute::new_bytes(identifier, &pack_attributes(attributes))
+}
+
+/// Create flag attribute, a flag is set by the presence of the attribute
+pub fn flag_attribute<ID: Into<u16>>(identifier: ID) -> Attribute {
+    Attribute::new_bytes(identifier, &[])
+}
+
+/// Get the octets of a hardware address in transmission order
+pub fn hardware_address_bytes(address: HardwareAddress) -> Vec<u8> {
+    Attribute::new(0u16, address).as_bytes()
+}
+
+/// Convert a cipher or AKM suite selector between the element encoding, the octets in
+/// transmission order read as little endian, and the kernel encoding with the OUI in the most
+/// significant octets
+///
+/// The conversion is its own inverse and is used in both directions.
+pub fn kernel_suite_selector(suite: u32) -> u32 {
+    suite.swap_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::LittleEndian;
+
+    #[test]
+    fn pack_padding() {
+        let attributes = [
+            Attribute::new_bytes(1u16, &[0xaa, 0xbb, 0xcc]),
+            Attribute::new_bytes(2u16, &[]),
+        ];
+        let data = pack_attributes(&attributes);
+        assert_eq!(data.len(), 12);
+        assert_eq!(NativeEndian::read_u16(&data[0..]), 7);
+        assert_eq!(NativeEndian::read_u16(&data[2..]), 1);
+        assert_eq!(&data[4..8], &[0xaa, 0xbb, 0xcc, 0x00]);
+        assert_eq!(NativeEndian::read_u16(&data[8..]), 4);
+        assert_eq!(NativeEndian::read_u16(&data[10..]), 2);
+    }
+
+    #[test]
+    fn pack_nested() {
+        let nested = nested_attribute(3u16, &[Attribute::new_bytes(1u16, &[1, 2, 3, 4])]);
+        let data = nested.as_bytes();
+        assert_eq!(nested.identifier, 3);
+        assert_eq!(data.len(), 8);
+        assert_eq!(NativeEndian::read_u16(&data[0..]), 8);
+        assert_eq!(NativeEndian::read_u16(&data[2..]), 1);
+        assert_eq!(&data[4..], &[1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn suite_selector() {
+        let suite = LittleEndian::read_u32(&[0x00, 0x0f, 0xac, 0x04]);
+        assert_eq!(kernel_suite_selector(suite), 0x000f_ac04);
+        assert_eq!(kernel_suite_selector(0x000f_ac04), suite);
+    }
+}
@@ -1,9 +1,15 @@
 use std::fmt;
+use std::io;
 
-use crate::attributes::{Attribute, RegulatoryRuleAttribute};
+use crate::attributes::{
+    Attribute, RegulatoryRuleAttribute, UserRegulatoryHintType, WmmRuleAttribute,
+};
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::pack::flag_attribute;
 use netlink_rust as netlink;
 use netlink_rust::generic;
-use netlink_rust::Result;
+use netlink_rust::{ConvertFrom, MessageMode, Result, Socket};
 
 bitflags! {
     #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
@@ -26,8 +32,9 @@ bitflags! {
     }
 }
 
-#[derive(Debug, PartialEq)]
-enum RegulatoryOrganization {
+/// Organization defining the DFS requirements of a regulatory domain
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RegulatoryOrganization {
     Unset,
     FCC,
     ETSI,
@@ -83,14 +90,42 @@ impl From<u8> for RegulatoryInitiator {
     }
 }
 
+/// Regulatory limits of a channel
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegulatoryLimits {
+    /// Maximum effective isotropic radiated power (EIRP) in mBm
+    pub maximum_eirp: u32,
+    /// Maximum antenna gain in mBi
+    pub maximum_antenna_gain: u32,
+    /// Flags of the rules covering the channel
+    pub flags: RegulatoryFlags,
+    /// Channel availability check (CAC) time in milliseconds
+    pub channel_available_check_time: u32,
+}
+
+impl RegulatoryLimits {
+    fn combine(self, other: RegulatoryLimits) -> RegulatoryLimits {
+        RegulatoryLimits {
+            maximum_eirp: self.maximum_eirp.min(other.maximum_eirp),
+            maximum_antenna_gain: self.maximum_antenna_gain.min(other.maximum_antenna_gain),
+            flags: self.flags | other.flags,
+            channel_available_check_time: self
+                .channel_available_check_time
+                .max(other.channel_available_check_time),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct RegulatoryRule {
-    start: u32,
-    end: u32,
-    flags: RegulatoryFlags,
-    bandwidth: u32,
-    effective_power: u32,
-    antenna_gain: u32,
-    channel_available_check_time: u32,
+    pub(crate) start: u32,
+    pub(crate) end: u32,
+    pub(crate) flags: RegulatoryFlags,
+    pub(crate) bandwidth: u32,
+    pub(crate) effective_power: u32,
+    pub(crate) antenna_gain: u32,
+    pub(crate) channel_available_check_time: u32,
+    pub(crate) power_spectral_density: Option<i8>,
 }
 
 impl fmt::Display for RegulatoryRule {
@@ -118,6 +153,7 @@ impl RegulatoryRule {
         let mut flags = 0u32;
         let mut effective_power = 0u32;
         let mut channel_available_check_time = 0u32;
+        let mut power_spectral_density = None;
         for attribute in attributes {
             let id = RegulatoryRuleAttribute::from(attribute.identifier);
             match id {
@@ -142,6 +178,9 @@ impl RegulatoryRule {
                 RegulatoryRuleAttribute::ChannelAvailableCheckTime => {
                     channel_available_check_time = attribute.as_u32()?;
                 }
+                RegulatoryRuleAttribute::PowerRulePsd => {
+                    power_spectral_density = Some(attribute.as_i8()?);
+                }
                 _ => (),
             }
         }
@@ -153,8 +192,10 @@ impl RegulatoryRule {
             antenna_gain,
             effective_power,
             channel_available_check_time,
+            power_spectral_density,
         })
     }
+
     fn from_nested_attribute_array(buffer: &[u8]) -> Vec<RegulatoryRule> {
         let mut rules = vec![];
         for attributes in netlink::nested_attribute_array(buffer) {
@@ -164,16 +205,130 @@ impl RegulatoryRule {
         }
         rules
     }
+
+    /// Start of the frequency range in kHz
+    pub fn start_frequency(&self) -> u32 {
+        self.start
+    }
+
+    /// End of the frequency range in kHz
+    pub fn end_frequency(&self) -> u32 {
+        self.end
+    }
+
+    /// Maximum channel bandwidth in kHz
+    pub fn maximum_bandwidth(&self) -> u32 {
+        self.bandwidth
+    }
+
+    /// Maximum antenna gain in mBi
+    pub fn maximum_antenna_gain(&self) -> u32 {
+        self.antenna_gain
+    }
+
+    /// Maximum effective isotropic radiated power (EIRP) in mBm
+    pub fn maximum_eirp(&self) -> u32 {
+        self.effective_power
+    }
+
+    /// Maximum power spectral density in dBm/MHz
+    pub fn power_spectral_density(&self) -> Option<i8> {
+        self.power_spectral_density
+    }
+
+    /// Regulatory flags of the rule
+    pub fn flags(&self) -> RegulatoryFlags {
+        self.flags
+    }
+
+    /// Channel availability check (CAC) time in milliseconds
+    pub fn channel_available_check_time(&self) -> u32 {
+        self.channel_available_check_time
+    }
+
+    fn limits(&self) -> RegulatoryLimits {
+        RegulatoryLimits {
+            maximum_eirp: self.effective_power,
+            maximum_antenna_gain: self.antenna_gain,
+            flags: self.flags,
+            channel_available_check_time: self.channel_available_check_time,
+        }
+    }
+}
+
+/// WMM limits of an access category
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct WmmRule {
+    /// Minimum contention window
+    pub cw_min: u16,
+    /// Maximum contention window
+    pub cw_max: u16,
+    /// Arbitration inter-frame space number
+    pub aifsn: u8,
+    /// Maximum transmit opportunity in units of 32 microseconds
+    pub txop: u16,
+}
+
+impl fmt::Display for WmmRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "CW {}-{} AIFSN {} TXOP {}",
+            self.cw_min, self.cw_max, self.aifsn, self.txop
+        )
+    }
+}
+
+impl WmmRule {
+    /// Decode WMM limits of an access category from attributes
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<WmmRule> {
+        let mut rule = WmmRule::default();
+        for attribute in attributes {
+            match WmmRuleAttribute::convert_from(attribute.identifier) {
+                Some(WmmRuleAttribute::CwMin) => {
+                    rule.cw_min = attribute.as_u16()?;
+                }
+                Some(WmmRuleAttribute::CwMax) => {
+                    rule.cw_max = attribute.as_u16()?;
+                }
+                Some(WmmRuleAttribute::Aifsn) => {
+                    rule.aifsn = attribute.as_u8()?;
+                }
+                Some(WmmRuleAttribute::Txop) => {
+                    rule.txop = attribute.as_u16()?;
+                }
+                _ => (),
+            }
+        }
+        Ok(rule)
+    }
+
+    /// Unpack the WMM rules of the access categories, in the order voice, video, best effort
+    /// and background
+    pub fn from_nested_attribute_array(buffer: &[u8]) -> Result<Vec<WmmRule>> {
+        netlink::nested_attribute_array(buffer)
+            .iter()
+            .map(|attributes| WmmRule::from_attributes(attributes))
+            .collect()
+    }
 }
 
 pub struct RegulatoryInformation {
     country: String,
     region: RegulatoryOrganization,
     rules: Vec<RegulatoryRule>,
+    wiphy: Option<u32>,
+    self_managed: bool,
 }
 
 impl fmt::Display for RegulatoryInformation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(wiphy) = self.wiphy {
+            write!(f, "phy#{} ", wiphy)?;
+            if self.self_managed {
+                write!(f, "(self-managed) ")?;
+            }
+        }
         writeln!(f, "{0} {1:?}", self.country, self.region)?;
         for rule in &(self.rules) {
             writeln!(f, "  {}", rule)?;
@@ -183,10 +338,26 @@ impl fmt::Display for RegulatoryInformation {
 }
 
 impl RegulatoryInformation {
+    pub(crate) fn new(
+        country: String,
+        region: RegulatoryOrganization,
+        rules: Vec<RegulatoryRule>,
+    ) -> RegulatoryInformation {
+        RegulatoryInformation {
+            country,
+            region,
+            rules,
+            wiphy: None,
+            self_managed: false,
+        }
+    }
+
     pub fn from_message(message: &generic::Message) -> Result<RegulatoryInformation> {
         let mut country = String::new();
         let mut region = 0u8;
         let mut rules = vec![];
+        let mut wiphy = None;
+        let mut self_managed = false;
         for attribute in &(message.attributes) {
             let id = Attribute::from(attribute.identifier);
             match id {
@@ -199,6 +370,12 @@ impl RegulatoryInformation {
                 Attribute::RegRules => {
                     rules = RegulatoryRule::from_nested_attribute_array(&attribute.as_bytes());
                 }
+                Attribute::Wiphy => {
+                    wiphy = Some(attribute.as_u32()?);
+                }
+                Attribute::WiphySelfManagedReg => {
+                    self_managed = true;
+                }
                 _ => (),
             }
         }
@@ -206,8 +383,85 @@ impl RegulatoryInformation {
             country,
             region: RegulatoryOrganization::from(region),
             rules,
+            wiphy,
+            self_managed,
         })
     }
+
+    /// ISO 3166-1 alpha2 country code, "00" for the world regulatory domain
+    pub fn country(&self) -> &str {
+        &self.country
+    }
+
+    /// Regulatory organization defining the DFS requirements of the domain
+    pub fn dfs_region(&self) -> RegulatoryOrganization {
+        self.region
+    }
+
+    /// Regulatory rules of the domain, in the order reported by the kernel
+    pub fn rules(&self) -> &[RegulatoryRule] {
+        &self.rules
+    }
+
+    /// Wiphy of a per wiphy regulatory domain, none for the global regulatory domain
+    pub fn wiphy(&self) -> Option<u32> {
+        self.wiphy
+    }
+
+    /// The regulatory domain is managed by the wiphy and not by the kernel
+    pub fn self_managed(&self) -> bool {
+        self.self_managed
+    }
+
+    /// Limits of the channel with the center frequency and width in MHz, none if the channel
+    /// is not allowed
+    ///
+    /// The channel shall be within a rule allowing the width, or within adjacent rules with
+    /// automatic bandwidth.
+    pub fn rules_for(&self, frequency: u32, width: u32) -> Option<RegulatoryLimits> {
+        let start = (frequency * 1000).checked_sub(width * 500)?;
+        let end = frequency * 1000 + width * 500;
+        let rule = self
+            .rules
+            .iter()
+            .find(|r| r.start <= start && end <= r.end && width * 1000 <= r.bandwidth);
+        if let Some(rule) = rule {
+            return Some(rule.limits());
+        }
+        let mut covered = start;
+        let mut limits: Option<RegulatoryLimits> = None;
+        while covered < end {
+            let rule = self.rules.iter().find(|r| {
+                r.flags.contains(RegulatoryFlags::AUTO_BW) && r.start <= covered && covered < r.end
+            })?;
+            limits = Some(match limits {
+                Some(limits) => limits.combine(rule.limits()),
+                None => rule.limits(),
+            });
+            covered = rule.end;
+        }
+        limits
+    }
+
+    /// Check that all segments of the channel are allowed by the rules
+    pub fn check_channel(&self, channel: &ChannelDefinition) -> Result<()> {
+        let segments = channel.segments();
+        if segments.is_empty() {
+            return Err(
+                io::Error::new(io::ErrorKind::InvalidInput, "Invalid channel definition").into(),
+            );
+        }
+        for (start, end) in segments {
+            if self.rules_for((start + end) / 2, end - start).is_none() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Channel not allowed by the regulatory domain",
+                )
+                .into());
+            }
+        }
+        Ok(())
+    }
 }
 
 pub struct RegulatoryChange {
@@ -257,3 +511,229 @@ impl RegulatoryChange {
         })
     }
 }
+
+/// Regulatory hint from user space
+#[derive(Clone, Debug, PartialEq)]
+pub enum RegulatoryHint {
+    /// ISO 3166-1 alpha2 country code of the country the user is in, "00" for the world
+    /// regulatory domain
+    User(String),
+    /// ISO 3166-1 alpha2 country code given by a cellular base station
+    CellBase(String),
+    /// The device is operating indoor or outdoor
+    ///
+    /// With socket owner set the setting is bound to the socket used for the request and is
+    /// reverted to outdoor when the socket is closed, the socket must be kept open for as long
+    /// as the setting should apply.
+    ///
+    /// Without socket owner the kernel ignores the indoor setting and always sets the device
+    /// indoor, so outdoor operation can only be requested with socket owner set.
+    Indoor { indoor: bool, socket_owner: bool },
+}
+
+impl RegulatoryHint {
+    fn validate(&self) -> Result<()> {
+        let alpha2 = match *self {
+            RegulatoryHint::User(ref alpha2) => {
+                if alpha2 == "00" {
+                    return Ok(());
+                }
+                alpha2
+            }
+            RegulatoryHint::CellBase(ref alpha2) => alpha2,
+            RegulatoryHint::Indoor {
+                indoor,
+                socket_owner,
+            } => {
+                if !indoor && !socket_owner {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "Outdoor requires socket owner",
+                    )
+                    .into());
+                }
+                return Ok(());
+            }
+        };
+        if alpha2.len() != 2 || !alpha2.bytes().all(|b| b.is_ascii_uppercase()) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "Invalid country code").into());
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        let hint_type = match *self {
+            RegulatoryHint::User(ref alpha2) => {
+                attributes.push(netlink::Attribute::new_string_with_nul(
+                    Attribute::RegAlpha2,
+                    alpha2,
+                ));
+                UserRegulatoryHintType::User
+            }
+            RegulatoryHint::CellBase(ref alpha2) => {
+                attributes.push(netlink::Attribute::new_string_with_nul(
+                    Attribute::RegAlpha2,
+                    alpha2,
+                ));
+                UserRegulatoryHintType::CellBase
+            }
+            RegulatoryHint::Indoor {
+                indoor,
+                socket_owner,
+            } => {
+                if indoor {
+                    attributes.push(flag_attribute(Attribute::RegIndoor));
+                }
+                if socket_owner {
+                    attributes.push(flag_attribute(Attribute::SocketOwner));
+                }
+                UserRegulatoryHintType::Indoor
+            }
+        };
+        attributes.push(netlink::Attribute::new(
+            Attribute::UserRegHintType,
+            u32::from(hint_type),
+        ));
+        attributes
+    }
+}
+
+/// Request the kernel to apply the regulatory hint
+///
+/// The kernel may reject or ignore the hint, for example when the hint conflicts with a
+/// regulatory domain given by the driver.
+pub fn set_regulatory(socket: &mut Socket, family_id: u16, hint: &RegulatoryHint) -> Result<()> {
+    hint.validate()?;
+    let mut msg = generic::Message::new(
+        family_id,
+        Command::RequestSetRegulatory,
+        MessageMode::Acknowledge,
+    );
+    for attribute in hint.attributes() {
+        msg.append_attribute(attribute);
+    }
+    socket.send_message(&msg)?;
+    loop {
+        let messages = socket.receive_messages()?;
+        if messages.is_empty() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::attributes::ChannelWidth;
+
+    fn rule(
+        start: u32,
+        end: u32,
+        bandwidth: u32,
+        power: u32,
+        flags: RegulatoryFlags,
+    ) -> RegulatoryRule {
+        RegulatoryRule {
+            start: start * 1000,
+            end: end * 1000,
+            flags,
+            bandwidth: bandwidth * 1000,
+            effective_power: power,
+            antenna_gain: 0,
+            channel_available_check_time: 0,
+            power_spectral_density: None,
+        }
+    }
+
+    #[test]
+    fn rules_for() {
+        let info = RegulatoryInformation {
+            country: String::from("SE"),
+            region: RegulatoryOrganization::ETSI,
+            rules: vec![
+                rule(2400, 2483, 40, 2000, RegulatoryFlags::empty()),
+                rule(
+                    5150,
+                    5250,
+                    80,
+                    2300,
+                    RegulatoryFlags::NO_OUTDOOR | RegulatoryFlags::AUTO_BW,
+                ),
+                rule(
+                    5250,
+                    5350,
+                    80,
+                    2000,
+                    RegulatoryFlags::DFS | RegulatoryFlags::AUTO_BW,
+                ),
+            ],
+            wiphy: None,
+            self_managed: false,
+        };
+        let limits = info.rules_for(2412, 20).unwrap();
+        assert_eq!(limits.maximum_eirp, 2000);
+        assert!(limits.flags.is_empty());
+        assert!(info.rules_for(2412, 80).is_none());
+        assert!(info.rules_for(2484, 20).is_none());
+        let limits = info.rules_for(5180, 20).unwrap();
+        assert_eq!(limits.maximum_eirp, 2300);
+        assert!(!limits.flags.contains(RegulatoryFlags::DFS));
+        let limits = info.rules_for(5250, 160).unwrap();
+        assert_eq!(limits.maximum_eirp, 2000);
+        assert!(limits
+            .flags
+            .contains(RegulatoryFlags::DFS | RegulatoryFlags::NO_OUTDOOR));
+        assert!(info.rules_for(5330, 80).is_none());
+        let channel = ChannelDefinition::with_width(5180, ChannelWidth::Width160, 5250);
+        assert!(info.check_channel(&channel).is_ok());
+        let channel = ChannelDefinition::with_width(5180, ChannelWidth::Width80P80, 5210);
+        assert!(info
+            .check_channel(&channel.center_frequency2(5530))
+            .is_err());
+        assert!(info
+            .check_channel(&ChannelDefinition::with_width(
+                10,
+                ChannelWidth::Width80,
+                10
+            ))
+            .is_err());
+    }
+
+    #[test]
+    fn regulatory_hint() {
+        assert!(RegulatoryHint::User(String::from("SE")).validate().is_ok());
+        assert!(RegulatoryHint::User(String::from("00")).validate().is_ok());
+        assert!(RegulatoryHint::User(String::from("se")).validate().is_err());
+        assert!(RegulatoryHint::CellBase(String::from("00"))
+            .validate()
+            .is_err());
+        assert!(RegulatoryHint::CellBase(String::from("SWE"))
+            .validate()
+            .is_err());
+        let hint = RegulatoryHint::Indoor {
+            indoor: true,
+            socket_owner: true,
+        };
+        assert!(hint.validate().is_ok());
+        let attributes = hint.attributes();
+        assert_eq!(attributes.len(), 3);
+        assert_eq!(
+            Attribute::convert_from(attributes[1].identifier),
+            Some(Attribute::SocketOwner)
+        );
+        assert_eq!(attributes[2].as_u32().unwrap(), 2);
+        let hint = RegulatoryHint::Indoor {
+            indoor: false,
+            socket_owner: false,
+        };
+        assert!(hint.validate().is_err());
+        let hint = RegulatoryHint::Indoor {
+            indoor: false,
+            socket_owner: true,
+        };
+        assert!(hint.validate().is_ok());
+        assert_eq!(hint.attributes().len(), 2);
+    }
+}
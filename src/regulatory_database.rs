@@ -0,0 +1,402 @@
+//! ## Regulatory Database
+//!
+//! Offline parsing of the wireless-regdb regulatory database, the binary `regulatory.db` loaded
+//! by the kernel and the `db.txt` source it is built from.
+//!
+//! The signature of the binary database is distributed in a separate file
+//! (`regulatory.db.p7s`) and is not verified here.
+//!
+
+use std::io;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::regulatory::{
+    RegulatoryFlags, RegulatoryInformation, RegulatoryOrganization, RegulatoryRule,
+};
+use netlink_rust::Result;
+
+const DATABASE_MAGIC: u32 = 0x5247_4442;
+const DATABASE_VERSION: u32 = 20;
+// Length of a rule without the optional channel availability check time and WMM pointer
+const RULE_LENGTH: usize = 16;
+
+bitflags! {
+    // Flags of the rules in the binary database
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    struct DatabaseFlags: u8 {
+        const NO_OFDM       = 1;
+        const NO_OUTDOOR    = 1 << 1;
+        const DFS           = 1 << 2;
+        const NO_IR         = 1 << 3;
+        const AUTO_BW       = 1 << 4;
+    }
+}
+
+impl From<DatabaseFlags> for RegulatoryFlags {
+    fn from(value: DatabaseFlags) -> RegulatoryFlags {
+        let mut flags = RegulatoryFlags::empty();
+        flags.set(
+            RegulatoryFlags::NO_OFDM,
+            value.contains(DatabaseFlags::NO_OFDM),
+        );
+        flags.set(
+            RegulatoryFlags::NO_OUTDOOR,
+            value.contains(DatabaseFlags::NO_OUTDOOR),
+        );
+        flags.set(RegulatoryFlags::DFS, value.contains(DatabaseFlags::DFS));
+        flags.set(RegulatoryFlags::NO_IR, value.contains(DatabaseFlags::NO_IR));
+        flags.set(
+            RegulatoryFlags::AUTO_BW,
+            value.contains(DatabaseFlags::AUTO_BW),
+        );
+        flags
+    }
+}
+
+fn invalid_data<T>(description: &str) -> Result<T> {
+    Err(io::Error::new(io::ErrorKind::InvalidData, description).into())
+}
+
+fn bytes(data: &[u8], offset: usize, length: usize) -> Result<&[u8]> {
+    match data.get(offset..offset + length) {
+        Some(bytes) => Ok(bytes),
+        None => invalid_data("Regulatory database truncated"),
+    }
+}
+
+/// Regulatory domains of the wireless-regdb regulatory database
+pub struct RegulatoryDatabase {
+    domains: Vec<RegulatoryInformation>,
+}
+
+impl RegulatoryDatabase {
+    /// Parse the binary database, `regulatory.db`
+    pub fn from_bytes(data: &[u8]) -> Result<RegulatoryDatabase> {
+        let header = bytes(data, 0, 8)?;
+        if BigEndian::read_u32(header) != DATABASE_MAGIC {
+            return invalid_data("Not a regulatory database");
+        }
+        if BigEndian::read_u32(&header[4..]) != DATABASE_VERSION {
+            return invalid_data("Unsupported regulatory database version");
+        }
+        let mut domains = vec![];
+        let mut offset = header.len();
+        loop {
+            let country = bytes(data, offset, 4)?;
+            let collection = usize::from(BigEndian::read_u16(&country[2..])) << 2;
+            if collection == 0 {
+                break;
+            }
+            let alpha2 = String::from_utf8_lossy(&country[..2]).into_owned();
+            domains.push(Self::parse_collection(data, alpha2, collection)?);
+            offset += country.len();
+        }
+        Ok(RegulatoryDatabase { domains })
+    }
+
+    fn parse_collection(
+        data: &[u8],
+        country: String,
+        offset: usize,
+    ) -> Result<RegulatoryInformation> {
+        let header = bytes(data, offset, 3)?;
+        let length = usize::from(header[0]);
+        let count = usize::from(header[1]);
+        if length < header.len() {
+            return invalid_data("Invalid regulatory collection length");
+        }
+        // The rule pointers follow the collection, aligned to two bytes
+        let pointers = bytes(data, offset + ((length + 1) & !1), count * 2)?;
+        let mut rules = vec![];
+        for pointer in pointers.chunks(2) {
+            let offset = usize::from(BigEndian::read_u16(pointer)) << 2;
+            rules.push(Self::parse_rule(data, offset)?);
+        }
+        Ok(RegulatoryInformation::new(
+            country,
+            RegulatoryOrganization::from(header[2]),
+            rules,
+        ))
+    }
+
+    fn parse_rule(data: &[u8], offset: usize) -> Result<RegulatoryRule> {
+        let length = usize::from(bytes(data, offset, 1)?[0]);
+        if length < RULE_LENGTH {
+            return invalid_data("Invalid regulatory rule length");
+        }
+        let rule = bytes(data, offset, length)?;
+        let flags = DatabaseFlags::from_bits_truncate(rule[1]);
+        // The channel availability check time is given in seconds
+        let channel_available_check_time = if length >= RULE_LENGTH + 2 {
+            u32::from(BigEndian::read_u16(&rule[RULE_LENGTH..])) * 1000
+        } else {
+            0
+        };
+        Ok(RegulatoryRule {
+            start: BigEndian::read_u32(&rule[4..]),
+            end: BigEndian::read_u32(&rule[8..]),
+            flags: RegulatoryFlags::from(flags),
+            bandwidth: BigEndian::read_u32(&rule[12..]),
+            effective_power: u32::from(BigEndian::read_u16(&rule[2..])),
+            antenna_gain: 0,
+            channel_available_check_time,
+            power_spectral_density: None,
+        })
+    }
+
+    /// Parse the text database, `db.txt`
+    pub fn from_text(text: &str) -> Result<RegulatoryDatabase> {
+        let mut domains = vec![];
+        let mut current: Option<(String, RegulatoryOrganization, Vec<RegulatoryRule>)> = None;
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(country) = line.strip_prefix("country ") {
+                if let Some((country, region, rules)) = current.take() {
+                    domains.push(RegulatoryInformation::new(country, region, rules));
+                }
+                current = Some(Self::parse_country(country)?);
+            } else if line.starts_with('(') {
+                if let Some((_, _, ref mut rules)) = current {
+                    rules.push(Self::parse_text_rule(line)?);
+                }
+            } else if let Some((country, region, rules)) = current.take() {
+                // Other sections, such as WMM rules, end the country
+                domains.push(RegulatoryInformation::new(country, region, rules));
+            }
+        }
+        if let Some((country, region, rules)) = current.take() {
+            domains.push(RegulatoryInformation::new(country, region, rules));
+        }
+        Ok(RegulatoryDatabase { domains })
+    }
+
+    fn parse_country(line: &str) -> Result<(String, RegulatoryOrganization, Vec<RegulatoryRule>)> {
+        let mut parts = line.splitn(2, ':');
+        let country = parts.next().unwrap_or("").trim();
+        if country.len() != 2 {
+            return invalid_data("Invalid country code");
+        }
+        let region = match parts.next().map(str::trim) {
+            Some("DFS-FCC") => RegulatoryOrganization::FCC,
+            Some("DFS-ETSI") => RegulatoryOrganization::ETSI,
+            Some("DFS-JP") => RegulatoryOrganization::Japan,
+            Some("") | Some("DFS-UNSET") | None => RegulatoryOrganization::Unset,
+            Some(_) => return invalid_data("Invalid DFS region"),
+        };
+        Ok((country.to_string(), region, vec![]))
+    }
+
+    fn parse_text_rule(line: &str) -> Result<RegulatoryRule> {
+        let (band, rest) = Self::split_parenthesis(line)?;
+        let (frequencies, bandwidth) = match band.find('@') {
+            Some(index) => (&band[..index], &band[index + 1..]),
+            None => return invalid_data("Missing rule bandwidth"),
+        };
+        let mut frequencies = frequencies.splitn(2, '-');
+        let start = Self::parse_khz(frequencies.next().unwrap_or(""))?;
+        let end = Self::parse_khz(frequencies.next().unwrap_or(""))?;
+        let bandwidth = Self::parse_khz(bandwidth)?;
+        let rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+        let (power, mut rest) = Self::split_parenthesis(rest)?;
+        let (antenna_gain, effective_power) = Self::parse_power(power)?;
+        let mut flags = RegulatoryFlags::empty();
+        let mut channel_available_check_time = 0;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c == ',' || c.is_whitespace());
+            if rest.is_empty() {
+                break;
+            }
+            if rest.starts_with('(') {
+                // Channel availability check time in seconds
+                let (time, remaining) = Self::split_parenthesis(rest)?;
+                channel_available_check_time = match time.trim().parse::<u32>() {
+                    Ok(time) => time * 1000,
+                    Err(_) => return invalid_data("Invalid channel availability check time"),
+                };
+                rest = remaining;
+                continue;
+            }
+            let (item, remaining) = match rest.find(',') {
+                Some(index) => (&rest[..index], &rest[index..]),
+                None => (rest, ""),
+            };
+            flags |= Self::parse_flag(item.trim())?;
+            rest = remaining;
+        }
+        Ok(RegulatoryRule {
+            start,
+            end,
+            flags,
+            bandwidth,
+            effective_power,
+            antenna_gain,
+            channel_available_check_time,
+            power_spectral_density: None,
+        })
+    }
+
+    fn split_parenthesis(text: &str) -> Result<(&str, &str)> {
+        if !text.starts_with('(') {
+            return invalid_data("Expected parenthesis");
+        }
+        match text.find(')') {
+            Some(index) => Ok((&text[1..index], &text[index + 1..])),
+            None => invalid_data("Unterminated parenthesis"),
+        }
+    }
+
+    fn parse_khz(text: &str) -> Result<u32> {
+        match text.trim().parse::<f64>() {
+            Ok(mhz) if mhz >= 0.0 => Ok((mhz * 1000.0).round() as u32),
+            _ => invalid_data("Invalid frequency"),
+        }
+    }
+
+    // Returns the antenna gain in mBi and the EIRP in mBm
+    fn parse_power(text: &str) -> Result<(u32, u32)> {
+        let mut values = text.split(',').map(str::trim).collect::<Vec<_>>();
+        let eirp = match values.pop() {
+            Some(eirp) => eirp,
+            None => return invalid_data("Missing power"),
+        };
+        let gain = match values.pop() {
+            Some("N/A") | None => 0.0,
+            Some(gain) => match gain.parse::<f64>() {
+                Ok(gain) => gain,
+                Err(_) => return invalid_data("Invalid antenna gain"),
+            },
+        };
+        let eirp = if eirp.ends_with("mW") {
+            match eirp.trim_end_matches("mW").trim().parse::<f64>() {
+                Ok(mw) if mw > 0.0 => 10.0 * mw.log10(),
+                _ => return invalid_data("Invalid power"),
+            }
+        } else {
+            match eirp.parse::<f64>() {
+                Ok(dbm) => dbm,
+                Err(_) => return invalid_data("Invalid power"),
+            }
+        };
+        Ok((
+            (gain * 100.0).round().max(0.0) as u32,
+            (eirp * 100.0).round().max(0.0) as u32,
+        ))
+    }
+
+    fn parse_flag(flag: &str) -> Result<RegulatoryFlags> {
+        let flag = match flag {
+            "NO-OFDM" => RegulatoryFlags::NO_OFDM,
+            "NO-CCK" => RegulatoryFlags::NO_CCK,
+            "NO-INDOOR" => RegulatoryFlags::NO_INDOOR,
+            "NO-OUTDOOR" => RegulatoryFlags::NO_OUTDOOR,
+            "DFS" => RegulatoryFlags::DFS,
+            "PTP-ONLY" => RegulatoryFlags::PTP_ONLY,
+            "PTMP-ONLY" => RegulatoryFlags::PTMP_ONLY,
+            "NO-IR" | "PASSIVE-SCAN" => RegulatoryFlags::NO_IR,
+            "NO-IBSS" => RegulatoryFlags::NO_IBSS,
+            "AUTO-BW" => RegulatoryFlags::AUTO_BW,
+            "IR-CONCURRENT" => RegulatoryFlags::IR_CONCURRENT,
+            "NO-HT40MINUS" => RegulatoryFlags::HT40MINUS,
+            "NO-HT40PLUS" => RegulatoryFlags::HT40PLUS,
+            "NO-80MHZ" => RegulatoryFlags::NO_80MHZ,
+            "NO-160MHZ" => RegulatoryFlags::NO_160MHZ,
+            _ if flag.starts_with("wmmrule=") => RegulatoryFlags::empty(),
+            _ => return invalid_data("Unknown regulatory rule flag"),
+        };
+        Ok(flag)
+    }
+
+    /// Regulatory domains of the database
+    pub fn domains(&self) -> &[RegulatoryInformation] {
+        &self.domains
+    }
+
+    /// Regulatory domain of the country, "00" is the world regulatory domain
+    pub fn get(&self, country: &str) -> Option<&RegulatoryInformation> {
+        self.domains.iter().find(|d| d.country() == country)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_database() {
+        let mut data = vec![];
+        data.extend_from_slice(b"RGDB");
+        data.extend_from_slice(&[0, 0, 0, 20]);
+        // Country SE, collection at 16, terminated with a zero entry
+        data.extend_from_slice(&[b'S', b'E', 0, 4]);
+        data.extend_from_slice(&[0, 0, 0, 0]);
+        // Collection of two rules, ETSI DFS region, rule pointers at 20 to rules at 24 and 40
+        data.extend_from_slice(&[3, 2, 2, 0, 0, 6, 0, 10]);
+        // 2400-2483.5 MHz @ 40 MHz, 20 dBm
+        data.extend_from_slice(&[16, 0, 0x07, 0xd0]);
+        data.extend_from_slice(&2_400_000u32.to_be_bytes());
+        data.extend_from_slice(&2_483_500u32.to_be_bytes());
+        data.extend_from_slice(&40_000u32.to_be_bytes());
+        // 5250-5350 MHz @ 80 MHz, 20 dBm, DFS and AUTO-BW, 60 s CAC
+        data.extend_from_slice(&[20, 0x14, 0x07, 0xd0]);
+        data.extend_from_slice(&5_250_000u32.to_be_bytes());
+        data.extend_from_slice(&5_350_000u32.to_be_bytes());
+        data.extend_from_slice(&80_000u32.to_be_bytes());
+        data.extend_from_slice(&[0, 60, 0, 0]);
+        let database = RegulatoryDatabase::from_bytes(&data).unwrap();
+        let domain = database.get("SE").unwrap();
+        assert_eq!(domain.dfs_region(), RegulatoryOrganization::ETSI);
+        assert_eq!(domain.rules().len(), 2);
+        let rule = &domain.rules()[1];
+        assert_eq!(rule.start_frequency(), 5_250_000);
+        assert_eq!(rule.maximum_eirp(), 2000);
+        assert_eq!(rule.channel_available_check_time(), 60_000);
+        assert_eq!(
+            rule.flags(),
+            RegulatoryFlags::DFS | RegulatoryFlags::AUTO_BW
+        );
+        assert!(RegulatoryDatabase::from_bytes(&data[..20]).is_err());
+        data[3] = b'C';
+        assert!(RegulatoryDatabase::from_bytes(&data).is_err());
+    }
+
+    #[test]
+    fn text_database() {
+        let text = "# Test database\n\
+                    country 00:\n\
+                    \t(2402 - 2472 @ 40), (20)\n\
+                    \n\
+                    wmmrule ETSI:\n\
+                    \tvo_c: cw_min=3, cw_max=7, aifsn=2, cot=2\n\
+                    \n\
+                    country SE: DFS-ETSI\n\
+                    \t(2400 - 2483.5 @ 40), (100 mW)\n\
+                    \t(5250 - 5350 @ 80), (N/A, 200 mW), DFS, AUTO-BW, wmmrule=ETSI\n\
+                    \t(5470 - 5725 @ 160), (500 mW), (600), DFS\n";
+        let database = RegulatoryDatabase::from_text(text).unwrap();
+        assert_eq!(database.domains().len(), 2);
+        assert_eq!(database.get("00").unwrap().rules().len(), 1);
+        let domain = database.get("SE").unwrap();
+        assert_eq!(domain.dfs_region(), RegulatoryOrganization::ETSI);
+        let rules = domain.rules();
+        assert_eq!(rules.len(), 3);
+        assert_eq!(rules[0].end_frequency(), 2_483_500);
+        assert_eq!(rules[0].maximum_eirp(), 2000);
+        assert_eq!(rules[1].maximum_eirp(), 2301);
+        assert_eq!(
+            rules[1].flags(),
+            RegulatoryFlags::DFS | RegulatoryFlags::AUTO_BW
+        );
+        assert_eq!(rules[2].maximum_bandwidth(), 160_000);
+        assert_eq!(rules[2].channel_available_check_time(), 600_000);
+        let limits = domain.rules_for(5260, 20).unwrap();
+        assert!(limits.flags.contains(RegulatoryFlags::DFS));
+        assert!(
+            RegulatoryDatabase::from_text("country SE:\n\t(2400 - 2483.5 @ 40), (20), FOO\n")
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! ## Remain on Channel
+//!
+//! Remaining on another channel than the operating channel for off-channel operations, such as
+//! transmitting action frames and waiting for the responses.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::Attribute;
+use crate::channel_definition::ChannelDefinition;
+use crate::commands::Command;
+use crate::management_frame::FrameTransmission;
+use crate::wireless_interface::WirelessInterface;
+use crate::wireless_phy::WirelessPhy;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, MessageMode, Socket};
+
+/// Minimum duration in milliseconds to remain on a channel
+const MIN_REMAIN_ON_CHANNEL_DURATION: u32 = 10;
+
+fn validate_duration(phy: &WirelessPhy, duration: u32) -> Result<(), Error> {
+    match phy.max_remain_on_channel_duration() {
+        Some(max) if duration >= MIN_REMAIN_ON_CHANNEL_DURATION && duration <= max => Ok(()),
+        Some(_) => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Invalid remain on channel duration",
+        )
+        .into()),
+        None => Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Remain on channel not supported",
+        )
+        .into()),
+    }
+}
+
+impl WirelessInterface {
+    /// Remain on the channel for the duration in milliseconds, bounded by the maximum duration
+    /// of the phy
+    ///
+    /// The returned cookie identifies the remain on channel events and is used to cancel.
+    pub fn remain_on_channel(
+        &self,
+        socket: &mut Socket,
+        phy: &WirelessPhy,
+        channel: &ChannelDefinition,
+        duration: u32,
+    ) -> Result<u64, Error> {
+        validate_duration(phy, duration)?;
+        let mut msg = self.prepare_message(Command::RemainOnChannel, MessageMode::Acknowledge)?;
+        for attribute in channel.attributes() {
+            msg.append_attribute(attribute);
+        }
+        msg.append_attribute(netlink::Attribute::new(Attribute::Duration, duration));
+        socket.send_message(&msg)?;
+        let mut cookie = None;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+            for m in messages {
+                let (_, msg) = generic::Message::unpack(&m.data)?;
+                for attr in &msg.attributes {
+                    if Attribute::convert_from(attr.identifier) == Some(Attribute::Cookie) {
+                        cookie = Some(attr.as_u64()?);
+                    }
+                }
+            }
+        }
+        cookie.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing cookie").into())
+    }
+
+    /// Return to the operating channel before the remain on channel duration has passed
+    pub fn cancel_remain_on_channel(&self, socket: &mut Socket, cookie: u64) -> Result<(), Error> {
+        let mut msg =
+            self.prepare_message(Command::CancelRemainOnChannel, MessageMode::Acknowledge)?;
+        msg.append_attribute(netlink::Attribute::new(Attribute::Cookie, cookie));
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Transmit a management frame on the channel and wait there for the response, bounded by
+    /// the maximum remain on channel duration of the phy
+    ///
+    /// The interface returns to the operating channel when the wait time has passed or the wait
+    /// is cancelled with the returned cookie.
+    pub fn transmit_frame_off_channel(
+        &self,
+        socket: &mut Socket,
+        phy: &WirelessPhy,
+        channel: &ChannelDefinition,
+        wait: u32,
+        transmission: FrameTransmission,
+    ) -> Result<Option<u64>, Error> {
+        validate_duration(phy, wait)?;
+        let transmission = transmission
+            .frequency(channel.frequency)
+            .wait(wait)
+            .offchannel(true);
+        self.transmit_frame(socket, &transmission)
+    }
+}
+
+/// Remain on channel event
+pub struct RemainOnChannelEvent {
+    /// Remaining on the channel has ended, otherwise it has started
+    pub cancelled: bool,
+    /// Cookie returned when requesting to remain on the channel
+    pub cookie: u64,
+    /// Channel
+    pub channel: ChannelDefinition,
+    /// Duration in milliseconds, only reported when remaining on the channel starts
+    pub duration: Option<u32>,
+}
+
+impl RemainOnChannelEvent {
+    /// Decode a RemainOnChannel or CancelRemainOnChannel message
+    pub fn from_message(message: &generic::Message) -> Result<RemainOnChannelEvent, Error> {
+        let cancelled = match Command::convert_from(message.command) {
+            Some(Command::RemainOnChannel) => false,
+            Some(Command::CancelRemainOnChannel) => true,
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "Not a remain on channel notification",
+                )
+                .into());
+            }
+        };
+        let mut cookie = None;
+        let mut duration = None;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Cookie) => {
+                    cookie = Some(attr.as_u64()?);
+                }
+                Some(Attribute::Duration) => {
+                    duration = Some(attr.as_u32()?);
+                }
+                _ => (),
+            }
+        }
+        let cookie =
+            cookie.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing cookie"))?;
+        let channel = ChannelDefinition::from_attributes(&message.attributes)?;
+        Ok(RemainOnChannelEvent {
+            cancelled,
+            cookie,
+            channel,
+            duration,
+        })
+    }
+}
+
+impl fmt::Display for RemainOnChannelEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.cancelled {
+            write!(f, "Remain On Channel Ended {}", self.channel)?;
+        } else {
+            write!(f, "Remain On Channel {}", self.channel)?;
+        }
+        write!(f, " Cookie {}", self.cookie)?;
+        if let Some(duration) = self.duration {
+            write!(f, " Duration {} ms", duration)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn duration_limit() {
+        let attributes = [
+            netlink::Attribute::new(Attribute::Wiphy, 0u32),
+            netlink::Attribute::new(Attribute::MaxRemainOnChannelDuration, 5000u32),
+        ];
+        let phy = WirelessPhy::from_attributes(&attributes).unwrap();
+        assert_eq!(phy.max_remain_on_channel_duration(), Some(5000));
+        assert!(validate_duration(&phy, 200).is_ok());
+        assert!(validate_duration(&phy, 5).is_err());
+        assert!(validate_duration(&phy, 5001).is_err());
+        let phy = WirelessPhy::from_attributes(&attributes[..1]).unwrap();
+        assert!(validate_duration(&phy, 200).is_err());
+    }
+}
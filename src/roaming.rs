@@ -0,0 +1,704 @@
+//! ## Roaming
+//!
+//! Roam events, connection parameter updates, external authentication and BSS transition
+//! management (BTM) action frames.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::{Attribute, AuthenticationType, ExternalAuthenticationAction};
+use crate::commands::Command;
+use crate::information_element::{
+    AuthenticationKeyManagement, InformationElement, InformationElements, NeighborReport,
+};
+use crate::pack::{hardware_address_bytes, kernel_suite_selector};
+use crate::unpack::LittleUnpack;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, HardwareAddress, MessageMode, Socket};
+
+/// Roam event, sent when the device has roamed to a new access point within the same ESS
+pub struct RoamEvent {
+    /// BSSID of the new access point
+    pub bssid: HardwareAddress,
+    /// Information elements of the (re)association request
+    pub request_ies: Vec<u8>,
+    /// Information elements of the (re)association response
+    pub response_ies: Vec<u8>,
+    /// The port is authorized, the driver has completed the 4-way handshake
+    pub authorized: bool,
+    /// FILS key encryption key
+    pub fils_kek: Option<Vec<u8>>,
+    /// FILS EAP re-authentication protocol next sequence number
+    pub fils_erp_next_sequence_number: Option<u16>,
+    /// Pairwise master key derived by the driver
+    pub pmk: Option<Vec<u8>>,
+    /// Pairwise master key identifier
+    pub pmkid: Option<Vec<u8>>,
+}
+
+impl RoamEvent {
+    /// Decode a Roam message
+    pub fn from_message(message: &generic::Message) -> Result<RoamEvent, Error> {
+        let mut bssid = None;
+        let mut request_ies = vec![];
+        let mut response_ies = vec![];
+        let mut authorized = false;
+        let mut fils_kek = None;
+        let mut fils_erp_next_sequence_number = None;
+        let mut pmk = None;
+        let mut pmkid = None;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Mac) => {
+                    bssid = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::ReqIe) => {
+                    request_ies = attr.as_bytes();
+                }
+                Some(Attribute::RespIe) => {
+                    response_ies = attr.as_bytes();
+                }
+                Some(Attribute::PortAuthorized) => {
+                    authorized = true;
+                }
+                Some(Attribute::FilsKek) => {
+                    fils_kek = Some(attr.as_bytes());
+                }
+                Some(Attribute::FilsErpNextSeqNum) => {
+                    fils_erp_next_sequence_number = Some(attr.as_u16()?);
+                }
+                Some(Attribute::Pmk) => {
+                    pmk = Some(attr.as_bytes());
+                }
+                Some(Attribute::Pmkid) => {
+                    pmkid = Some(attr.as_bytes());
+                }
+                _ => (),
+            }
+        }
+        let bssid = bssid
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "Missing roam BSSID"))?;
+        Ok(RoamEvent {
+            bssid,
+            request_ies,
+            response_ies,
+            authorized,
+            fils_kek,
+            fils_erp_next_sequence_number,
+            pmk,
+            pmkid,
+        })
+    }
+
+    /// Information elements of the (re)association request
+    pub fn request_information_elements(&self) -> InformationElements<'_> {
+        InformationElements::parse(&self.request_ies)
+    }
+
+    /// Information elements of the (re)association response
+    pub fn response_information_elements(&self) -> InformationElements<'_> {
+        InformationElements::parse(&self.response_ies)
+    }
+}
+
+impl fmt::Display for RoamEvent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Roamed to {}", self.bssid)?;
+        if self.authorized {
+            write!(f, " Authorized")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parameters to update for the current connection
+///
+/// The parameters are used by the driver for the next (re)association, for example when roaming
+/// within the ESS.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectParameters {
+    information_elements: Option<Vec<u8>>,
+    authentication_type: Option<AuthenticationType>,
+    fils_erp_username: Option<Vec<u8>>,
+    fils_erp_realm: Option<Vec<u8>>,
+    fils_erp_next_sequence_number: Option<u16>,
+    fils_erp_rrk: Option<Vec<u8>>,
+}
+
+impl ConnectParameters {
+    pub fn new() -> ConnectParameters {
+        ConnectParameters::default()
+    }
+
+    /// Information elements to add to the (re)association request
+    pub fn information_elements(mut self, ies: &[u8]) -> Self {
+        self.information_elements = Some(ies.to_vec());
+        self
+    }
+
+    /// Authentication type to use
+    pub fn authentication_type(mut self, authentication_type: AuthenticationType) -> Self {
+        self.authentication_type = Some(authentication_type);
+        self
+    }
+
+    /// FILS EAP re-authentication protocol (ERP) parameters
+    pub fn fils_erp(
+        mut self,
+        username: &[u8],
+        realm: &[u8],
+        next_sequence_number: u16,
+        rrk: &[u8],
+    ) -> Self {
+        self.fils_erp_username = Some(username.to_vec());
+        self.fils_erp_realm = Some(realm.to_vec());
+        self.fils_erp_next_sequence_number = Some(next_sequence_number);
+        self.fils_erp_rrk = Some(rrk.to_vec());
+        self
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        if let Some(ref ies) = self.information_elements {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::InformationElement,
+                ies,
+            ));
+        }
+        if let Some(ref authentication_type) = self.authentication_type {
+            attributes.push(netlink::Attribute::new(
+                Attribute::AuthType,
+                u32::from(authentication_type.clone()),
+            ));
+        }
+        if let Some(ref username) = self.fils_erp_username {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::FilsErpUsername,
+                username,
+            ));
+        }
+        if let Some(ref realm) = self.fils_erp_realm {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::FilsErpRealm,
+                realm,
+            ));
+        }
+        if let Some(sequence_number) = self.fils_erp_next_sequence_number {
+            attributes.push(netlink::Attribute::new(
+                Attribute::FilsErpNextSeqNum,
+                sequence_number,
+            ));
+        }
+        if let Some(ref rrk) = self.fils_erp_rrk {
+            attributes.push(netlink::Attribute::new_bytes(Attribute::FilsErpRrk, rrk));
+        }
+        attributes
+    }
+}
+
+/// External authentication request from the driver
+///
+/// The driver asks user space to perform the authentication, for example SAE, with the access
+/// point. The result is reported back with `WirelessInterface::external_authentication`.
+pub struct ExternalAuthenticationRequest {
+    /// Start or abort the authentication
+    pub action: ExternalAuthenticationAction,
+    /// BSSID of the access point to authenticate with
+    pub bssid: HardwareAddress,
+    /// SSID of the access point
+    pub ssid: Vec<u8>,
+    /// Authentication key management suite to use
+    pub akm: Option<AuthenticationKeyManagement>,
+}
+
+impl ExternalAuthenticationRequest {
+    /// Decode an ExternalAuthentication message
+    pub fn from_message(
+        message: &generic::Message,
+    ) -> Result<ExternalAuthenticationRequest, Error> {
+        let mut action = None;
+        let mut bssid = None;
+        let mut ssid = vec![];
+        let mut akm = None;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::ExternalAuthenticationAction) => {
+                    action = ExternalAuthenticationAction::convert_from(attr.as_u32()?);
+                }
+                Some(Attribute::Bssid) => {
+                    bssid = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::Ssid) => {
+                    ssid = attr.as_bytes();
+                }
+                Some(Attribute::AkmSuites) => {
+                    akm = Some(AuthenticationKeyManagement::from(kernel_suite_selector(
+                        attr.as_u32()?,
+                    )));
+                }
+                _ => (),
+            }
+        }
+        match (action, bssid) {
+            (Some(action), Some(bssid)) => Ok(ExternalAuthenticationRequest {
+                action,
+                bssid,
+                ssid,
+                akm,
+            }),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Invalid external authentication request",
+            )
+            .into()),
+        }
+    }
+}
+
+impl WirelessInterface {
+    /// Update the connection parameters used for the next (re)association
+    pub fn update_connect_parameters(
+        &self,
+        socket: &mut Socket,
+        parameters: &ConnectParameters,
+    ) -> Result<(), Error> {
+        let mut msg =
+            self.prepare_message(Command::UpdateConnectParams, MessageMode::Acknowledge)?;
+        for attribute in parameters.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Report the result of an external authentication to the driver
+    ///
+    /// The status is a 802.11 status code, zero for success. The PMKID is given when a PMKSA
+    /// was derived during the authentication.
+    pub fn external_authentication(
+        &self,
+        socket: &mut Socket,
+        request: &ExternalAuthenticationRequest,
+        status: u16,
+        pmkid: Option<&[u8]>,
+    ) -> Result<(), Error> {
+        let mut msg =
+            self.prepare_message(Command::ExternalAuthentication, MessageMode::Acknowledge)?;
+        msg.append_attribute(netlink::Attribute::new(Attribute::Bssid, request.bssid));
+        msg.append_attribute(netlink::Attribute::new_bytes(
+            Attribute::Ssid,
+            &request.ssid,
+        ));
+        msg.append_attribute(netlink::Attribute::new(Attribute::StatusCode, status));
+        if let Some(pmkid) = pmkid {
+            msg.append_attribute(netlink::Attribute::new_bytes(Attribute::Pmkid, pmkid));
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Wireless network management (WNM) action category
+const WNM_CATEGORY: u8 = 10;
+const BSS_TRANSITION_QUERY: u8 = 6;
+const BSS_TRANSITION_REQUEST: u8 = 7;
+const BSS_TRANSITION_RESPONSE: u8 = 8;
+const BSS_TERMINATION_DURATION_SUBELEMENT: u8 = 4;
+
+fn invalid_action(message: &str) -> Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string()).into()
+}
+
+fn check_action(data: &[u8], action: u8, length: usize) -> Result<(), Error> {
+    if data.len() < length || data[0] != WNM_CATEGORY || data[1] != action {
+        return Err(invalid_action("Invalid BSS transition management frame"));
+    }
+    Ok(())
+}
+
+fn parse_candidates(data: &[u8]) -> Vec<NeighborReport> {
+    let mut candidates = vec![];
+    for element in InformationElements::parse(data).elements {
+        if let Some(id) = element.ie_id() {
+            if let Ok(InformationElement::NeighborReport(report)) =
+                InformationElement::from(id, element.data)
+            {
+                candidates.push(report);
+            }
+        }
+    }
+    candidates
+}
+
+bitflags! {
+    /// BSS transition management request mode
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct BssTransitionRequestMode: u8 {
+        /// The request contains a list of preferred candidates
+        const PREFERRED_CANDIDATE_LIST_INCLUDED = 0x01;
+        /// Access points not in the candidate list are not preferred
+        const ABRIDGED = 0x02;
+        /// The station will be disassociated when the disassociation timer expires
+        const DISASSOCIATION_IMMINENT = 0x04;
+        /// The BSS will be terminated, the termination duration is included
+        const BSS_TERMINATION_INCLUDED = 0x08;
+        /// The station will be disassociated from the ESS
+        const ESS_DISASSOCIATION_IMMINENT = 0x10;
+    }
+}
+
+/// BSS transition management status code
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BssTransitionStatus {
+    Accept,
+    RejectUnspecified,
+    RejectInsufficientBeacon,
+    RejectInsufficientCapacity,
+    RejectBssTerminationUndesired,
+    RejectBssTerminationDelayRequest,
+    RejectCandidateListProvided,
+    RejectNoSuitableCandidates,
+    RejectLeavingEss,
+    Reserved(u8),
+}
+
+impl From<u8> for BssTransitionStatus {
+    fn from(value: u8) -> Self {
+        use self::BssTransitionStatus::*;
+        match value {
+            0 => Accept,
+            1 => RejectUnspecified,
+            2 => RejectInsufficientBeacon,
+            3 => RejectInsufficientCapacity,
+            4 => RejectBssTerminationUndesired,
+            5 => RejectBssTerminationDelayRequest,
+            6 => RejectCandidateListProvided,
+            7 => RejectNoSuitableCandidates,
+            8 => RejectLeavingEss,
+            v => Reserved(v),
+        }
+    }
+}
+
+impl From<BssTransitionStatus> for u8 {
+    fn from(value: BssTransitionStatus) -> Self {
+        use self::BssTransitionStatus::*;
+        match value {
+            Accept => 0,
+            RejectUnspecified => 1,
+            RejectInsufficientBeacon => 2,
+            RejectInsufficientCapacity => 3,
+            RejectBssTerminationUndesired => 4,
+            RejectBssTerminationDelayRequest => 5,
+            RejectCandidateListProvided => 6,
+            RejectNoSuitableCandidates => 7,
+            RejectLeavingEss => 8,
+            Reserved(v) => v,
+        }
+    }
+}
+
+/// BSS transition management query, sent by a station to ask for transition candidates
+#[derive(Clone, Debug, PartialEq)]
+pub struct BssTransitionManagementQuery {
+    pub dialog_token: u8,
+    /// Reason for the query
+    pub reason: u8,
+    /// Candidates known to the station
+    pub candidates: Vec<NeighborReport>,
+}
+
+impl BssTransitionManagementQuery {
+    /// Parse query from action frame body, starting with the category
+    pub fn parse(data: &[u8]) -> Result<BssTransitionManagementQuery, Error> {
+        check_action(data, BSS_TRANSITION_QUERY, 4)?;
+        Ok(BssTransitionManagementQuery {
+            dialog_token: data[2],
+            reason: data[3],
+            candidates: parse_candidates(&data[4..]),
+        })
+    }
+
+    /// Pack query into action frame body, starting with the category
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![
+            WNM_CATEGORY,
+            BSS_TRANSITION_QUERY,
+            self.dialog_token,
+            self.reason,
+        ];
+        for candidate in &self.candidates {
+            data.extend(candidate.pack());
+        }
+        data
+    }
+}
+
+/// BSS transition management request, sent by an access point to steer a station
+#[derive(Clone, Debug, PartialEq)]
+pub struct BssTransitionManagementRequest {
+    pub dialog_token: u8,
+    pub mode: BssTransitionRequestMode,
+    /// Number of beacon intervals until disassociation
+    pub disassociation_timer: u16,
+    /// Number of beacon intervals the candidate list is valid
+    pub validity_interval: u8,
+    /// TSF of the BSS termination and duration in minutes
+    pub bss_termination: Option<(u64, u16)>,
+    /// URL with information about the ESS disassociation
+    pub session_information_url: Option<String>,
+    /// Candidates for the transition
+    pub candidates: Vec<NeighborReport>,
+}
+
+impl BssTransitionManagementRequest {
+    /// Parse request from action frame body, starting with the category
+    pub fn parse(data: &[u8]) -> Result<BssTransitionManagementRequest, Error> {
+        check_action(data, BSS_TRANSITION_REQUEST, 7)?;
+        let mode = BssTransitionRequestMode::from_bits_retain(data[3]);
+        let mut offset = 7;
+        let bss_termination = if mode.contains(BssTransitionRequestMode::BSS_TERMINATION_INCLUDED) {
+            if data.len() < offset + 12 || data[offset] != BSS_TERMINATION_DURATION_SUBELEMENT {
+                return Err(invalid_action("Invalid BSS termination duration"));
+            }
+            let tsf = u64::unpack_unchecked(&data[offset + 2..]);
+            let duration = u16::unpack_unchecked(&data[offset + 10..]);
+            offset += 12;
+            Some((tsf, duration))
+        } else {
+            None
+        };
+        let session_information_url =
+            if mode.contains(BssTransitionRequestMode::ESS_DISASSOCIATION_IMMINENT) {
+                let length = *data
+                    .get(offset)
+                    .ok_or_else(|| invalid_action("Missing session information URL"))?
+                    as usize;
+                let url = data
+                    .get(offset + 1..offset + 1 + length)
+                    .ok_or_else(|| invalid_action("Invalid session information URL"))?;
+                offset += 1 + length;
+                Some(String::from_utf8_lossy(url).into_owned())
+            } else {
+                None
+            };
+        Ok(BssTransitionManagementRequest {
+            dialog_token: data[2],
+            mode,
+            disassociation_timer: u16::unpack_unchecked(&data[4..]),
+            validity_interval: data[6],
+            bss_termination,
+            session_information_url,
+            candidates: parse_candidates(&data[offset..]),
+        })
+    }
+
+    /// Pack request into action frame body, starting with the category
+    ///
+    /// The session information URL can be at most 255 bytes long.
+    pub fn pack(&self) -> Result<Vec<u8>, Error> {
+        let mut mode = self.mode;
+        mode.set(
+            BssTransitionRequestMode::PREFERRED_CANDIDATE_LIST_INCLUDED,
+            !self.candidates.is_empty(),
+        );
+        mode.set(
+            BssTransitionRequestMode::BSS_TERMINATION_INCLUDED,
+            self.bss_termination.is_some(),
+        );
+        mode.set(
+            BssTransitionRequestMode::ESS_DISASSOCIATION_IMMINENT,
+            self.session_information_url.is_some(),
+        );
+        let mut data = vec![
+            WNM_CATEGORY,
+            BSS_TRANSITION_REQUEST,
+            self.dialog_token,
+            mode.bits(),
+        ];
+        data.extend_from_slice(&self.disassociation_timer.to_le_bytes());
+        data.push(self.validity_interval);
+        if let Some((tsf, duration)) = self.bss_termination {
+            data.push(BSS_TERMINATION_DURATION_SUBELEMENT);
+            data.push(10);
+            data.extend_from_slice(&tsf.to_le_bytes());
+            data.extend_from_slice(&duration.to_le_bytes());
+        }
+        if let Some(ref url) = self.session_information_url {
+            if url.len() > usize::from(u8::MAX) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Session information URL too long",
+                )
+                .into());
+            }
+            data.push(url.len() as u8);
+            data.extend_from_slice(url.as_bytes());
+        }
+        for candidate in &self.candidates {
+            data.extend(candidate.pack());
+        }
+        Ok(data)
+    }
+}
+
+/// BSS transition management response, sent by a station as answer to a request
+#[derive(Clone, Debug, PartialEq)]
+pub struct BssTransitionManagementResponse {
+    pub dialog_token: u8,
+    pub status: BssTransitionStatus,
+    /// Number of minutes the station asks the access point to delay BSS termination
+    pub termination_delay: u8,
+    /// BSSID of the access point the station transitions to, when accepted
+    pub target_bssid: Option<HardwareAddress>,
+    /// Candidates preferred by the station
+    pub candidates: Vec<NeighborReport>,
+}
+
+impl BssTransitionManagementResponse {
+    /// Parse response from action frame body, starting with the category
+    pub fn parse(data: &[u8]) -> Result<BssTransitionManagementResponse, Error> {
+        check_action(data, BSS_TRANSITION_RESPONSE, 5)?;
+        let status = BssTransitionStatus::from(data[3]);
+        let (target_bssid, offset) = if status == BssTransitionStatus::Accept {
+            if data.len() < 11 {
+                return Err(invalid_action("Missing target BSSID"));
+            }
+            (Some(HardwareAddress::unpack_unchecked(&data[5..])), 11)
+        } else {
+            (None, 5)
+        };
+        Ok(BssTransitionManagementResponse {
+            dialog_token: data[2],
+            status,
+            termination_delay: data[4],
+            target_bssid,
+            candidates: parse_candidates(&data[offset..]),
+        })
+    }
+
+    /// Pack response into action frame body, starting with the category
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = vec![
+            WNM_CATEGORY,
+            BSS_TRANSITION_RESPONSE,
+            self.dialog_token,
+            self.status.into(),
+            self.termination_delay,
+        ];
+        if let Some(bssid) = self.target_bssid {
+            data.extend(hardware_address_bytes(bssid));
+        }
+        for candidate in &self.candidates {
+            data.extend(candidate.pack());
+        }
+        data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::information_element::{BssidInformation, Reachability};
+
+    fn candidate() -> NeighborReport {
+        NeighborReport {
+            bssid: HardwareAddress::from(&[0x02, 0x11, 0x22, 0x33, 0x44, 0x55][..]),
+            bssid_information: BssidInformation::SECURITY
+                .with_reachability(Reachability::Reachable),
+            operating_class: 81,
+            channel: 6,
+            phy_type: 7,
+            preference: Some(255),
+        }
+    }
+
+    #[test]
+    fn bss_transition_request() {
+        let request = BssTransitionManagementRequest {
+            dialog_token: 3,
+            mode: BssTransitionRequestMode::DISASSOCIATION_IMMINENT,
+            disassociation_timer: 100,
+            validity_interval: 10,
+            bss_termination: None,
+            session_information_url: Some("http://a".to_string()),
+            candidates: vec![candidate()],
+        };
+        let data = request.pack().unwrap();
+        let parsed = BssTransitionManagementRequest::parse(&data).unwrap();
+        assert!(parsed
+            .mode
+            .contains(BssTransitionRequestMode::PREFERRED_CANDIDATE_LIST_INCLUDED));
+        assert_eq!(parsed.disassociation_timer, 100);
+        assert_eq!(parsed.session_information_url, Some("http://a".to_string()));
+        assert_eq!(parsed.candidates, vec![candidate()]);
+        let url = format!("http://{}", "a".repeat(248));
+        let request = BssTransitionManagementRequest {
+            session_information_url: Some(url.clone()),
+            ..request
+        };
+        let data = request.pack().unwrap();
+        let parsed = BssTransitionManagementRequest::parse(&data).unwrap();
+        assert_eq!(parsed.session_information_url, Some(url));
+        let request = BssTransitionManagementRequest {
+            session_information_url: Some(format!("http://{}", "a".repeat(249))),
+            ..request
+        };
+        assert!(request.pack().is_err());
+    }
+
+    #[test]
+    fn bss_transition_response() {
+        let data = [10, 8, 3, 0, 0, 0x02, 0x11, 0x22, 0x33, 0x44, 0x55];
+        let response = BssTransitionManagementResponse::parse(&data).unwrap();
+        assert_eq!(response.status, BssTransitionStatus::Accept);
+        assert_eq!(response.target_bssid, Some(candidate().bssid));
+        assert_eq!(response.pack(), data.to_vec());
+        let data = [10, 8, 3, 7, 0];
+        let response = BssTransitionManagementResponse::parse(&data).unwrap();
+        assert_eq!(
+            response.status,
+            BssTransitionStatus::RejectNoSuitableCandidates
+        );
+        assert_eq!(response.target_bssid, None);
+    }
+
+    #[test]
+    fn external_authentication_request() {
+        let mut message = generic::Message::new(
+            0u16,
+            Command::ExternalAuthentication,
+            MessageMode::Acknowledge,
+        );
+        message.append_attribute(netlink::Attribute::new(
+            Attribute::ExternalAuthenticationAction,
+            0u32,
+        ));
+        message.append_attribute(netlink::Attribute::new(Attribute::Bssid, candidate().bssid));
+        message.append_attribute(netlink::Attribute::new(
+            Attribute::AkmSuites,
+            0x000f_ac08u32,
+        ));
+        let request = ExternalAuthenticationRequest::from_message(&message).unwrap();
+        assert_eq!(request.action, ExternalAuthenticationAction::Start);
+        assert_eq!(
+            request.akm,
+            Some(AuthenticationKeyManagement::SimultaneousAuthenticationOfEquals)
+        );
+    }
+}
@@ -0,0 +1,748 @@
+//! ## Station Management
+//!
+//! Adding, modifying and removing stations of access point interfaces, for access points with
+//! station management (SME) in user space.
+//!
+
+use std::fmt;
+use std::io;
+
+use crate::attributes::{
+    Attribute, MeshPowerMode, PeerLinkState, StationInformationAttributes, StationWmeAttribute,
+};
+use crate::commands::Command;
+use crate::information_element::SupportedRates;
+use crate::pack::nested_attribute;
+use crate::wireless_interface::WirelessInterface;
+use netlink_rust as netlink;
+use netlink_rust::generic;
+use netlink_rust::{ConvertFrom, Error, HardwareAddress, MessageMode, Socket};
+
+bitflags! {
+    /// Station flags
+    #[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+    pub struct StationFlags: u32 {
+        /// Station is authorized to send and receive data frames
+        const AUTHORIZED = 1 << 1;
+        /// Station is capable of receiving frames with short preamble
+        const SHORT_PREAMBLE = 1 << 2;
+        /// Station is WME/QoS capable
+        const WME = 1 << 3;
+        /// Station uses management frame protection
+        const MFP = 1 << 4;
+        /// Station is authenticated
+        const AUTHENTICATED = 1 << 5;
+        /// Station is a TDLS peer
+        const TDLS_PEER = 1 << 6;
+        /// Station is associated
+        const ASSOCIATED = 1 << 7;
+    }
+}
+
+/// Update of station flags, only the flags in the mask are changed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StationFlagUpdate {
+    /// Flags to change
+    pub mask: StationFlags,
+    /// Flag values
+    pub set: StationFlags,
+}
+
+impl StationFlagUpdate {
+    /// Set the given flags, leaving other flags unchanged
+    pub fn set(flags: StationFlags) -> StationFlagUpdate {
+        StationFlagUpdate {
+            mask: flags,
+            set: flags,
+        }
+    }
+
+    /// Clear the given flags, leaving other flags unchanged
+    pub fn clear(flags: StationFlags) -> StationFlagUpdate {
+        StationFlagUpdate {
+            mask: flags,
+            set: StationFlags::empty(),
+        }
+    }
+
+    /// Unpack station flag update
+    pub fn unpack(data: &[u8]) -> Result<StationFlagUpdate, Error> {
+        if data.len() >= 8 {
+            let mask = u32::from_ne_bytes([data[0], data[1], data[2], data[3]]);
+            let set = u32::from_ne_bytes([data[4], data[5], data[6], data[7]]);
+            return Ok(StationFlagUpdate {
+                mask: StationFlags::from_bits_truncate(mask),
+                set: StationFlags::from_bits_truncate(set),
+            });
+        }
+        Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid station flag update").into())
+    }
+
+    /// Pack station flag update
+    pub fn pack(&self) -> Vec<u8> {
+        let mut data = self.mask.bits().to_ne_bytes().to_vec();
+        data.extend_from_slice(&self.set.bits().to_ne_bytes());
+        data
+    }
+}
+
+/// Frame sent to a station when removing it, with the reason code
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StationRemoval {
+    /// Send a deauthentication frame
+    Deauthenticate(u16),
+    /// Send a disassociation frame
+    Disassociate(u16),
+}
+
+/// Station parameters for adding or modifying a station
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StationParameters {
+    supported_rates: Option<Vec<u8>>,
+    listen_interval: Option<u16>,
+    association_id: Option<u16>,
+    ht_capability: Option<Vec<u8>>,
+    vht_capability: Option<Vec<u8>>,
+    he_capability: Option<Vec<u8>>,
+    eht_capability: Option<Vec<u8>>,
+    flags: Option<StationFlagUpdate>,
+    uapsd_queues: Option<u8>,
+    max_service_period: Option<u8>,
+    vlan_id: Option<u16>,
+}
+
+impl StationParameters {
+    pub fn new() -> StationParameters {
+        StationParameters::default()
+    }
+
+    /// Rates supported by the station
+    pub fn supported_rates(mut self, rates: &SupportedRates) -> Self {
+        self.supported_rates = Some(rates.rates.clone());
+        self
+    }
+
+    /// Listen interval of the station in beacon intervals
+    pub fn listen_interval(mut self, interval: u16) -> Self {
+        self.listen_interval = Some(interval);
+        self
+    }
+
+    /// Association identifier (AID), 1 to 2007
+    pub fn association_id(mut self, aid: u16) -> Self {
+        self.association_id = Some(aid);
+        self
+    }
+
+    /// High throughput (HT) capabilities element payload of the station
+    pub fn ht_capability(mut self, capability: &[u8]) -> Self {
+        self.ht_capability = Some(capability.to_vec());
+        self
+    }
+
+    /// Very high throughput (VHT) capabilities element payload of the station
+    pub fn vht_capability(mut self, capability: &[u8]) -> Self {
+        self.vht_capability = Some(capability.to_vec());
+        self
+    }
+
+    /// High efficiency (HE) capabilities element payload of the station
+    pub fn he_capability(mut self, capability: &[u8]) -> Self {
+        self.he_capability = Some(capability.to_vec());
+        self
+    }
+
+    /// Extremely high throughput (EHT) capabilities element payload of the station
+    pub fn eht_capability(mut self, capability: &[u8]) -> Self {
+        self.eht_capability = Some(capability.to_vec());
+        self
+    }
+
+    /// Station flags to change
+    pub fn flags(mut self, flags: StationFlagUpdate) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    /// QoS information, the U-APSD enabled access categories and the maximum service period
+    pub fn qos_info(mut self, uapsd_queues: u8, max_service_period: u8) -> Self {
+        self.uapsd_queues = Some(uapsd_queues);
+        self.max_service_period = Some(max_service_period);
+        self
+    }
+
+    /// VLAN identifier to assign the station to
+    pub fn vlan_id(mut self, vlan_id: u16) -> Self {
+        self.vlan_id = Some(vlan_id);
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(aid) = self.association_id {
+            if aid == 0 || aid > 2007 {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Invalid association identifier",
+                )
+                .into());
+            }
+        }
+        if let Some(ref rates) = self.supported_rates {
+            if rates.is_empty() {
+                return Err(
+                    io::Error::new(io::ErrorKind::InvalidInput, "No supported rates").into(),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn attributes(&self) -> Vec<netlink::Attribute> {
+        let mut attributes = vec![];
+        if let Some(ref rates) = self.supported_rates {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::StaSupportedRates,
+                rates,
+            ));
+        }
+        if let Some(interval) = self.listen_interval {
+            attributes.push(netlink::Attribute::new(
+                Attribute::StaListenInterval,
+                interval,
+            ));
+        }
+        if let Some(aid) = self.association_id {
+            attributes.push(netlink::Attribute::new(Attribute::StaAid, aid));
+        }
+        if let Some(ref capability) = self.ht_capability {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::HtCapability,
+                capability,
+            ));
+        }
+        if let Some(ref capability) = self.vht_capability {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::VhtCapability,
+                capability,
+            ));
+        }
+        if let Some(ref capability) = self.he_capability {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::HighEfficiencyCapability,
+                capability,
+            ));
+        }
+        if let Some(ref capability) = self.eht_capability {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::EhtCapability,
+                capability,
+            ));
+        }
+        if let Some(ref flags) = self.flags {
+            attributes.push(netlink::Attribute::new_bytes(
+                Attribute::StaFlags2,
+                &flags.pack(),
+            ));
+        }
+        if self.uapsd_queues.is_some() || self.max_service_period.is_some() {
+            let mut wme = vec![];
+            if let Some(queues) = self.uapsd_queues {
+                wme.push(netlink::Attribute::new(
+                    StationWmeAttribute::UapsdQueues,
+                    queues,
+                ));
+            }
+            if let Some(max_service_period) = self.max_service_period {
+                wme.push(netlink::Attribute::new(
+                    StationWmeAttribute::MaxServicePeriod,
+                    max_service_period,
+                ));
+            }
+            attributes.push(nested_attribute(Attribute::StaWme, &wme));
+        }
+        if let Some(vlan_id) = self.vlan_id {
+            attributes.push(netlink::Attribute::new(Attribute::VlanId, vlan_id));
+        }
+        attributes
+    }
+}
+
+impl WirelessInterface {
+    fn station_request(
+        &self,
+        socket: &mut Socket,
+        command: Command,
+        station: HardwareAddress,
+        parameters: &StationParameters,
+    ) -> Result<(), Error> {
+        parameters.validate()?;
+        let mut msg = self.prepare_message(command, MessageMode::Acknowledge)?;
+        msg.append_attribute(netlink::Attribute::new(Attribute::Mac, station));
+        for attribute in parameters.attributes() {
+            msg.append_attribute(attribute);
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Add a station, supported rates, listen interval and association identifier are required
+    pub fn new_station(
+        &self,
+        socket: &mut Socket,
+        station: HardwareAddress,
+        parameters: &StationParameters,
+    ) -> Result<(), Error> {
+        if parameters.supported_rates.is_none()
+            || parameters.listen_interval.is_none()
+            || parameters.association_id.is_none()
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Missing supported rates, listen interval or association identifier",
+            )
+            .into());
+        }
+        self.station_request(socket, Command::NewStation, station, parameters)
+    }
+
+    /// Modify the parameters of a station
+    pub fn set_station(
+        &self,
+        socket: &mut Socket,
+        station: HardwareAddress,
+        parameters: &StationParameters,
+    ) -> Result<(), Error> {
+        self.station_request(socket, Command::SetStation, station, parameters)
+    }
+
+    /// Remove a station, all stations are removed if no station is given
+    pub fn del_station(
+        &self,
+        socket: &mut Socket,
+        station: Option<HardwareAddress>,
+        removal: Option<StationRemoval>,
+    ) -> Result<(), Error> {
+        let mut msg = self.prepare_message(Command::DelStation, MessageMode::Acknowledge)?;
+        if let Some(station) = station {
+            msg.append_attribute(netlink::Attribute::new(Attribute::Mac, station));
+        }
+        if let Some(removal) = removal {
+            let (subtype, reason_code) = match removal {
+                // Management frame subtypes
+                StationRemoval::Deauthenticate(reason_code) => (12u8, reason_code),
+                StationRemoval::Disassociate(reason_code) => (10u8, reason_code),
+            };
+            msg.append_attribute(netlink::Attribute::new(Attribute::MgmtSubtype, subtype));
+            msg.append_attribute(netlink::Attribute::new(Attribute::ReasonCode, reason_code));
+        }
+        socket.send_message(&msg)?;
+        loop {
+            let messages = socket.receive_messages()?;
+            if messages.is_empty() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+// Signal strength per antenna chain, nested with the chain index as identifier
+fn chain_signal(attr: &netlink::Attribute) -> Result<Vec<i8>, Error> {
+    let (_, chains) = netlink::Attribute::unpack_all(&attr.as_bytes());
+    chains.iter().map(|chain| chain.as_i8()).collect()
+}
+
+/// Station information, including the mesh peer link information of mesh stations
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct StationInformation {
+    /// Station address
+    pub address: Option<HardwareAddress>,
+    /// Time since the last activity in milliseconds
+    pub inactive_time: Option<u32>,
+    /// Time since the station connected in seconds
+    pub connected_time: Option<u32>,
+    /// Received bytes
+    pub received_bytes: Option<u64>,
+    /// Transmitted bytes
+    pub transmitted_bytes: Option<u64>,
+    /// Received packets
+    pub received_packets: Option<u32>,
+    /// Transmitted packets
+    pub transmitted_packets: Option<u32>,
+    /// Signal strength of the last received frame in dBm
+    pub signal: Option<i8>,
+    /// Average signal strength in dBm
+    pub signal_average: Option<i8>,
+    /// Signal strength of the last received frame per antenna chain in dBm
+    pub chain_signal: Vec<i8>,
+    /// Average signal strength per antenna chain in dBm
+    pub chain_signal_average: Vec<i8>,
+    /// Transmit retries
+    pub transmit_retries: Option<u32>,
+    /// Failed transmissions
+    pub transmit_failures: Option<u32>,
+    /// Dropped received packets
+    pub receive_dropped: Option<u64>,
+    /// Beacons lost
+    pub beacon_loss: Option<u32>,
+    /// Received beacons
+    pub received_beacons: Option<u64>,
+    /// Average signal strength of received beacons in dBm
+    pub beacon_signal_average: Option<i8>,
+    /// Timing offset of the mesh station
+    pub timing_offset: Option<i64>,
+    /// Expected throughput in kbps
+    pub expected_throughput: Option<u32>,
+    /// Station flags
+    pub flags: Option<StationFlags>,
+    /// Mesh local link identifier
+    pub local_link_id: Option<u16>,
+    /// Mesh peer link identifier
+    pub peer_link_id: Option<u16>,
+    /// Mesh peer link state
+    pub peer_link_state: Option<PeerLinkState>,
+    /// Mesh power mode towards the peer
+    pub local_power_mode: Option<MeshPowerMode>,
+    /// Mesh power mode of the peer
+    pub peer_power_mode: Option<MeshPowerMode>,
+    /// Mesh power mode of the peer towards non-peers
+    pub non_peer_power_mode: Option<MeshPowerMode>,
+    /// The mesh station is connected to a mesh gate
+    pub connected_to_gate: Option<bool>,
+    /// The mesh station is connected to an authentication server
+    pub connected_to_authentication_server: Option<bool>,
+    /// Mesh airtime link metric
+    pub airtime_link_metric: Option<u32>,
+}
+
+impl StationInformation {
+    /// Decode station information from a NewStation message
+    pub fn from_message(message: &generic::Message) -> Result<StationInformation, Error> {
+        let mut address = None;
+        let mut information = None;
+        for attr in &message.attributes {
+            match Attribute::convert_from(attr.identifier) {
+                Some(Attribute::Mac) => {
+                    address = Some(attr.as_hardware_address()?);
+                }
+                Some(Attribute::StaInfo) => {
+                    let (_, attributes) = netlink::Attribute::unpack_all(&attr.as_bytes());
+                    information = Some(StationInformation::from_attributes(&attributes)?);
+                }
+                _ => (),
+            }
+        }
+        let mut information = information.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "Missing station information")
+        })?;
+        information.address = address;
+        Ok(information)
+    }
+
+    /// Decode station information from the station information attributes
+    pub fn from_attributes(attributes: &[netlink::Attribute]) -> Result<StationInformation, Error> {
+        let mut info = StationInformation::default();
+        for attr in attributes {
+            match StationInformationAttributes::convert_from(attr.identifier) {
+                Some(StationInformationAttributes::InactiveTime) => {
+                    info.inactive_time = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::ConnectedTime) => {
+                    info.connected_time = Some(attr.as_u32()?);
+                }
+                // The 64 bit counter takes precedence
+                Some(StationInformationAttributes::ReceivedBytes)
+                    if info.received_bytes.is_none() =>
+                {
+                    info.received_bytes = Some(u64::from(attr.as_u32()?));
+                }
+                // The 64 bit counter takes precedence
+                Some(StationInformationAttributes::TransmittedBytes)
+                    if info.transmitted_bytes.is_none() =>
+                {
+                    info.transmitted_bytes = Some(u64::from(attr.as_u32()?));
+                }
+                Some(StationInformationAttributes::ReceivedBytes64) => {
+                    info.received_bytes = Some(attr.as_u64()?);
+                }
+                Some(StationInformationAttributes::TransmittedBytes64) => {
+                    info.transmitted_bytes = Some(attr.as_u64()?);
+                }
+                Some(StationInformationAttributes::ReceivedPackets) => {
+                    info.received_packets = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::TransmittedPackets) => {
+                    info.transmitted_packets = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::Signal) => {
+                    info.signal = Some(attr.as_i8()?);
+                }
+                Some(StationInformationAttributes::SignalAverage) => {
+                    info.signal_average = Some(attr.as_i8()?);
+                }
+                Some(StationInformationAttributes::ChainSignal) => {
+                    info.chain_signal = chain_signal(attr)?;
+                }
+                Some(StationInformationAttributes::ChainSignalAverage) => {
+                    info.chain_signal_average = chain_signal(attr)?;
+                }
+                Some(StationInformationAttributes::TransmitRetries) => {
+                    info.transmit_retries = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::TransmitFailures) => {
+                    info.transmit_failures = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::ReceiveDropped) => {
+                    info.receive_dropped = Some(attr.as_u64()?);
+                }
+                Some(StationInformationAttributes::BeaconLoss) => {
+                    info.beacon_loss = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::ReceivedBeacons) => {
+                    info.received_beacons = Some(attr.as_u64()?);
+                }
+                Some(StationInformationAttributes::BeaconsSignalAverage) => {
+                    info.beacon_signal_average = Some(attr.as_i8()?);
+                }
+                Some(StationInformationAttributes::TimingOffset) => {
+                    info.timing_offset = Some(attr.as_i64()?);
+                }
+                Some(StationInformationAttributes::ExpectedThroughput) => {
+                    info.expected_throughput = Some(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::StationFlags) => {
+                    let update = StationFlagUpdate::unpack(&attr.as_bytes())?;
+                    info.flags = Some(update.set & update.mask);
+                }
+                Some(StationInformationAttributes::MeshLinkLinkId) => {
+                    info.local_link_id = Some(attr.as_u16()?);
+                }
+                Some(StationInformationAttributes::MeshPeerLinkId) => {
+                    info.peer_link_id = Some(attr.as_u16()?);
+                }
+                Some(StationInformationAttributes::MeshPeerLinkState) => {
+                    info.peer_link_state = PeerLinkState::convert_from(attr.as_u8()?);
+                }
+                Some(StationInformationAttributes::LocalPowerMode) => {
+                    info.local_power_mode = MeshPowerMode::convert_from(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::PeerPowerMode) => {
+                    info.peer_power_mode = MeshPowerMode::convert_from(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::NonPeerPowerMode) => {
+                    info.non_peer_power_mode = MeshPowerMode::convert_from(attr.as_u32()?);
+                }
+                Some(StationInformationAttributes::ConnectedToGate) => {
+                    info.connected_to_gate = Some(attr.as_u8()? != 0);
+                }
+                Some(StationInformationAttributes::ConnectedToAs) => {
+                    info.connected_to_authentication_server = Some(attr.as_u8()? != 0);
+                }
+                Some(StationInformationAttributes::AirtimeLinkMetric) => {
+                    info.airtime_link_metric = Some(attr.as_u32()?);
+                }
+                _ => (),
+            }
+        }
+        Ok(info)
+    }
+}
+
+impl fmt::Display for StationInformation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Station")?;
+        if let Some(address) = self.address {
+            write!(f, " {}", address)?;
+        }
+        if let Some(signal) = self.signal {
+            write!(f, " Signal {} dBm", signal)?;
+        }
+        if let Some(time) = self.inactive_time {
+            write!(f, " Inactive {} ms", time)?;
+        }
+        if let Some(signal) = self.signal_average {
+            write!(f, " Signal Average {} dBm", signal)?;
+        }
+        if !self.chain_signal.is_empty() {
+            write!(f, " Chain Signal {:?} dBm", self.chain_signal)?;
+        }
+        if !self.chain_signal_average.is_empty() {
+            write!(
+                f,
+                " Chain Signal Average {:?} dBm",
+                self.chain_signal_average
+            )?;
+        }
+        if let Some(time) = self.connected_time {
+            write!(f, " Connected {} s", time)?;
+        }
+        if let (Some(received), Some(transmitted)) = (self.received_bytes, self.transmitted_bytes) {
+            write!(f, " RX {} TX {} bytes", received, transmitted)?;
+        }
+        if let (Some(received), Some(transmitted)) =
+            (self.received_packets, self.transmitted_packets)
+        {
+            write!(f, " RX {} TX {} packets", received, transmitted)?;
+        }
+        if let Some(retries) = self.transmit_retries {
+            write!(f, " TX Retries {}", retries)?;
+        }
+        if let Some(failures) = self.transmit_failures {
+            write!(f, " TX Failures {}", failures)?;
+        }
+        if let Some(dropped) = self.receive_dropped {
+            write!(f, " RX Dropped {}", dropped)?;
+        }
+        if let Some(beacons) = self.received_beacons {
+            write!(f, " Beacons {}", beacons)?;
+        }
+        if let Some(loss) = self.beacon_loss {
+            write!(f, " Beacon Loss {}", loss)?;
+        }
+        if let Some(signal) = self.beacon_signal_average {
+            write!(f, " Beacon Signal Average {} dBm", signal)?;
+        }
+        if let Some(offset) = self.timing_offset {
+            write!(f, " Timing Offset {}", offset)?;
+        }
+        if let Some(throughput) = self.expected_throughput {
+            write!(f, " Expected Throughput {} kbps", throughput)?;
+        }
+        if let Some(flags) = self.flags {
+            write!(f, " Flags {:?}", flags)?;
+        }
+        if let Some(ref state) = self.peer_link_state {
+            write!(f, " Peer Link {}", state)?;
+        }
+        if let (Some(local), Some(peer)) = (self.local_link_id, self.peer_link_id) {
+            write!(f, " Link Id {}/{}", local, peer)?;
+        }
+        if let Some(ref mode) = self.local_power_mode {
+            write!(f, " Local Power Mode {}", mode)?;
+        }
+        if let Some(ref mode) = self.peer_power_mode {
+            write!(f, " Peer Power Mode {}", mode)?;
+        }
+        if let Some(ref mode) = self.non_peer_power_mode {
+            write!(f, " Non-Peer Power Mode {}", mode)?;
+        }
+        if self.connected_to_gate == Some(true) {
+            write!(f, " Gate")?;
+        }
+        if self.connected_to_authentication_server == Some(true) {
+            write!(f, " AS")?;
+        }
+        if let Some(metric) = self.airtime_link_metric {
+            write!(f, " Metric {}", metric)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flag_update() {
+        let update = StationFlagUpdate::set(StationFlags::AUTHORIZED | StationFlags::WME);
+        let data = update.pack();
+        assert_eq!(data.len(), 8);
+        assert_eq!(StationFlagUpdate::unpack(&data).unwrap(), update);
+        let update = StationFlagUpdate::clear(StationFlags::AUTHORIZED);
+        assert_eq!(update.set, StationFlags::empty());
+    }
+
+    #[test]
+    fn station_parameters() {
+        let parameters = StationParameters::new().association_id(1).qos_info(0x0f, 0);
+        assert!(parameters.validate().is_ok());
+        let attributes = parameters.attributes();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(
+            Attribute::convert_from(attributes[1].identifier),
+            Some(Attribute::StaWme)
+        );
+        assert!(StationParameters::new()
+            .association_id(2008)
+            .validate()
+            .is_err());
+    }
+
+    #[test]
+    fn station_information() {
+        let attributes = [
+            netlink::Attribute::new(StationInformationAttributes::ReceivedBytes, 10u32),
+            netlink::Attribute::new(StationInformationAttributes::ReceivedBytes64, 1u64 << 33),
+            netlink::Attribute::new(StationInformationAttributes::Signal, -40i8),
+            netlink::Attribute::new(StationInformationAttributes::MeshLinkLinkId, 0x1234u16),
+            netlink::Attribute::new(StationInformationAttributes::MeshPeerLinkId, 0x5678u16),
+            netlink::Attribute::new(StationInformationAttributes::MeshPeerLinkState, 4u8),
+            netlink::Attribute::new(StationInformationAttributes::LocalPowerMode, 1u32),
+            netlink::Attribute::new(StationInformationAttributes::PeerPowerMode, 2u32),
+            netlink::Attribute::new(StationInformationAttributes::NonPeerPowerMode, 3u32),
+            netlink::Attribute::new(StationInformationAttributes::ConnectedToGate, 1u8),
+            netlink::Attribute::new(StationInformationAttributes::ConnectedToAs, 0u8),
+            netlink::Attribute::new(StationInformationAttributes::AirtimeLinkMetric, 340u32),
+        ];
+        let info = StationInformation::from_attributes(&attributes).unwrap();
+        assert_eq!(info.received_bytes, Some(1 << 33));
+        assert_eq!(info.signal, Some(-40));
+        assert_eq!(info.local_link_id, Some(0x1234));
+        assert_eq!(info.peer_link_id, Some(0x5678));
+        assert_eq!(info.peer_link_state, Some(PeerLinkState::Established));
+        assert_eq!(info.local_power_mode, Some(MeshPowerMode::Active));
+        assert_eq!(info.peer_power_mode, Some(MeshPowerMode::LightSleep));
+        assert_eq!(info.non_peer_power_mode, Some(MeshPowerMode::DeepSleep));
+        assert_eq!(info.connected_to_gate, Some(true));
+        assert_eq!(info.connected_to_authentication_server, Some(false));
+        assert_eq!(info.airtime_link_metric, Some(340));
+
+        let chains = [
+            netlink::Attribute::new(1u16, -41i8),
+            netlink::Attribute::new(2u16, -45i8),
+        ];
+        let attributes = [
+            nested_attribute(StationInformationAttributes::ChainSignal, &chains),
+            nested_attribute(
+                StationInformationAttributes::ChainSignalAverage,
+                &chains[..1],
+            ),
+            netlink::Attribute::new(StationInformationAttributes::TransmitRetries, 7u32),
+            netlink::Attribute::new(StationInformationAttributes::TransmitFailures, 2u32),
+            netlink::Attribute::new(StationInformationAttributes::ReceiveDropped, 3u64),
+            netlink::Attribute::new(StationInformationAttributes::BeaconLoss, 1u32),
+            netlink::Attribute::new(StationInformationAttributes::ReceivedBeacons, 600u64),
+            netlink::Attribute::new(StationInformationAttributes::BeaconsSignalAverage, -50i8),
+            netlink::Attribute::new(StationInformationAttributes::TimingOffset, -12i64),
+            netlink::Attribute::new(StationInformationAttributes::ExpectedThroughput, 65000u32),
+        ];
+        let info = StationInformation::from_attributes(&attributes).unwrap();
+        assert_eq!(info.chain_signal, vec![-41, -45]);
+        assert_eq!(info.chain_signal_average, vec![-41]);
+        assert_eq!(info.transmit_retries, Some(7));
+        assert_eq!(info.transmit_failures, Some(2));
+        assert_eq!(info.receive_dropped, Some(3));
+        assert_eq!(info.beacon_loss, Some(1));
+        assert_eq!(info.received_beacons, Some(600));
+        assert_eq!(info.beacon_signal_average, Some(-50));
+        assert_eq!(info.timing_offset, Some(-12));
+        assert_eq!(info.expected_throughput, Some(65000));
+        let text = format!("{}", info);
+        assert!(text.contains("Chain Signal [-41, -45] dBm"));
+        assert!(text.contains("TX Retries 7"));
+        assert!(text.contains("Expected Throughput 65000 kbps"));
+
+        let malformed = [netlink::Attribute::new(
+            StationInformationAttributes::MeshPeerLinkId,
+            1u32,
+        )];
+        assert!(StationInformation::from_attributes(&malformed).is_err());
+    }
+}
@@ -1,6 +1,9 @@
 use crate::attributes;
+use crate::channel_definition::ChannelDefinition;
 use crate::commands::Command;
-use crate::regulatory::RegulatoryInformation;
+use crate::regulatory::{self, RegulatoryHint, RegulatoryInformation};
+use crate::station::StationInformation;
+use crate::wireless_phy::WirelessPhy;
 use netlink_rust::generic;
 use netlink_rust::{Attribute, ConvertFrom, Error, HardwareAddress, MessageMode, Socket};
 use std::fmt;
@@ -34,6 +37,7 @@ pub struct WirelessInterface {
     pub tx_power_level: u32,
     pub ssid: Option<String>,
     pub channel_width: Option<u32>,
+    pub channel: Option<ChannelDefinition>,
     wireless_device_id: WirelessDeviceId,
 }
 
@@ -56,6 +60,9 @@ impl fmt::Display for WirelessInterface {
         if let Some(ref cw) = self.channel_width {
             write!(f, " Width: {}", cw)?;
         };
+        if let Some(ref channel) = self.channel {
+            write!(f, " Channel: {}", channel)?;
+        };
         Ok(())
     }
 }
@@ -75,6 +82,7 @@ impl WirelessInterface {
         let mut ssid = None;
         let mut channel_width = None;
         let mut wdev_id = WirelessDeviceId::None;
+        let channel = ChannelDefinition::from_attributes(&message.attributes).ok();
         for attr in message.attributes {
             let identifier = attributes::Attribute::convert_from(attr.identifier);
             if let Some(identifier) = identifier {
@@ -176,6 +184,7 @@ impl WirelessInterface {
                 tx_power_level,
                 ssid,
                 channel_width,
+                channel,
                 wireless_device_id: wdev_id,
             })
         } else {
@@ -310,11 +319,12 @@ impl WirelessInterface {
         Ok(())
     }
 
-    pub fn get_regulatory(&self, socket: &mut Socket) -> Result<(), Error> {
+    /// Get the global regulatory domain and the regulatory domains of the wiphys with a self
+    /// managed or driver provided regulatory domain
+    pub fn get_regulatory(&self, socket: &mut Socket) -> Result<Vec<RegulatoryInformation>, Error> {
         let msg = generic::Message::new(self.family.id, Command::GetRegulatory, MessageMode::Dump);
-        /* let msg = self.prepare_message(Command::GetRegulatory,
-        MessageMode::Dump)?; */
         socket.send_message(&msg)?;
+        let mut domains = vec![];
         loop {
             let messages = socket.receive_messages()?;
             if messages.is_empty() {
@@ -323,169 +333,64 @@ impl WirelessInterface {
             for m in messages {
                 let (_, msg) = generic::Message::unpack(&m.data)?;
                 if msg.command == Command::GetRegulatory {
-                    let info = RegulatoryInformation::from_message(&msg)?;
-                    println!("{}", info);
-                } else {
-                    println!("{:?}", Command::from(msg.command));
+                    domains.push(RegulatoryInformation::from_message(&msg)?);
                 }
             }
         }
-        Ok(())
+        Ok(domains)
     }
 
-    pub fn set_regulatory(&self, socket: &mut Socket, alpha2: &str) -> Result<(), Error> {
-        {
-            assert!(alpha2.len() == 2);
-            let mut msg =
-                self.prepare_message(Command::RequestSetRegulatory, MessageMode::Acknowledge)?;
-            let attr = Attribute::new_string_with_nul(attributes::Attribute::RegAlpha2, alpha2);
-            msg.append_attribute(attr);
-            socket.send_message(&msg)?;
-        }
-        loop {
-            let messages = socket.receive_messages()?;
-            if messages.is_empty() {
-                break;
-            }
-            for m in messages {
-                let (_, msg) = generic::Message::unpack(&m.data)?;
-                let cmd = Command::from(msg.command);
-                println!("{:?}", cmd);
-            }
-        }
-        Ok(())
+    /// Request the kernel to apply the regulatory hint
+    pub fn set_regulatory(&self, socket: &mut Socket, hint: &RegulatoryHint) -> Result<(), Error> {
+        regulatory::set_regulatory(socket, self.family.id, hint)
     }
 
+    /// Set the channel to a 20 MHz channel without high throughput
     pub fn set_channel(&self, socket: &mut Socket, frequency: u32) -> Result<(), Error> {
-        {
-            let mut msg = self.prepare_message(Command::SetChannel, MessageMode::Acknowledge)?;
-            let attr = Attribute::new(attributes::Attribute::WiphyFreq, frequency);
-            msg.append_attribute(attr);
-            socket.send_message(&msg)?;
+        self.set_channel_definition(socket, &ChannelDefinition::new(frequency))
+    }
+
+    /// Set the channel, the channel definition is validated before the request is sent
+    pub fn set_channel_definition(
+        &self,
+        socket: &mut Socket,
+        channel: &ChannelDefinition,
+    ) -> Result<(), Error> {
+        channel.validate()?;
+        let mut msg = self.prepare_message(Command::SetChannel, MessageMode::Acknowledge)?;
+        for attribute in channel.attributes() {
+            msg.append_attribute(attribute);
         }
+        socket.send_message(&msg)?;
         loop {
             let messages = socket.receive_messages()?;
             if messages.is_empty() {
                 break;
             }
-            for m in messages {
-                let (_, msg) = generic::Message::unpack(&m.data)?;
-                let cmd = Command::from(msg.command);
-                println!("{:?}", cmd);
-            }
         }
         Ok(())
     }
 
-    pub fn get_station(&self, socket: &mut Socket) -> Result<(), Error> {
+    /// Get information about the stations of the interface
+    pub fn get_station(&self, socket: &mut Socket) -> Result<Vec<StationInformation>, Error> {
         {
             let msg = self.prepare_message(Command::GetStation, MessageMode::Dump)?;
             socket.send_message(&msg)?;
         }
+        let mut stations = vec![];
         loop {
             let messages = socket.receive_messages()?;
             if messages.is_empty() {
                 break;
             }
-
             for m in messages {
                 let (_, msg) = generic::Message::unpack(&m.data)?;
-                match Command::from(msg.command) {
-                    Command::NewStation => {
-                        for ref attr in &msg.attributes {
-                            let attr_id = attributes::Attribute::from(attr.identifier);
-                            match attr_id {
-                                attributes::Attribute::Ifindex
-                                | attributes::Attribute::Generation
-                                | attributes::Attribute::Mac => {}
-                                attributes::Attribute::StaInfo => {
-                                    println!("Station");
-                                    let (_, attrs) = Attribute::unpack_all(&attr.as_bytes());
-                                    for attr in attrs {
-                                        let attr_id =
-                                            attributes::StationInformationAttributes::from(
-                                                attr.identifier,
-                                            );
-                                        match attr_id {
-                                            attributes::StationInformationAttributes::InactiveTime => {
-                                                println!("Inactive time: {} ms", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ReceivedBytes => {
-                                                println!("Received bytes: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::TransmittedBytes => {
-                                                println!("Transmitted bytes: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::Signal => {
-                                                println!("Signal: {} dBm", attr.as_i8().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ReceivedPackets => {
-                                                println!("Received Packets: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::TransmittedPackets => {
-                                                println!("Transmitted Packets: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::TransmitRetries => {
-                                                println!("Transmit Retries: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::TransmitFailures => {
-                                                println!("Transmit Failures: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::SignalAverage => {
-                                                println!("Signal Average: {} dBm", attr.as_i8().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ConnectedTime => {
-                                                println!("Connected time: {} ms", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::BeaconLoss => {
-                                                println!("Beacon Loss: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::TimingOffset => {
-                                                println!("Timing Offset: {} ms", attr.as_i64().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ReceivedBytes64 => {
-                                                println!("Received bytes: {}", attr.as_u64().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::TransmittedBytes64 => {
-                                                println!("Transmitted bytes: {}", attr.as_u64().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ChainSignal => {
-                                                println!("Chain Signal: {} dBm", attr.as_i8().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ChainSignalAverage => {
-                                                println!("Chain Signal Average: {} dBm", attr.as_i8().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ExpectedThroughput => {
-                                                println!("Expected Throughput: {} kbps", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ReceiveDropped => {
-                                                println!("Dropped receive packets: {}", attr.as_u32().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::ReceivedBeacons => {
-                                                println!("Received Beacons: {}", attr.as_u64().unwrap());
-                                            }
-                                            attributes::StationInformationAttributes::BeaconsSignalAverage => {
-                                                println!("Beacon Signal Average: {}", attr.as_i8().unwrap());
-                                            }
-                                            _ => {
-                                                println!("** Attribute: {:?}, Len: {}", attr_id, attr.len());
-                                            }
-                                        }
-                                    }
-                                }
-                                _ => {
-                                    println!("Attribute: {:?}, Len: {}", attr_id, attr.len());
-                                }
-                            }
-                        }
-                    }
-                    cmd => {
-                        println!("Command {:?}", cmd);
-                    }
+                if Command::convert_from(msg.command) == Some(Command::NewStation) {
+                    stations.push(StationInformation::from_message(&msg)?);
                 }
             }
         }
-        Ok(())
+        Ok(stations)
     }
 }
 
@@ -514,3 +419,55 @@ pub fn get_wireless_interfaces(
     }
     Ok(devices)
 }
+
+/// Create a wireless interface of the given type on the phy
+pub fn new_wireless_interface(
+    socket: &mut Socket,
+    family: &generic::Family,
+    phy: &WirelessPhy,
+    name: &str,
+    interface_type: attributes::InterfaceType,
+) -> Result<WirelessInterface, Error> {
+    if !phy.supports_interface_type(interface_type.clone()) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "Interface type not supported by phy",
+        )
+        .into());
+    }
+    {
+        let mut tx_msg =
+            generic::Message::new(family.id, Command::NewInterface, MessageMode::Acknowledge);
+        tx_msg.append_attribute(Attribute::new(
+            attributes::Attribute::Wiphy,
+            phy.identifier(),
+        ));
+        tx_msg.append_attribute(Attribute::new_string_with_nul(
+            attributes::Attribute::Ifname,
+            name,
+        ));
+        tx_msg.append_attribute(Attribute::new(
+            attributes::Attribute::Iftype,
+            u32::from(interface_type),
+        ));
+        socket.send_message(&tx_msg)?;
+    }
+    let mut device = None;
+    loop {
+        let messages = socket.receive_messages()?;
+        if messages.is_empty() {
+            break;
+        }
+        for m in messages {
+            if m.header.identifier == family.id {
+                let (_, gmsg) = generic::Message::unpack(&m.data)?;
+                if let Ok(wi) = WirelessInterface::from_message(gmsg, family.clone()) {
+                    device = Some(wi);
+                }
+            }
+        }
+    }
+    device.ok_or_else(|| {
+        io::Error::new(io::ErrorKind::NotFound, "Wireless Interface Not Created").into()
+    })
+}
@@ -1,6 +1,8 @@
 use super::join_to_string;
 use crate::attributes::{self, Attribute, InterfaceType};
+use crate::channel_definition::{ChannelDefinition, PhyChannel};
 use crate::commands::Command;
+use crate::dfs::{self, DfsChannel};
 use crate::information_element::CipherSuite;
 use netlink_rust as netlink;
 use netlink_rust::generic;
@@ -23,6 +25,11 @@ pub struct WirelessPhy {
     commands: Vec<Command>,
     if_types: InterfaceTypeFlags,
     software_if_types: InterfaceTypeFlags,
+    roam_support: bool,
+    max_mac_acl_entries: Option<u32>,
+    max_remain_on_channel_duration: Option<u32>,
+    channels: Vec<PhyChannel>,
+    dfs_channels: Vec<DfsChannel>,
 }
 
 bitflags! {
@@ -150,6 +157,11 @@ impl WirelessPhy {
         let mut phy_name = String::new();
         let mut if_types = InterfaceTypeFlags::empty();
         let mut software_if_types = InterfaceTypeFlags::empty();
+        let mut roam_support = false;
+        let mut max_mac_acl_entries = None;
+        let mut max_remain_on_channel_duration = None;
+        let mut channels = vec![];
+        let mut dfs_channels = vec![];
         for attr in attributes {
             let identifier = Attribute::convert_from(attr.identifier);
             if let Some(identifier) = identifier {
@@ -163,8 +175,16 @@ impl WirelessPhy {
                         }
                     }
                     Attribute::Generation => (),
-                    Attribute::RoamSupport
-                    | Attribute::TdlsSupport
+                    Attribute::RoamSupport => {
+                        roam_support = true;
+                    }
+                    Attribute::MacAclMax => {
+                        max_mac_acl_entries = Some(attr.as_u32()?);
+                    }
+                    Attribute::MaxRemainOnChannelDuration => {
+                        max_remain_on_channel_duration = Some(attr.as_u32()?);
+                    }
+                    Attribute::TdlsSupport
                     | Attribute::OffchannelTxOk
                     | Attribute::SupportIbssRsn
                     | Attribute::ControlPortEthertype
@@ -197,10 +217,7 @@ impl WirelessPhy {
                             );
                         }
                     }
-                    Attribute::MaxScanIeLen
-                    | Attribute::MaxSchedScanIeLen
-                    | Attribute::MacAclMax
-                    | Attribute::MaxRemainOnChannelDuration => {
+                    Attribute::MaxScanIeLen | Attribute::MaxSchedScanIeLen => {
                         if attr.as_u16().is_err() {
                             println!(
                                 "[{:?}] {:?} {} Invalid type",
@@ -330,6 +347,18 @@ impl WirelessPhy {
                                         attributes::BandAttributes::Frequencies => {
                                             for freq_attrs in netlink::nested_attribute_array(&data)
                                             {
+                                                if let Ok(channel) =
+                                                    PhyChannel::from_attributes(&freq_attrs)
+                                                {
+                                                    channels.push(channel);
+                                                }
+                                                if let Ok(channel) =
+                                                    DfsChannel::from_attributes(&freq_attrs)
+                                                {
+                                                    if channel.radar {
+                                                        dfs_channels.push(channel);
+                                                    }
+                                                }
                                                 for freq_attr in freq_attrs {
                                                     if let Some(id) =
                                                         attributes::FrequencyAttribute::convert_from(
@@ -346,6 +375,7 @@ impl WirelessPhy {
                                                                 let power = f64::from(power) / 100.0;
                                                                 println!("{} {} dBm", id, power);
                                                             }
+                                                            attributes::FrequencyAttribute::Wmm => (),
                                                             _ => {
                                                                 println!("{:04x} {} {}", freq_attr.identifier, id, freq_attr.len());
                                                             }
@@ -415,6 +445,11 @@ impl WirelessPhy {
                 commands,
                 if_types,
                 software_if_types,
+                roam_support,
+                max_mac_acl_entries,
+                max_remain_on_channel_duration,
+                channels,
+                dfs_channels,
             })
         } else {
             Err(io::Error::new(io::ErrorKind::NotFound, "Wireless Phy Not Found").into())
@@ -422,6 +457,63 @@ impl WirelessPhy {
     }
 }
 
+impl WirelessPhy {
+    /// Phy identifier
+    pub fn identifier(&self) -> u32 {
+        self.identifier
+    }
+
+    /// Phy name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The device roams between access points of the same ESS on its own
+    pub fn roam_support(&self) -> bool {
+        self.roam_support
+    }
+
+    /// The interface type is supported by the phy
+    pub fn supports_interface_type(&self, interface_type: InterfaceType) -> bool {
+        self.if_types
+            .contains(InterfaceTypeFlags::from(interface_type))
+    }
+
+    /// Maximum number of entries in a MAC access control list, none if MAC ACLs are not
+    /// supported by the driver
+    pub fn max_mac_acl_entries(&self) -> Option<u32> {
+        self.max_mac_acl_entries
+    }
+
+    /// Maximum duration in milliseconds to remain on a channel, none if remaining on a channel
+    /// is not supported by the driver
+    pub fn max_remain_on_channel_duration(&self) -> Option<u32> {
+        self.max_remain_on_channel_duration
+    }
+
+    /// Channels of all bands of the phy
+    pub fn channels(&self) -> &[PhyChannel] {
+        &self.channels
+    }
+
+    /// Check that the channel is consistent and supported by the phy, with all its subchannels
+    /// enabled and allowing the channel width
+    pub fn check_channel(&self, channel: &ChannelDefinition) -> Result<(), Error> {
+        channel.check_channels(&self.channels)
+    }
+
+    /// Channels requiring radar detection, with their DFS state
+    pub fn dfs_channels(&self) -> &[DfsChannel] {
+        &self.dfs_channels
+    }
+
+    /// Channel availability check time in milliseconds of the channel, none if no radar
+    /// detection is required on the channel
+    pub fn cac_time(&self, channel: &ChannelDefinition) -> Option<u32> {
+        dfs::cac_time(&self.dfs_channels, channel)
+    }
+}
+
 impl PartialEq for WirelessPhy {
     fn eq(&self, other: &WirelessPhy) -> bool {
         self.identifier == other.identifier
@@ -434,8 +526,13 @@ impl fmt::Display for WirelessPhy {
         write!(
             f,
             "Wireless Phy\n  Identifier: {}\n  Name: {}\n  Commands: {}\n\
-             Interfaces: {:?}\n  Software Interfaces: {:?}",
-            self.identifier, self.name, commands, self.if_types, self.software_if_types
+             Interfaces: {:?}\n  Software Interfaces: {:?}\n  Roam Support: {}",
+            self.identifier,
+            self.name,
+            commands,
+            self.if_types,
+            self.software_if_types,
+            self.roam_support
         )
     }
 }